@@ -0,0 +1,250 @@
+//! Image attachments, copied into an `attachments` directory alongside the database
+//! and referenced from `entries.image_paths` by their path relative to it.
+//!
+//! Files are never removed together with their entries, since trashed entries can be
+//! restored; `cl attachments gc` deletes the ones no entry references any more.
+use crate::database::is_in_memory;
+use anyhow::{Context, Result, anyhow};
+use chrono::Local;
+use rand::distr::SampleString;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A file in the attachments directory, or a reference to one that is missing
+#[derive(Debug, PartialEq)]
+pub struct AttachmentFile {
+    /// Path relative to the database directory, as stored in entries
+    pub path: String,
+    /// Size in bytes, None when an entry references a file that doesn't exist
+    pub size: Option<u64>,
+    /// Entries referencing the file; empty for orphaned files
+    pub entry_ids: Vec<i64>,
+}
+
+impl AttachmentFile {
+    pub fn is_orphaned(&self) -> bool {
+        self.entry_ids.is_empty()
+    }
+}
+
+/// File extensions accepted as images
+const IMAGE_EXTENSIONS: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "webp", "heic", "bmp", "tif", "tiff",
+];
+
+/// Get the attachments directory path (alongside the database)
+pub fn get_attachments_directory(db_path: &Path) -> Result<PathBuf> {
+    if is_in_memory(db_path) {
+        return Err(anyhow!("An in-memory database cannot store attachments"));
+    }
+    let db_dir = db_path
+        .parent()
+        .context("Failed to get database directory")?;
+    Ok(db_dir.join("attachments"))
+}
+
+/// Check that `path` is an existing file with an image extension
+pub fn check_image(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(anyhow!("File not found: {}", path.display()));
+    }
+    if !is_image(path) {
+        return Err(anyhow!(
+            "Not an image: {} (supported: {})",
+            path.display(),
+            IMAGE_EXTENSIONS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Unique name for an attached copy of `source`, keeping its file name
+/// Format: YYYYMMDD_HHMMSS_random6_<name>
+fn attachment_filename(source: &Path) -> String {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let suffix = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 6);
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().replace(' ', "_"))
+        .unwrap_or_default();
+
+    format!("{}_{}_{}", timestamp, suffix.to_lowercase(), name)
+}
+
+/// Copy an image into the attachments directory and return its relative path
+/// (`attachments/<file>`), as stored in the database
+pub fn store_attachment(db_path: &Path, source: &Path) -> Result<String> {
+    check_image(source)?;
+
+    let dir = get_attachments_directory(db_path)?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create attachments directory at {:?}", dir))?;
+
+    let filename = attachment_filename(source);
+    fs::copy(source, dir.join(&filename))
+        .with_context(|| format!("Failed to copy {}", source.display()))?;
+
+    Ok(format!("attachments/{}", filename))
+}
+
+/// Open a file with the platform's default application, without waiting for it
+pub fn open_externally(path: &Path) -> Result<()> {
+    let mut command = match std::env::consts::OS {
+        "macos" => Command::new("open"),
+        "windows" => {
+            // `start` is a cmd builtin; its first quoted argument is the window title
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        _ => Command::new("xdg-open"),
+    };
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    let status = command
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch '{}'", program))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "'{}' could not open {} ({})",
+            program,
+            path.display(),
+            status
+        ));
+    }
+    Ok(())
+}
+
+/// Every file in the attachments directory and every attachment referenced by an
+/// entry (`references` pairs an entry ID with a stored path), sorted by path
+pub fn list_attachment_files(
+    db_path: &Path,
+    references: &[(i64, String)],
+) -> Result<Vec<AttachmentFile>> {
+    let mut files: BTreeMap<String, AttachmentFile> = BTreeMap::new();
+
+    let dir = get_attachments_directory(db_path)?;
+    if dir.is_dir() {
+        for dir_entry in fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read attachments directory at {:?}", dir))?
+        {
+            let dir_entry = dir_entry?;
+            let metadata = dir_entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let path = format!("attachments/{}", dir_entry.file_name().to_string_lossy());
+            files.insert(
+                path.clone(),
+                AttachmentFile {
+                    path,
+                    size: Some(metadata.len()),
+                    entry_ids: Vec::new(),
+                },
+            );
+        }
+    }
+
+    for (id, path) in references {
+        // Audio recorded before attachments existed lives elsewhere (e.g. `audio/`)
+        if !path.starts_with("attachments/") {
+            continue;
+        }
+        files
+            .entry(path.clone())
+            .or_insert_with(|| AttachmentFile {
+                path: path.clone(),
+                size: None,
+                entry_ids: Vec::new(),
+            })
+            .entry_ids
+            .push(*id);
+    }
+
+    Ok(files.into_values().collect())
+}
+
+/// Delete the orphaned files among `files`, returning how many bytes were freed
+pub fn remove_orphaned(db_path: &Path, files: &[AttachmentFile]) -> Result<u64> {
+    let db_dir = db_path
+        .parent()
+        .context("Failed to get database directory")?;
+    let mut freed = 0;
+    for file in files.iter().filter(|file| file.is_orphaned()) {
+        let full_path = db_dir.join(&file.path);
+        fs::remove_file(&full_path)
+            .with_context(|| format!("Failed to remove {}", full_path.display()))?;
+        freed += file.size.unwrap_or(0);
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_is_image() {
+        assert!(is_image(Path::new("bridge.png")));
+        assert!(is_image(Path::new("/tmp/Away Team.JPG")));
+        assert!(!is_image(Path::new("log.txt")));
+        assert!(!is_image(Path::new("png")));
+    }
+
+    #[test]
+    fn test_list_and_remove_orphaned_attachments() {
+        let dir = TempDir::new("attachments");
+        let db_path = dir.join("journal.db");
+        fs::create_dir_all(dir.join("attachments")).unwrap();
+        fs::write(dir.join("attachments/kept.png"), b"kept").unwrap();
+        fs::write(dir.join("attachments/orphan.png"), b"orphan").unwrap();
+
+        let references = vec![
+            (1, "attachments/kept.png".to_string()),
+            (2, "attachments/missing.png".to_string()),
+            (3, "audio/old.wav".to_string()),
+        ];
+        let files = list_attachment_files(&db_path, &references).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                AttachmentFile {
+                    path: "attachments/kept.png".to_string(),
+                    size: Some(4),
+                    entry_ids: vec![1],
+                },
+                AttachmentFile {
+                    path: "attachments/missing.png".to_string(),
+                    size: None,
+                    entry_ids: vec![2],
+                },
+                AttachmentFile {
+                    path: "attachments/orphan.png".to_string(),
+                    size: Some(6),
+                    entry_ids: Vec::new(),
+                },
+            ]
+        );
+
+        assert_eq!(remove_orphaned(&db_path, &files).unwrap(), 6);
+        assert!(dir.join("attachments/kept.png").exists());
+        assert!(!dir.join("attachments/orphan.png").exists());
+    }
+
+    #[test]
+    fn test_attachment_filename_keeps_name() {
+        let name = attachment_filename(Path::new("/photos/away team.png"));
+        assert!(name.ends_with("_away_team.png"));
+        assert_eq!(name.len(), "YYYYMMDD_HHMMSS_abcdef_away_team.png".len());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::database::is_in_memory;
 use anyhow::{Context, Result};
 use chrono::Local;
 use rand::distr::SampleString;
@@ -6,6 +7,11 @@ use std::path::{Path, PathBuf};
 
 /// Get the audio directory path (alongside the database)
 pub fn get_audio_directory(db_path: &Path) -> Result<PathBuf> {
+    if is_in_memory(db_path) {
+        return Err(anyhow::anyhow!(
+            "An in-memory database cannot store recordings"
+        ));
+    }
     let db_dir = db_path
         .parent()
         .context("Failed to get database directory")?;
@@ -1,10 +1,11 @@
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 
 /// Parse relative date strings into NaiveDate
 ///
 /// A relative date string can be:
 /// - "today", "yesterday", "tomorrow"
 /// - "last week", "last month", "last year"
+/// - "last monday" ... "last sunday" -> the most recent such day before today
 /// - "next week", "next month", "next year"
 /// - "X days ago", "X days from now"
 /// - "X weeks ago", "X weeks from now"
@@ -49,6 +50,14 @@ pub fn parse_relative_date(input: &str) -> Result<NaiveDate, String> {
             NaiveDate::from_ymd_opt(year, today.month(), day).ok_or("Invalid date".to_string())
         }
 
+        s if s.starts_with("last ") => {
+            let weekday = s["last ".len()..]
+                .trim()
+                .parse::<Weekday>()
+                .map_err(|_| format!("Could not parse: {}", input))?;
+            Ok(last_weekday(today, weekday))
+        }
+
         // Next relative dates
         "next week" => Ok(today + Duration::weeks(1)),
         "next month" => {
@@ -123,6 +132,35 @@ pub fn parse_relative_date(input: &str) -> Result<NaiveDate, String> {
     }
 }
 
+/// Parse an age like `30d`, `2w`, `30 days` or `1 week`
+pub fn parse_age(input: &str) -> Result<Duration, String> {
+    let input = input.trim().to_lowercase();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let number = number
+        .parse::<i64>()
+        .map_err(|_| format!("Could not parse: {}", input))?;
+
+    match unit.trim() {
+        "d" | "day" | "days" => Ok(Duration::days(number)),
+        "w" | "week" | "weeks" => Ok(Duration::weeks(number)),
+        _ => Err(format!(
+            "Could not parse: {} (use days or weeks, e.g. '30d')",
+            input
+        )),
+    }
+}
+
+/// The most recent `weekday` strictly before `today` (a week ago if today is that day)
+fn last_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_back = (today.weekday().num_days_from_monday() as i64
+        - weekday.num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today - Duration::days(if days_back == 0 { 7 } else { days_back })
+}
+
 /// Helper function to get days in a month
 fn days_in_month(year: i32, month: u32) -> u32 {
     NaiveDate::from_ymd_opt(
@@ -135,3 +173,36 @@ fn days_in_month(year: i32, month: u32) -> u32 {
     .unwrap()
     .day()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_weekday() {
+        // 2025-09-10 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 9, 10).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 9, d).unwrap();
+        assert_eq!(last_weekday(today, Weekday::Mon), date(8));
+        assert_eq!(last_weekday(today, Weekday::Tue), date(9));
+        assert_eq!(last_weekday(today, Weekday::Wed), date(3));
+        assert_eq!(last_weekday(today, Weekday::Sun), date(7));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d"), Ok(Duration::days(30)));
+        assert_eq!(parse_age("2 weeks"), Ok(Duration::weeks(2)));
+        assert_eq!(parse_age("1 Day"), Ok(Duration::days(1)));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3 months").is_err());
+    }
+
+    #[test]
+    fn test_parse_last_weekday_names() {
+        assert!(parse_relative_date("last monday").is_ok());
+        assert!(parse_relative_date("Last Fri").is_ok());
+        assert!(parse_relative_date("last someday").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+/// Line-based diff used by `cl history --diff`.
+///
+/// A plain longest-common-subsequence table: entries are short enough that the
+/// quadratic cost never shows.
+#[derive(Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diff `old` against `new` line by line, removals before additions
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let (n, m) = (old.len(), new.len());
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "Warp core stable\nCoolant low\nAll hands";
+        let new = "Warp core stable\nCoolant refilled\nAll hands\nEnd of shift";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same("Warp core stable"),
+                DiffLine::Removed("Coolant low"),
+                DiffLine::Added("Coolant refilled"),
+                DiffLine::Same("All hands"),
+                DiffLine::Added("End of shift"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_empty_sides() {
+        assert_eq!(diff_lines("", "New"), vec![DiffLine::Added("New")]);
+        assert_eq!(diff_lines("Old", ""), vec![DiffLine::Removed("Old")]);
+        assert!(diff_lines("", "").is_empty());
+    }
+}
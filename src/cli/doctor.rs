@@ -0,0 +1,328 @@
+/// Module for `cl doctor`: health checks of the database, attachments and configuration,
+/// each with a suggested fix when something is wrong.
+use colored::*;
+use std::path::Path;
+
+use crate::attachments::list_attachment_files;
+use crate::cli::saved_search::SavedSearch;
+use crate::config::Config;
+use crate::database::Database;
+use crate::journal::Journal;
+
+/// Entry IDs listed in a check's details before the rest are summarized
+const MAX_LISTED_IDS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Outcome of one health check
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about it, for warnings and errors
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: Status,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Check {
+            name,
+            status,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run every check against the database at `db_path` and the loaded `config`
+pub fn run_checks(journal: &Journal, config: &Config, db_path: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    // A connection of its own, read-only: the checks never write to the journal
+    match Database::new_readonly(db_path) {
+        Ok(db) => {
+            checks.push(check_integrity(&db));
+            checks.push(check_schema(&db));
+            checks.push(check_timestamps(&db));
+        }
+        Err(e) => checks.push(Check::problem(
+            "Database",
+            Status::Error,
+            format!("cannot open {}: {}", db_path.display(), e),
+            "Check the path (`cl config set database.path`) or restore a backup with `cl restore`",
+        )),
+    }
+    checks.push(check_attachments(journal, db_path));
+    checks.push(check_config_file());
+    checks.extend(check_config(config));
+
+    checks
+}
+
+fn check_integrity(db: &Database) -> Check {
+    match db.integrity_problems() {
+        Ok(problems) if problems.is_empty() => Check::ok("Integrity", "ok"),
+        Ok(problems) => Check::problem(
+            "Integrity",
+            Status::Error,
+            problems.join("; "),
+            "Restore a recent backup with `cl restore`, or save what is readable with `cl export --format json`",
+        ),
+        Err(e) => Check::problem(
+            "Integrity",
+            Status::Error,
+            format!("integrity check failed: {}", e),
+            "Restore a recent backup with `cl restore`",
+        ),
+    }
+}
+
+fn check_schema(db: &Database) -> Check {
+    match db.missing_schema() {
+        Ok(missing) if missing.is_empty() => Check::ok("Schema", "up to date"),
+        Ok(missing) => Check::problem(
+            "Schema",
+            Status::Warning,
+            format!("missing {}", missing.join(", ")),
+            "Run any command without --readonly to migrate the database",
+        ),
+        Err(e) => Check::problem(
+            "Schema",
+            Status::Error,
+            e.to_string(),
+            "Restore a recent backup with `cl restore`",
+        ),
+    }
+}
+
+fn check_timestamps(db: &Database) -> Check {
+    match db.invalid_timestamp_ids() {
+        Ok(ids) if ids.is_empty() => Check::ok("Timestamps", "all entries have a valid date"),
+        Ok(ids) => Check::problem(
+            "Timestamps",
+            Status::Warning,
+            format!(
+                "{} entries with a missing or invalid timestamp ({})",
+                ids.len(),
+                list_ids(&ids)
+            ),
+            "Set their date in the frontmatter with `cl edit <id>`",
+        ),
+        Err(e) => Check::problem(
+            "Timestamps",
+            Status::Error,
+            e.to_string(),
+            "Run any command without --readonly to migrate the database",
+        ),
+    }
+}
+
+fn check_attachments(journal: &Journal, db_path: &Path) -> Check {
+    let files = match journal
+        .list_attachment_references()
+        .and_then(|references| list_attachment_files(db_path, &references))
+    {
+        Ok(files) => files,
+        Err(e) => {
+            return Check::problem(
+                "Attachments",
+                Status::Error,
+                e.to_string(),
+                "Check the permissions of the attachments directory next to the database",
+            );
+        }
+    };
+
+    let orphaned = files.iter().filter(|file| file.is_orphaned()).count();
+    let missing: Vec<_> = files.iter().filter(|file| file.size.is_none()).collect();
+    if !missing.is_empty() {
+        let paths: Vec<&str> = missing.iter().map(|file| file.path.as_str()).collect();
+        Check::problem(
+            "Attachments",
+            Status::Error,
+            format!(
+                "{} referenced files are missing ({})",
+                missing.len(),
+                paths.join(", ")
+            ),
+            "Copy the files back into the attachments directory, or attach them again with `cl attach`",
+        )
+    } else if orphaned > 0 {
+        Check::problem(
+            "Attachments",
+            Status::Warning,
+            format!("{} files no entry references", orphaned),
+            "Delete them with `cl attachments gc` (empty the trash first if entries were deleted for good)",
+        )
+    } else {
+        Check::ok("Attachments", format!("{} files, all in use", files.len()))
+    }
+}
+
+/// The config file must parse, since every command loads it
+fn check_config_file() -> Check {
+    let path = match Config::get_config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return Check::problem(
+                "Config file",
+                Status::Error,
+                e.to_string(),
+                "Set HOME (or the platform's config directory) so the config can be found",
+            );
+        }
+    };
+    if !path.exists() {
+        return Check::ok("Config file", "not created yet (defaults in use)");
+    }
+
+    match Config::load_from(&path) {
+        Ok(_) => Check::ok("Config file", path.display().to_string()),
+        Err(e) => Check::problem(
+            "Config file",
+            Status::Error,
+            format!("{}: {:#}", path.display(), e),
+            "Fix it by hand (`cl config path` shows where it is), or move it away to start over from the defaults",
+        ),
+    }
+}
+
+/// Values that parse but would fail when used
+pub fn check_config(config: &Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let editor = config.get_editor_command();
+    if which::which(&editor).is_err() {
+        checks.push(Check::problem(
+            "Editor",
+            Status::Warning,
+            format!("'{}' not found on PATH", editor),
+            "Set one with `cl config set editor.command <command>`",
+        ));
+    }
+
+    if let Some(tz) = &config.display.timezone
+        && tz.parse::<chrono_tz::Tz>().is_err()
+    {
+        checks.push(Check::problem(
+            "Timezone",
+            Status::Error,
+            format!("unknown timezone '{}'", tz),
+            "Use an IANA name: `cl config set display.timezone Europe/Rome` (or `auto`)",
+        ));
+    }
+
+    if !matches!(
+        config.display.default_sort.as_str(),
+        "timestamp" | "created"
+    ) {
+        checks.push(Check::problem(
+            "Sort order",
+            Status::Warning,
+            format!(
+                "display.default_sort is '{}' (entries are sorted by date)",
+                config.display.default_sort
+            ),
+            "`cl config set display.default_sort timestamp` (or `created`)",
+        ));
+    }
+
+    for (name, args) in &config.search {
+        if let Err(e) = SavedSearch::from_args(args) {
+            checks.push(Check::problem(
+                "Saved search",
+                Status::Warning,
+                format!("'{}': {}", name, e),
+                format!(
+                    "Fix it with `cl config set search.{} '<filters>'`, or remove it with an empty value",
+                    name
+                ),
+            ));
+        }
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::ok("Config values", "ok"));
+    }
+    checks
+}
+
+fn list_ids(ids: &[i64]) -> String {
+    let mut listed: Vec<String> = ids
+        .iter()
+        .take(MAX_LISTED_IDS)
+        .map(i64::to_string)
+        .collect();
+    if ids.len() > MAX_LISTED_IDS {
+        listed.push(format!("and {} more", ids.len() - MAX_LISTED_IDS));
+    }
+    listed.join(", ")
+}
+
+/// Print the checks, returning how many found a problem
+pub fn print_checks(checks: &[Check]) -> usize {
+    for check in checks {
+        let mark = match check.status {
+            Status::Ok => "✓".green(),
+            Status::Warning => "⚠".yellow(),
+            Status::Error => "✗".red(),
+        };
+        println!("{} {}: {}", mark, check.name.bold(), check.detail);
+        if let Some(fix) = &check.fix {
+            println!("    {} {}", "→".bright_black(), fix.bright_black());
+        }
+    }
+    checks
+        .iter()
+        .filter(|check| check.status != Status::Ok)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_config() {
+        let mut config = Config::default();
+        config.editor.command = Some("sh".to_string());
+        assert_eq!(check_config(&config)[0].status, Status::Ok);
+
+        config.display.timezone = Some("Mars/Olympus_Mons".to_string());
+        config
+            .search
+            .insert("broken".to_string(), "--since".to_string());
+        let checks = check_config(&config);
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].name, "Timezone");
+        assert_eq!(checks[0].status, Status::Error);
+        assert_eq!(checks[1].name, "Saved search");
+    }
+
+    #[test]
+    fn test_list_ids() {
+        assert_eq!(list_ids(&[1, 2]), "1, 2");
+        let ids: Vec<i64> = (1..=12).collect();
+        assert_eq!(list_ids(&ids), "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, and 2 more");
+    }
+}
@@ -194,6 +194,9 @@ pub fn get_wrap_width() -> u16 {
 
 /// Wrap text to the specified width, preserving existing line breaks.
 ///
+/// Widths are measured in terminal columns, so double-width (e.g. CJK) characters count
+/// as two, and lines without spaces are broken at Unicode line break opportunities.
+///
 /// TODO: handle ANSI escape codes properly so that they don't count towards the width.
 ///
 /// # Arguments
@@ -211,9 +214,11 @@ pub fn get_wrap_width() -> u16 {
 /// println!("{}", wrapped);
 /// ```
 pub fn wrap_text(text: &str, width: u16) -> String {
-    use textwrap::{Options, wrap};
+    use textwrap::{Options, WordSeparator, wrap};
 
     let opts = Options::new(width as usize)
+        // CJK text has no spaces; UAX #14 allows breaks between ideographs.
+        .word_separator(WordSeparator::UnicodeBreakProperties)
         // Keep long “words” (like very long URLs) from exceeding the width.
         .break_words(true);
 
@@ -228,3 +233,116 @@ pub fn wrap_text(text: &str, width: u16) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+/// Byte ranges of the non-overlapping, case-insensitive occurrences of `needle` in `haystack`.
+pub fn find_matches(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    let mut next_start = 0;
+    for (start, _) in haystack.char_indices() {
+        if start < next_start {
+            continue;
+        }
+
+        // Walk the haystack from `start`, comparing lowercased characters with the needle
+        let mut expected = needle.iter();
+        let mut end = start;
+        let mut matched = false;
+        for (offset, c) in haystack[start..].char_indices() {
+            if !c.to_lowercase().all(|lc| expected.next() == Some(&lc)) {
+                break;
+            }
+            end = start + offset + c.len_utf8();
+            if expected.len() == 0 {
+                matched = true;
+                break;
+            }
+        }
+
+        if matched {
+            matches.push((start, end));
+            next_start = end;
+        }
+    }
+    matches
+}
+
+/// A one-line excerpt of `text` around the first occurrence of `query`, with the
+/// match highlighted and about `radius` characters of context on each side.
+/// Returns `None` when `query` does not occur in `text`.
+pub fn search_snippet(text: &str, query: &str, radius: usize) -> Option<String> {
+    let (start, end) = *find_matches(text, query).first()?;
+
+    let before: String = {
+        let chars: Vec<char> = text[..start].chars().collect();
+        let skip = chars.len().saturating_sub(radius);
+        let prefix = if skip > 0 { "…" } else { "" };
+        format!("{}{}", prefix, chars[skip..].iter().collect::<String>())
+    };
+    let after: String = {
+        let rest = &text[end..];
+        let suffix = if rest.chars().count() > radius {
+            "…"
+        } else {
+            ""
+        };
+        format!(
+            "{}{}",
+            rest.chars().take(radius).collect::<String>(),
+            suffix
+        )
+    };
+
+    Some(format!(
+        "{}{}{}",
+        before.replace('\n', " "),
+        text[start..end].yellow().bold(),
+        after.replace('\n', " ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    #[test]
+    fn test_wrap_text_cjk_respects_display_width() {
+        let text = "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。\
+                    何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。";
+        let wrapped = wrap_text(text, 20);
+
+        assert!(wrapped.lines().count() > 1);
+        for line in wrapped.lines() {
+            assert!(line.width() <= 20, "line too wide: {:?}", line);
+        }
+        assert_eq!(wrapped.replace('\n', ""), text);
+    }
+
+    #[test]
+    fn test_find_matches_case_insensitive() {
+        assert_eq!(
+            find_matches("Warp core, WARP drive", "warp"),
+            vec![(0, 4), (11, 15)]
+        );
+        assert_eq!(find_matches("Größe und GRÖSSE", "größe"), vec![(0, 7)]);
+        assert!(find_matches("nothing here", "warp").is_empty());
+        assert!(find_matches("anything", "").is_empty());
+    }
+
+    #[test]
+    fn test_search_snippet_centers_on_match() {
+        colored::control::set_override(false);
+        let text = "We spent the morning calibrating sensors.\nThen the warp core failed again.";
+        assert_eq!(
+            search_snippet(text, "WARP", 10).unwrap(),
+            "… Then the warp core fail…"
+        );
+        assert_eq!(search_snippet("warp", "warp", 10).unwrap(), "warp");
+        assert_eq!(search_snippet(text, "shields", 10), None);
+    }
+}
@@ -2,7 +2,8 @@
 /// This is a very hardcoded implementation tailored for CaptainLog's needs.
 /// I don't want to introduce too much complexity here.
 ///
-/// So, for now, this just handles the `journal` and `timestamp` fields.
+/// So, for now, this just handles the `journal` and `timestamp` fields, plus an
+/// optional `tags` list.
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,6 +14,9 @@ const FRONTMATTER_DELIMITER: &str = "---";
 pub struct EntryMetadata {
     pub journal: String,
     pub timestamp: DateTime<Utc>,
+    /// Tags in addition to the `#hashtags` found in the content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 /// Parse content with YAML frontmatter
@@ -58,11 +62,13 @@ pub fn parse_frontmatter(content: &str) -> Result<(EntryMetadata, String)> {
 pub fn format_entry_with_frontmatter(
     journal: &str,
     timestamp: DateTime<Utc>,
+    tags: &[String],
     content: &str,
 ) -> Result<String> {
     let metadata = EntryMetadata {
         journal: journal.to_string(),
         timestamp,
+        tags: tags.to_vec(),
     };
 
     let yaml =
@@ -127,7 +133,7 @@ Content without closing delimiter"#;
         let timestamp = Utc.with_ymd_and_hms(2025, 10, 6, 14, 30, 0).unwrap();
         let content = "# Title\n\nContent";
 
-        let result = format_entry_with_frontmatter("Personal", timestamp, content);
+        let result = format_entry_with_frontmatter("Personal", timestamp, &[], content);
         assert!(result.is_ok());
 
         let formatted = result.unwrap();
@@ -135,5 +141,17 @@ Content without closing delimiter"#;
         assert!(formatted.contains("journal: Personal"));
         assert!(formatted.contains("timestamp:"));
         assert!(formatted.contains("# Title"));
+        assert!(!formatted.contains("tags:"));
+    }
+
+    #[test]
+    fn test_frontmatter_tags_round_trip() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 10, 6, 14, 30, 0).unwrap();
+        let tags = vec!["travel".to_string(), "risa".to_string()];
+
+        let formatted =
+            format_entry_with_frontmatter("Personal", timestamp, &tags, "Body").unwrap();
+        let (metadata, _) = parse_frontmatter(&formatted).unwrap();
+        assert_eq!(metadata.tags, tags);
     }
 }
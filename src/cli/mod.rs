@@ -1,25 +1,72 @@
 pub mod dateparser;
+pub mod diff;
+pub mod doctor;
 pub mod formatting;
 pub mod frontmatter;
+pub mod saved_search;
 pub mod stardate;
+pub mod template;
 
-use crate::cli::formatting::{get_wrap_width, wrap_text};
+use crate::cli::diff::{DiffLine, diff_lines};
+use crate::cli::formatting::{find_matches, get_wrap_width, search_snippet, wrap_text};
 use crate::cli::frontmatter::{format_entry_with_frontmatter, parse_frontmatter};
+use crate::cli::saved_search::SavedSearch;
 use crate::cli::stardate::Stardate;
-use crate::config::Config;
+use crate::config::{Config, DEFAULT_PROFILE, JOURNAL_MODES, active_profile};
+use crate::crypto::{Encryption, decrypt_text};
+use crate::database::Database;
+use crate::database::backup::{
+    create_backup, default_backup_dir, prune_backups, restore_backup, validate_backup,
+};
+use crate::database::encryption::{KEY_ENV, check_key, is_encrypted};
+use crate::database::session::{clear_session, load_session, save_session, session_dir};
+use crate::database::{encryption, keychain};
 use crate::export::{ExportFilters, Exporter};
-use crate::import::Importer;
-use crate::journal::{Entry, Journal};
+use crate::import::{DateFilter, ImportStats, Importer, detect_format};
+use crate::journal::{
+    DEFAULT_JOURNAL, Entry, EntryFilter, Journal, JournalInfo, JournalStats, STATUS_DRAFT,
+    STATUS_FINAL, SearchQuery, Streak, extract_tags,
+};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, TimeZone, Utc};
 use chrono_tz::Tz;
 use clap::Subcommand;
 use colored::*;
-use dateparser::parse_relative_date;
+use dateparser::{parse_age, parse_relative_date};
 use formatting::render_markdown;
+use signal_hook::consts::SIGINT;
+use signal_hook::iterator::Signals;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Minimum similarity (0.0 to 1.0) for an entry to be reported by `search --fuzzy`
+const FUZZY_SEARCH_THRESHOLD: f64 = 0.85;
+
+/// Maximum number of results reported by `search --fuzzy`
+const FUZZY_SEARCH_LIMIT: usize = 20;
+
+/// Characters of context shown on each side of the first match in `search` results
+const SEARCH_SNIPPET_RADIUS: usize = 40;
+
+/// Set from the global `--yes` flag: answer yes to every confirmation prompt
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Cleared by the global `--no-editor-check` flag: look the editor up on PATH before launching it
+static EDITOR_CHECK: AtomicBool = AtomicBool::new(true);
+
+/// Journal colors from the `journals` table, loaded once per run by `handle_command`
+static JOURNAL_COLORS: OnceLock<HashMap<String, Color>> = OnceLock::new();
+
+/// age identity decrypting encrypted entries (`encryption.identity`), set by `handle_command`
+static ENCRYPTION_IDENTITY: OnceLock<Option<String>> = OnceLock::new();
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -37,41 +84,285 @@ pub enum Commands {
         #[arg(long)]
         until: Option<String>,
 
+        /// Show entries edited since date (filters on last update, not the entry date)
+        #[arg(long)]
+        updated_since: Option<String>,
+
         /// Filter by journal category
         #[arg(long)]
         journal: Option<String>,
+
+        /// Show only entries tagged with this tag (e.g. `work` or `#work`)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Show only draft entries
+        #[arg(long, conflicts_with = "final_only")]
+        drafts: bool,
+
+        /// Show only final (published) entries
+        #[arg(long = "final")]
+        final_only: bool,
+
+        /// Show only starred entries
+        #[arg(long)]
+        starred: bool,
+
+        /// Apply the filters of a saved search (see `search.<name>` in the config)
+        #[arg(long)]
+        saved: Option<String>,
+
+        /// Show at most this many entries (the page size for --page)
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Page to show, starting at 1 (page size from --limit or display.entries_per_page)
+        #[arg(long, conflicts_with = "offset")]
+        page: Option<usize>,
+
+        /// Skip this many entries before listing
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Include entries of archived journals
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Print only the matching entry IDs, one per line
+        #[arg(long)]
+        ids_only: bool,
     },
 
     /// Show a specific entry by ID
     Show {
-        /// Entry ID to show
-        id: i64,
+        /// Entry ID to show (or 'last')
+        id: String,
+
+        /// Use a single thin separator and no blank lines (denser when reading many entries)
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// List the earlier versions of an entry, or diff one against the current content
+    History {
+        /// Entry ID (or 'last')
+        id: String,
+
+        /// Revision number to compare with the current content
+        #[arg(long)]
+        diff: Option<usize>,
+    },
+
+    /// Show the most recent entry
+    Last {
+        /// Only consider entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Open the entry in the editor after showing it
+        #[arg(long)]
+        edit: bool,
+
+        /// Use a single thin separator and no blank lines
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// Show today's entries in full, grouped by journal
+    Today {
+        /// Only show entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Use a single thin separator and no blank lines
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// Show entries written on today's date in previous years
+    #[command(name = "onthisday")]
+    OnThisDay {
+        /// Only show entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+    },
+
+    /// Show writing statistics
+    Stats {
+        /// Only count entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+    },
+
+    /// Show the current and longest consecutive-day writing streaks
+    Streak {
+        /// Only count entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Print a single line, e.g. for a shell prompt
+        #[arg(long)]
+        short: bool,
+    },
+
+    /// Choose an entry with a fuzzy finder (`picker.command`, fzf by default), then show it
+    Pick {
+        /// Only offer entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Edit the selected entry instead of showing it
+        #[arg(long, conflicts_with = "delete")]
+        edit: bool,
+
+        /// Delete the selected entry (asks for confirmation)
+        #[arg(long)]
+        delete: bool,
     },
 
     /// Search entries
     Search {
-        /// Search query
-        query: String,
+        /// Search query: words, "quoted phrases", AND/OR/NOT and parentheses
+        #[arg(required_unless_present = "saved")]
+        query: Option<String>,
+
+        /// Rank entries by fuzzy similarity instead of exact substring match
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Only search entries tagged with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only search entries from specific date (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Only search entries since date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only search entries until date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only search entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Run a saved search (see `search.<name>` in the config); a query given
+        /// here is combined with the saved one
+        #[arg(long)]
+        saved: Option<String>,
+
+        /// Include entries of archived journals
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Print only the matching entry IDs, one per line
+        #[arg(long)]
+        ids_only: bool,
     },
 
-    /// Delete an entry
+    /// Move an entry to the trash
     Delete {
-        /// Entry ID to delete
-        id: i64,
+        /// Entry ID to delete (or 'last')
+        id: String,
+    },
+
+    /// Reverse the last delete, edit or move
+    Undo,
+
+    /// List, restore or permanently delete trashed entries
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+
+    /// Add text to the end of an existing entry
+    Append {
+        /// Entry ID to append to (or 'last')
+        id: String,
+
+        /// Text to append; repeat for multiple paragraphs
+        #[arg(short, long = "message")]
+        messages: Vec<String>,
+
+        /// Read the text from standard input (automatic when input is piped)
+        #[arg(long, conflicts_with = "messages")]
+        stdin: bool,
+
+        /// Put a line with the current date and time before the text
+        #[arg(long)]
+        timestamp: bool,
+    },
+
+    /// Mark a draft entry as final
+    Publish {
+        /// Entry ID to publish (or 'last')
+        id: String,
+    },
+
+    /// Star an entry
+    Star {
+        /// Entry ID to star (or 'last')
+        id: String,
+    },
+
+    /// Encrypt an entry's content to `encryption.recipient` (age or GPG); the title, date
+    /// and journal stay readable, and its tags and earlier revisions are removed
+    EncryptEntry {
+        /// Entry ID to encrypt (or 'last')
+        id: String,
+    },
+
+    /// Remove the star from an entry
+    Unstar {
+        /// Entry ID to unstar (or 'last')
+        id: String,
     },
 
-    /// Move an entry to a different journal
+    /// Move an entry to a different journal, or with --to every entry matching filters
     Move {
-        /// Entry ID to move
-        id: i64,
+        /// Entry ID to move (or 'last')
+        #[arg(required_unless_present = "to", conflicts_with = "to")]
+        id: Option<String>,
         /// Target journal name
-        journal: String,
+        #[arg(
+            value_name = "JOURNAL",
+            required_unless_present = "to",
+            conflicts_with = "to"
+        )]
+        target: Option<String>,
+        /// Move the entries of this journal (same as the global --journal)
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+        /// Only move entries since date (YYYY-MM-DD)
+        #[arg(long, requires = "to")]
+        since: Option<String>,
+        /// Only move entries until date (YYYY-MM-DD)
+        #[arg(long, requires = "to")]
+        until: Option<String>,
+        /// Only move entries tagged with this tag
+        #[arg(long, requires = "to")]
+        tag: Option<String>,
+        /// Journal receiving the entries matching the filters (previews them and asks for confirmation)
+        #[arg(long)]
+        to: Option<String>,
     },
 
     /// Edit an existing entry
     Edit {
-        /// Entry ID to edit
-        id: i64,
+        /// Entry ID to edit (or 'last')
+        id: String,
+
+        /// Show the rendered entry and ask for confirmation before opening the editor
+        #[arg(long)]
+        preview: bool,
+
+        /// Mark the entry as a draft after editing (use `publish` to finalize it)
+        #[arg(long)]
+        draft: bool,
     },
 
     /// Create a new entry
@@ -82,6 +373,38 @@ pub enum Commands {
 
         /// Quick entry content (if provided, creates entry directly without opening editor)
         content: Vec<String>,
+
+        /// Entry text, without opening the editor; repeat for multiple paragraphs
+        #[arg(short, long = "message", conflicts_with = "content")]
+        messages: Vec<String>,
+
+        /// Title of an entry created without the editor (with content or --message)
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// Read the entry from standard input (automatic when input is piped); a first
+        /// `# ` line is the title, as in the editor
+        #[arg(long, conflicts_with_all = ["content", "messages"])]
+        stdin: bool,
+
+        /// Show what would be saved (title, journal, timestamp, content) without saving it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Save the entry as a draft (use `publish` to finalize it)
+        #[arg(long)]
+        draft: bool,
+
+        /// Store the content encrypted to `encryption.recipient` (age or GPG)
+        #[arg(long)]
+        encrypted: bool,
+    },
+
+    /// List all tags with their entry counts and last use
+    Tags {
+        /// Only count entries of this journal category
+        #[arg(long)]
+        journal: Option<String>,
     },
 
     /// Display calendar view of entries
@@ -90,13 +413,28 @@ pub enum Commands {
         #[arg(long)]
         year: Option<i32>,
 
-        /// Month to display (1-12, default: current month)
+        /// Month to display: 1-12 or any date, absolute or relative, inside it
+        /// (e.g. 'last month', '2025-03-01'; default: current month)
         #[arg(long)]
-        month: Option<u32>,
+        month: Option<String>,
 
         /// Filter by journal category
         #[arg(long)]
         journal: Option<String>,
+
+        /// Show a yearly heatmap of entries per day instead of a month
+        #[arg(long, conflicts_with = "month")]
+        heatmap: bool,
+
+        /// Include entries of archived journals
+        #[arg(long)]
+        include_archived: bool,
+    },
+
+    /// List journal categories (default) or manage them
+    Journals {
+        #[command(subcommand)]
+        action: Option<JournalsAction>,
     },
 
     /// Manage configuration
@@ -105,13 +443,26 @@ pub enum Commands {
         action: Option<ConfigAction>,
     },
 
+    /// List or create profiles: separate config and database pairs selected with --profile
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// Create entries from templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
     /// Export entries to various formats
     Export {
         /// Output file path
         #[arg(short, long)]
         output: Option<String>,
 
-        /// Export format (currently only json is supported)
+        /// Export format: json, markdown, org, txt (plain text), jrnl, or into the --output directory:
+        /// site (a static HTML site) or vault (one markdown file per entry, as for Obsidian)
         #[arg(short, long, default_value = "json")]
         format: String,
 
@@ -130,28 +481,158 @@ pub enum Commands {
         /// Filter by journal category
         #[arg(long)]
         journal: Option<String>,
+
+        /// Only export entries whose title or content contains this text (same as `cl search`)
+        #[arg(long, visible_alias = "query", value_name = "QUERY")]
+        from_search: Option<String>,
+
+        /// Only export entries tagged with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Include draft entries (by default only final entries are exported)
+        #[arg(long)]
+        include_drafts: bool,
+
+        /// Split the export into numbered files of at most N entries each (requires --output)
+        #[arg(long, value_name = "N", requires = "output")]
+        split_size: Option<usize>,
+
+        /// Write one file per journal (e.g. Work.md, Personal.md) into the --output directory
+        #[arg(
+            long,
+            value_name = "FIELD",
+            value_parser = ["journal"],
+            requires = "output",
+            conflicts_with = "split_size"
+        )]
+        split_by: Option<String>,
+
+        /// Encrypt the output files with age (an age1... or ssh- recipient), gpg (a key ID or
+        /// email) or a prompted passphrase ('passphrase'); attachments are not copied
+        #[arg(long, value_name = "RECIPIENT", requires = "output")]
+        encrypt_to: Option<String>,
+
+        /// Append a footer with version, date, entry count and filters (markdown, org and txt)
+        #[arg(long)]
+        with_footer: bool,
     },
 
     /// Import entries from various formats
     Import {
-        /// Path to file to import
+        /// Path to file (or directory, with --format markdown; or DayOne export zip) to import
         path: String,
 
-        /// Import format (supported formats: org, dayone)
-        #[arg(short, long, default_value = "org")]
-        format: String,
+        /// Import format (supported formats: org, dayone, diaro, enex, jrnl, journey, json, markdown, text); detected from the file when omitted
+        #[arg(short, long)]
+        format: Option<String>,
 
         /// Filter by specific date (YYYY-MM-DD) - only import entries from this date
-        #[arg(long)]
+        #[arg(long, conflicts_with_all = ["since", "until"])]
         date: Option<String>,
 
+        /// Only import entries from this date on (YYYY-MM-DD or relative, e.g. "last month")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only import entries up to this date, included
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Target journal category for imported entries
+        #[arg(long)]
+        journal: Option<String>,
+
+        /// Treat the path as a directory of markdown files (same as --format markdown)
+        #[arg(long)]
+        directory: bool,
+
+        /// Also import markdown files in subdirectories (markdown directories only)
+        #[arg(long)]
+        recursive: bool,
+
+        /// Use each imported file's name as the journal of its entries (DayOne journals, Evernote notebooks)
+        #[arg(long, conflicts_with = "journal")]
+        journal_from_file: bool,
+
+        /// Regex matching the date header lines that start entries (--format text)
+        #[arg(long)]
+        date_pattern: Option<String>,
+
+        /// chrono format of the dates matched by --date-pattern (default: YYYY-MM-DD [HH:MM])
+        #[arg(long, requires = "date_pattern")]
+        date_format: Option<String>,
+
+        /// Report how many entries would be imported, with samples, without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Snapshot the database into a timestamped backup file, pruning old backups
+    Backup {
+        /// Directory to write the backup to (default: backup.dir from the config, or backups/ next to the database)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Number of most recent backups to keep (overrides backup.keep)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        keep: Option<u64>,
+    },
+
+    /// Check the health of the database, attachments and configuration, suggesting fixes
+    Doctor,
+
+    /// Encrypt the journal database with a passphrase (requires the sqlcipher feature)
+    Encrypt,
+
+    /// Remove the encryption of the journal database (requires the sqlcipher feature)
+    Decrypt,
+
+    /// Keep the passphrase of the encrypted database for a while, so the next commands
+    /// don't ask for it (`lock` forgets it)
+    Unlock {
+        /// Minutes the journal stays unlocked (default: database.unlock_minutes)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        minutes: Option<u64>,
+    },
+
+    /// Forget the passphrase kept by `unlock`
+    Lock,
+
+    /// Replace the journal database with a backup (the current database is kept aside)
+    Restore {
+        /// Backup file to restore (e.g. written by `cl backup`)
+        backup: String,
+    },
+
+    /// Watch a directory and import new or changed files as they appear (Ctrl-C to stop)
+    Watch {
+        /// Directory to watch
+        dir: String,
+
+        /// Format of the watched files (supported formats: markdown, org, jrnl)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
         /// Target journal category for imported entries
         #[arg(long)]
         journal: Option<String>,
+
+        /// Also watch subdirectories (markdown only)
+        #[arg(long)]
+        recursive: bool,
+
+        /// Seconds between scans of the directory
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
     },
 
-    /// Record audio and create a new journal entry with transcription
+    /// Record audio and create a new journal entry with transcription, or add it to an entry
     Record {
+        /// Entry to add the recording to (or 'last'); a new entry is created when omitted
+        #[arg(conflicts_with = "journal")]
+        id: Option<String>,
+
         /// Journal category for the new entry
         #[arg(long)]
         journal: Option<String>,
@@ -165,10 +646,36 @@ pub enum Commands {
         max_duration: Option<u64>,
     },
 
+    /// Attach images to an entry (copied into the attachments directory)
+    Attach {
+        /// Entry ID to attach to (or 'last')
+        id: String,
+
+        /// Image files to attach
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// List attached files (default) or remove the ones no entry references
+    Attachments {
+        #[command(subcommand)]
+        action: Option<AttachmentsAction>,
+    },
+
+    /// Open an entry's attachments with the system's default application
+    Open {
+        /// Entry ID to open attachments from (or 'last')
+        id: String,
+
+        /// Open only the Nth attachment: images as numbered by `cl show`, then the audio
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        nth: Option<u64>,
+    },
+
     /// Play audio from an existing entry
     Play {
-        /// Entry ID to play audio from
-        id: i64,
+        /// Entry ID to play audio from (or 'last')
+        id: String,
     },
 
     /// Start the LCARS web interface (read-only)
@@ -179,6 +686,92 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command writes to the journal database (rejected in `--readonly` mode).
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Commands::Delete { .. }
+            | Commands::Move { .. }
+            | Commands::Publish { .. }
+            | Commands::Star { .. }
+            | Commands::EncryptEntry { .. }
+            | Commands::Unstar { .. }
+            | Commands::Append { .. }
+            | Commands::Edit { .. }
+            | Commands::Last { edit: true, .. }
+            | Commands::Journals {
+                action:
+                    Some(
+                        JournalsAction::Delete { .. }
+                        | JournalsAction::Set { .. }
+                        | JournalsAction::Archive { .. }
+                        | JournalsAction::Unarchive { .. },
+                    ),
+            }
+            | Commands::Pick { edit: true, .. }
+            | Commands::Pick { delete: true, .. }
+            | Commands::New { dry_run: false, .. }
+            | Commands::Import { dry_run: false, .. }
+            | Commands::Watch { .. }
+            | Commands::Restore { .. }
+            | Commands::Encrypt
+            | Commands::Decrypt
+            | Commands::Record { .. }
+            | Commands::Attach { .. }
+            | Commands::Attachments {
+                action: Some(AttachmentsAction::Gc),
+            }
+            | Commands::Undo
+            | Commands::Trash {
+                action: TrashAction::Restore { .. } | TrashAction::Empty { .. },
+            }
+            | Commands::Template {
+                action: TemplateAction::Apply { .. },
+            } => true,
+            Commands::New { dry_run: true, .. }
+            | Commands::Import { dry_run: true, .. }
+            | Commands::Template {
+                action: TemplateAction::List,
+            }
+            | Commands::Trash {
+                action: TrashAction::List,
+            }
+            | Commands::Attachments {
+                action: None | Some(AttachmentsAction::List),
+            }
+            | Commands::List { .. }
+            | Commands::Show { .. }
+            | Commands::History { .. }
+            | Commands::Last { edit: false, .. }
+            | Commands::Today { .. }
+            | Commands::OnThisDay { .. }
+            | Commands::Stats { .. }
+            | Commands::Streak { .. }
+            | Commands::Journals {
+                action: None | Some(JournalsAction::List | JournalsAction::Describe { .. }),
+            }
+            | Commands::Pick {
+                edit: false,
+                delete: false,
+                ..
+            }
+            | Commands::Search { .. }
+            | Commands::Tags { .. }
+            | Commands::Calendar { .. }
+            | Commands::Config { .. }
+            | Commands::Export { .. }
+            | Commands::Open { .. }
+            | Commands::Play { .. }
+            | Commands::Backup { .. }
+            | Commands::Doctor
+            | Commands::Unlock { .. }
+            | Commands::Lock
+            | Commands::Profile { .. }
+            | Commands::Serve { .. } => false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum ConfigAction {
     /// Show current configuration
@@ -190,8 +783,117 @@ pub enum ConfigAction {
         /// Configuration value
         value: String,
     },
+    /// Put a configuration value back to its default (removes a saved search)
+    Unset {
+        /// Configuration key (e.g., editor.command, display.timezone, search.<name>)
+        key: String,
+    },
+    /// Reset the whole configuration to the defaults (asks for confirmation)
+    Reset,
     /// Show configuration file path
     Path,
+    /// Interactively set the most common options
+    Wizard,
+    /// Open the config file in the editor, checking it before it is saved
+    Edit,
+}
+
+#[derive(Subcommand)]
+pub enum JournalsAction {
+    /// List journals with entry counts and first/last entry dates
+    List,
+    /// Show a journal's settings and entry counts
+    Describe {
+        /// Journal name
+        name: String,
+    },
+    /// Change a journal setting: description, color or template (empty value to clear)
+    Set {
+        /// Journal name
+        name: String,
+        /// Setting: description, color (e.g. 'blue', 'bright magenta') or template
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// Hide a journal's entries from list, search and calendar unless --include-archived
+    Archive {
+        /// Journal name
+        name: String,
+    },
+    /// Show an archived journal's entries by default again
+    Unarchive {
+        /// Journal name
+        name: String,
+    },
+    /// Delete a journal; one with entries needs --move-to or --purge
+    Delete {
+        /// Journal to delete
+        name: String,
+
+        /// Keep the entries by moving them to this journal
+        #[arg(long, conflicts_with = "purge")]
+        move_to: Option<String>,
+
+        /// Delete the journal's entries too (asks for confirmation)
+        #[arg(long)]
+        purge: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AttachmentsAction {
+    /// List attached files with their size and the entries using them
+    List,
+    /// Delete the attached files no entry references (asks for confirmation)
+    Gc,
+}
+
+#[derive(Subcommand)]
+pub enum TrashAction {
+    /// List the entries in the trash
+    List,
+    /// Take an entry out of the trash
+    Restore {
+        /// Entry ID to restore
+        id: i64,
+    },
+    /// Permanently delete the entries in the trash (asks for confirmation)
+    Empty {
+        /// Only delete entries trashed longer ago than this (e.g. '30d', '2w')
+        #[arg(long)]
+        older_than: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// List the profiles, marking the active one
+    List,
+    /// Create a profile with the default configuration and its own database
+    Create {
+        /// Profile name (letters, digits, '-' and '_')
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplateAction {
+    /// List the available templates
+    List,
+    /// Create an entry from a template without opening the editor
+    Apply {
+        /// Template name (file name without `.md` in the templates directory)
+        name: String,
+
+        /// Date of the entry (YYYY-MM-DD or relative, e.g. 'today')
+        #[arg(long, default_value = "today")]
+        date: String,
+
+        /// Journal category for the new entry
+        #[arg(long)]
+        journal: Option<String>,
+    },
 }
 
 pub fn handle_command(
@@ -201,13 +903,38 @@ pub fn handle_command(
     db_path: &std::path::Path,
     global_journal: Option<&str>,
 ) -> Result<()> {
+    JOURNAL_COLORS.get_or_init(|| load_journal_colors(journal));
+    ENCRYPTION_IDENTITY.get_or_init(|| config.encryption.identity.clone());
+
     match command {
         Commands::List {
             date,
             since,
             until,
+            updated_since,
             journal: list_journal,
+            tag,
+            drafts,
+            final_only,
+            starred,
+            saved,
+            limit,
+            page,
+            offset,
+            include_archived,
+            ids_only,
         } => {
+            // Flags given on the command line win over the saved ones
+            let saved = saved
+                .map(|name| SavedSearch::load(config, &name))
+                .transpose()?
+                .unwrap_or_default();
+            let saved_query = saved.query();
+            let date = date.or(saved.date);
+            let since = since.or(saved.since);
+            let until = until.or(saved.until);
+            let tag = tag.or(saved.tag);
+            let list_journal = list_journal.or(saved.journal);
             let journal_filter = list_journal.as_deref().or(global_journal);
 
             // Parse date filters using .map().transpose() pattern
@@ -226,29 +953,75 @@ pub fn handle_command(
                 .map(parse_relative_date)
                 .transpose()
                 .map_err(|e| anyhow::anyhow!("Invalid until date: {}", e))?;
+            let updated_since_filter = updated_since
+                .as_deref()
+                .map(parse_relative_date)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid updated-since date: {}", e))?;
 
-            let entries = if date_filter.is_some()
-                || since_filter.is_some()
-                || until_filter.is_some()
-                || journal_filter.is_some()
-            {
-                journal.list_entries_filtered(
-                    date_filter.as_ref(),
-                    since_filter.as_ref(),
-                    until_filter.as_ref(),
-                    journal_filter,
-                )?
+            let status_filter = if drafts {
+                Some(STATUS_DRAFT)
+            } else if final_only {
+                Some(STATUS_FINAL)
             } else {
-                journal.list_entries()?
+                None
             };
 
-            if entries.is_empty() {
+            let filter = EntryFilter {
+                date: date_filter,
+                since: since_filter,
+                until: until_filter,
+                updated_since: updated_since_filter,
+                journal: journal_filter,
+                status: status_filter,
+                search: saved_query.as_deref(),
+                tag: tag.as_deref(),
+                // Naming a journal shows it even when archived
+                exclude_archived: !include_archived && journal_filter.is_none(),
+                starred,
+            };
+
+            // The configured page size applies to the listing, but `--ids-only` output is
+            // meant for scripts and stays complete unless paging is asked for explicitly
+            let page_size = match limit {
+                Some(limit) => Some(limit),
+                None if page.is_some() || !ids_only => config.display.entries_per_page,
+                None => None,
+            };
+            if page_size == Some(0) {
+                return Err(anyhow::anyhow!("The page size must be at least 1"));
+            }
+            let offset = match (page, page_size) {
+                (Some(0), _) => return Err(anyhow::anyhow!("Pages are numbered from 1")),
+                (Some(page), Some(size)) => (page - 1) * size,
+                (Some(_), None) => {
+                    return Err(anyhow::anyhow!(
+                        "--page needs a page size: pass --limit or set display.entries_per_page"
+                    ));
+                }
+                (None, _) => offset.unwrap_or(0),
+            };
+
+            let total = journal.count_entries(&filter)?;
+            let entries = journal.list_entries_page(
+                &filter,
+                config.display.sort_column(),
+                "DESC",
+                page_size,
+                offset,
+            )?;
+
+            if ids_only {
+                print_entry_ids(&entries);
+            } else if total == 0 {
                 println!("{}", "No entries found".yellow());
-            } else {
+            } else if entries.is_empty() {
                 println!(
                     "{}",
-                    format!("Found {} entries:", entries.len()).green().bold()
+                    format!("No entries past the first {} ({} in total)", offset, total).yellow()
                 );
+            } else {
+                println!("{}", format!("Found {} entries:", total).green().bold());
                 println!();
                 for entry in entries {
                     println!(
@@ -256,50 +1029,223 @@ pub fn handle_command(
                         format_entry_summary(
                             &entry,
                             config.display.stardate_mode,
-                            config.display.timezone.as_deref()
+                            config.display.timezone.as_deref(),
+                            config.display.age_fade,
                         )
                     );
                 }
+                if let Some(size) = page_size
+                    && total > size
+                {
+                    println!();
+                    println!(
+                        "{}",
+                        format!("Page {} of {}", offset / size + 1, total.div_ceil(size))
+                            .bright_black()
+                    );
+                }
             }
         }
-        Commands::Show { id } => match journal.get_entry(id)? {
-            Some(entry) => {
-                print_entry(
-                    &entry,
-                    config.display.stardate_mode,
-                    config.display.timezone.as_deref(),
-                );
+        Commands::Show { id, compact } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            match journal.get_entry(id)? {
+                Some(entry) => {
+                    print_entry(
+                        &entry,
+                        config.display.stardate_mode,
+                        config.display.timezone.as_deref(),
+                        compact || config.display.compact,
+                    );
+                }
+                None => println!("{}", format!("Entry {} not found", id).red()),
             }
-            None => println!("{}", format!("Entry {} not found", id).red()),
-        },
-        Commands::Search { query } => {
-            let entries = journal.search_entries(&query)?;
+        }
+        Commands::Last {
+            journal: last_journal,
+            edit,
+            compact,
+        } => {
+            let journal_filter = last_journal.as_deref().or(global_journal);
+            match journal.get_last_entry(journal_filter)? {
+                Some(entry) => {
+                    print_entry(
+                        &entry,
+                        config.display.stardate_mode,
+                        config.display.timezone.as_deref(),
+                        compact || config.display.compact,
+                    );
+                    if edit {
+                        edit_entry(journal, entry.id, config)?;
+                    }
+                }
+                None => match journal_filter {
+                    Some(name) => {
+                        println!("{}", format!("No entries in journal '{}'", name).yellow())
+                    }
+                    None => println!("{}", "No entries found".yellow()),
+                },
+            }
+        }
+        Commands::Today {
+            journal: today_journal,
+            compact,
+        } => {
+            let tz = config.display.timezone.as_deref();
+            let entries = list_entries_on_local_day(
+                journal,
+                to_local_dt(&Utc::now(), tz).date_naive(),
+                today_journal.as_deref().or(global_journal),
+                tz,
+                config.display.sort_column(),
+            )?;
+            print_day_summary(&entries, config, compact || config.display.compact);
+        }
+        Commands::OnThisDay {
+            journal: anniversary_journal,
+        } => {
+            let tz = config.display.timezone.as_deref();
+            let today = to_local_dt(&Utc::now(), tz).date_naive();
+            let filter = EntryFilter {
+                journal: anniversary_journal.as_deref().or(global_journal),
+                ..Default::default()
+            };
+            // Matched on local dates, which SQLite (always UTC) cannot compute
+            let mut entries = Vec::new();
+            journal.for_each_entry(&filter, "timestamp", "DESC", |entry| {
+                if is_anniversary(&entry.timestamp, today, tz) {
+                    entries.push(entry);
+                }
+                Ok(())
+            })?;
+            print_on_this_day(&entries, today, config);
+        }
+        Commands::Stats {
+            journal: stats_journal,
+        } => {
+            let journal_filter = stats_journal.as_deref().or(global_journal);
+            let stats = journal.stats(journal_filter)?;
+            print_stats(journal, &stats, config)?;
+        }
+        Commands::Streak {
+            journal: streak_journal,
+            short,
+        } => {
+            let journal_filter = streak_journal.as_deref().or(global_journal);
+            let tz = config.display.timezone.as_deref();
+            let streak = Streak::from_dates(
+                &local_dates(&journal.list_entry_timestamps(journal_filter, false)?, tz),
+                to_local_dt(&Utc::now(), tz).date_naive(),
+            );
+            if short {
+                println!("{}d streak (best {}d)", streak.current, streak.longest);
+            } else {
+                print_streak(&streak);
+            }
+        }
+        Commands::Pick {
+            journal: pick_journal,
+            edit,
+            delete,
+        } => {
+            let filter = EntryFilter {
+                journal: pick_journal.as_deref().or(global_journal),
+                ..Default::default()
+            };
+            let entries = journal.list_entries_filtered_with_order(
+                &filter,
+                config.display.sort_column(),
+                "DESC",
+            )?;
             if entries.is_empty() {
-                println!(
-                    "{}",
-                    format!("No entries found matching '{}'", query).yellow()
-                );
+                println!("{}", "No entries found".yellow());
+                return Ok(());
+            }
+
+            let Some(id) = pick_entry(config, &entries)? else {
+                println!("{}", "No entry selected".yellow());
+                return Ok(());
+            };
+            // Act on the selection exactly as the corresponding command would
+            let id = id.to_string();
+            let command = if edit {
+                Commands::Edit {
+                    id,
+                    preview: false,
+                    draft: false,
+                }
+            } else if delete {
+                Commands::Delete { id }
             } else {
-                println!(
-                    "{}",
-                    format!("Found {} entries matching '{}':", entries.len(), query)
-                        .green()
-                        .bold()
-                );
-                println!();
-                for entry in entries {
-                    println!(
-                        "{}",
-                        format_entry_summary(
-                            &entry,
-                            config.display.stardate_mode,
-                            config.display.timezone.as_deref()
-                        )
-                    );
+                Commands::Show { id, compact: false }
+            };
+            handle_command(command, journal, config, db_path, global_journal)?;
+        }
+        Commands::Search {
+            query,
+            fuzzy,
+            tag,
+            date,
+            since,
+            until,
+            journal: search_journal,
+            saved,
+            include_archived,
+            ids_only,
+        } => {
+            let saved = saved
+                .map(|name| SavedSearch::load(config, &name))
+                .transpose()?
+                .unwrap_or_default();
+            let query = match (saved.query(), query) {
+                (Some(saved_query), Some(query)) => format!("({}) ({})", saved_query, query),
+                (Some(query), None) | (None, Some(query)) => query,
+                (None, None) => {
+                    return Err(anyhow::anyhow!(
+                        "The saved search has no query; use `cl list --saved` or add a query"
+                    ));
                 }
+            };
+            let date = date.or(saved.date);
+            let since = since.or(saved.since);
+            let until = until.or(saved.until);
+            let tag = tag.or(saved.tag);
+            let search_journal = search_journal.or(saved.journal);
+
+            let date_filter = date
+                .as_deref()
+                .map(parse_relative_date)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid date: {}", e))?;
+            let since_filter = since
+                .as_deref()
+                .map(parse_relative_date)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid since date: {}", e))?;
+            let until_filter = until
+                .as_deref()
+                .map(parse_relative_date)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid until date: {}", e))?;
+
+            let journal_filter = search_journal.as_deref().or(global_journal);
+            let filter = EntryFilter {
+                date: date_filter,
+                since: since_filter,
+                until: until_filter,
+                journal: journal_filter,
+                tag: tag.as_deref(),
+                exclude_archived: !include_archived && journal_filter.is_none(),
+                ..Default::default()
+            };
+
+            if fuzzy {
+                handle_fuzzy_search(journal, config, &query, &filter, ids_only)?;
+            } else {
+                handle_search(journal, config, &query, filter, ids_only)?;
             }
         }
         Commands::Delete { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
             match journal.get_entry(id)? {
                 Some(entry) => {
                     // Show the entry to be deleted
@@ -309,25 +1255,20 @@ pub fn handle_command(
                         &entry,
                         config.display.stardate_mode,
                         config.display.timezone.as_deref(),
+                        false,
                     );
                     println!();
 
-                    // Ask for confirmation
-                    print!(
-                        "{}",
-                        "Are you sure you want to delete this entry? (y/N): "
-                            .red()
-                            .bold()
-                    );
-                    std::io::Write::flush(&mut std::io::stdout())?;
-
-                    let mut input = String::new();
-                    std::io::stdin().read_line(&mut input)?;
-                    let input = input.trim().to_lowercase();
-
-                    if input == "y" || input == "yes" {
+                    if confirm("Are you sure you want to delete this entry?") {
                         if journal.delete_entry(id)? {
-                            println!("{}", format!("Entry {} deleted", id).green());
+                            println!(
+                                "{}",
+                                format!(
+                                    "Entry {} moved to the trash (restore it with `cl trash restore {}`)",
+                                    id, id
+                                )
+                                .green()
+                            );
                         } else {
                             println!("{}", format!("Failed to delete entry {}", id).red());
                         }
@@ -340,58 +1281,329 @@ pub fn handle_command(
                 }
             }
         }
+        Commands::Append {
+            id,
+            messages,
+            stdin,
+            timestamp,
+        } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            let Some(entry) = journal.get_entry(id)? else {
+                println!("{}", format!("Entry {} not found", id).red());
+                return Ok(());
+            };
+            if entry.is_encrypted() {
+                return Err(anyhow::anyhow!(
+                    "Entry {} is encrypted; add to it with `cl edit {}`",
+                    id,
+                    id
+                ));
+            }
+
+            if !messages.is_empty() {
+                append_text(journal, config, id, &messages.join("\n\n"), timestamp)?;
+            } else if stdin || !std::io::stdin().is_terminal() {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .context("Failed to read the text from stdin")?;
+                append_text(journal, config, id, &text, timestamp)?;
+            } else {
+                append_from_editor(journal, config, id, timestamp)?;
+            }
+        }
+        Commands::Star { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            if journal.set_entry_starred(id, true)? {
+                println!("{}", format!("Entry {} starred", id).green());
+            } else {
+                println!("{}", format!("Entry {} not found", id).red());
+            }
+        }
+        Commands::EncryptEntry { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            let Some(entry) = journal.get_entry(id)? else {
+                println!("{}", format!("Entry {} not found", id).red());
+                return Ok(());
+            };
+            if entry.is_encrypted() {
+                println!("{}", format!("Entry {} is already encrypted", id).yellow());
+                return Ok(());
+            }
+            let encrypted = encrypt_entry_content(config, &entry.content)?;
+            journal.encrypt_entry(id, &encrypted)?;
+            println!("{}", format!("Entry {} encrypted 🔒", id).green());
+        }
+        Commands::Unstar { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            if journal.set_entry_starred(id, false)? {
+                println!("{}", format!("Entry {} unstarred", id).green());
+            } else {
+                println!("{}", format!("Entry {} not found", id).red());
+            }
+        }
+        Commands::Publish { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            if journal.set_entry_status(id, STATUS_FINAL)? {
+                println!("{}", format!("Entry {} published", id).green());
+            } else {
+                println!("{}", format!("Entry {} not found", id).red());
+            }
+        }
         Commands::Move {
             id,
-            journal: target_journal,
-        } => match journal.get_entry(id)? {
-            Some(entry) => {
-                let old_journal = &entry.journal;
-                if journal.move_entry(id, &target_journal)? {
-                    println!(
-                        "{}",
-                        format!(
-                            "Entry {} moved from '{}' to '{}'",
-                            id, old_journal, target_journal
-                        )
-                        .green()
-                    );
-                } else {
-                    println!("{}", format!("Failed to move entry {}", id).red());
+            target: target_journal,
+            from,
+            since,
+            until,
+            tag,
+            to,
+        } => {
+            let (id, target_journal) = match (id, target_journal, to) {
+                (Some(id), Some(target_journal), None) => (id, target_journal),
+                (None, None, Some(to)) => {
+                    let since_filter = since
+                        .as_deref()
+                        .map(parse_relative_date)
+                        .transpose()
+                        .map_err(|e| anyhow::anyhow!("Invalid since date: {}", e))?;
+                    let until_filter = until
+                        .as_deref()
+                        .map(parse_relative_date)
+                        .transpose()
+                        .map_err(|e| anyhow::anyhow!("Invalid until date: {}", e))?;
+                    let filter = EntryFilter {
+                        journal: from.as_deref().or(global_journal),
+                        since: since_filter,
+                        until: until_filter,
+                        tag: tag.as_deref(),
+                        ..Default::default()
+                    };
+                    return move_filtered_entries(journal, config, &filter, &to);
+                }
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Use `cl move <id> <journal>` or `cl move --journal <journal> --to <journal>`"
+                    ));
+                }
+            };
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            match journal.get_entry(id)? {
+                Some(entry) => {
+                    let old_journal = &entry.journal;
+                    if journal.move_entry(id, &target_journal)? {
+                        println!(
+                            "{}",
+                            format!(
+                                "Entry {} moved from '{}' to '{}'",
+                                id, old_journal, target_journal
+                            )
+                            .green()
+                        );
+                    } else {
+                        println!("{}", format!("Failed to move entry {}", id).red());
+                    }
+                }
+                None => {
+                    println!("{}", format!("Entry {} not found", id).red());
                 }
             }
-            None => {
-                println!("{}", format!("Entry {} not found", id).red());
+        }
+        Commands::Edit { id, preview, draft } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            if preview {
+                match journal.get_entry(id)? {
+                    Some(entry) => {
+                        print_entry(
+                            &entry,
+                            config.display.stardate_mode,
+                            config.display.timezone.as_deref(),
+                            false,
+                        );
+                        println!();
+                        if !confirm_default_yes("Edit this entry?") {
+                            println!("{}", "Edit cancelled".yellow());
+                            return Ok(());
+                        }
+                    }
+                    None => {
+                        println!("{}", format!("Entry {} not found", id).red());
+                        return Ok(());
+                    }
+                }
             }
-        },
-        Commands::Edit { id } => {
             edit_entry(journal, id, config)?;
+            if draft {
+                journal.set_entry_status(id, STATUS_DRAFT)?;
+                println!("{}", format!("Entry {} marked as draft", id).yellow());
+            }
         }
         Commands::New {
             journal: new_journal,
             content,
+            messages,
+            title,
+            stdin,
+            dry_run,
+            draft,
+            encrypted,
         } => {
             let journal_category = new_journal.as_deref().or(global_journal);
-            if content.is_empty() {
+            let options = NewEntryOptions {
+                dry_run,
+                draft,
+                encrypted,
+            };
+            // Positional words form a single line, each --message is a paragraph
+            let entry_content = if messages.is_empty() {
+                content.join(" ")
+            } else {
+                messages.join("\n\n")
+            };
+            // Empty piped input (e.g. stdin redirected from /dev/null) falls back to the editor
+            let stdin_text =
+                if entry_content.is_empty() && (stdin || !std::io::stdin().is_terminal()) {
+                    let mut text = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut text)
+                        .context("Failed to read the entry from stdin")?;
+                    Some(text).filter(|text| stdin || !text.trim().is_empty())
+                } else {
+                    None
+                };
+            if let Some(text) = stdin_text {
+                let text = match &title {
+                    Some(title) => format!("# {}\n{}", title, text),
+                    None => text,
+                };
+                create_entry_from_text(journal, journal_category, config, &text, options)?;
+            } else if entry_content.is_empty() {
+                if title.is_some() {
+                    return Err(anyhow::anyhow!(
+                        "--title needs the entry text as arguments or with --message"
+                    ));
+                }
                 // No content provided - open editor
-                new_entry(journal, journal_category, config)?;
+                new_entry(journal, journal_category, config, options)?;
+            } else if dry_run {
+                print_new_entry_preview(
+                    title.as_deref(),
+                    &entry_content,
+                    journal_category,
+                    draft,
+                    config,
+                );
             } else {
                 // Content provided - create entry directly
-                let entry_content = content.join(" ");
-                let id = journal.create_entry(None, &entry_content, journal_category)?;
+                let entry_content = if encrypted {
+                    encrypt_entry_content(config, &entry_content)?
+                } else {
+                    entry_content
+                };
+                let id =
+                    journal.create_entry(title.as_deref(), &entry_content, journal_category)?;
+                if draft {
+                    journal.set_entry_status(id, STATUS_DRAFT)?;
+                }
                 println!("{}", format!("Entry {} added successfully", id).green());
             }
         }
+        Commands::Tags {
+            journal: tags_journal,
+        } => {
+            let journal_filter = tags_journal.as_deref().or(global_journal);
+            let usage = journal.list_tag_usage(journal_filter)?;
+            if usage.is_empty() {
+                println!("{}", "No tags found".yellow());
+            } else {
+                let width = usage
+                    .iter()
+                    .map(|u| u.name.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                for tag in usage {
+                    println!(
+                        "{}  {}  {}",
+                        format!("#{:<width$}", tag.name, width = width)
+                            .cyan()
+                            .bold(),
+                        format!("{:>4} entries", tag.count).white(),
+                        format!(
+                            "last used {}",
+                            to_local_dt(&tag.last_used, config.display.timezone.as_deref())
+                                .format("%Y-%m-%d")
+                        )
+                        .bright_black()
+                    );
+                }
+            }
+        }
         Commands::Calendar {
             year,
             month,
             journal: calendar_journal,
+            heatmap,
+            include_archived,
         } => {
             let journal_filter = calendar_journal.as_deref().or(global_journal);
-            show_calendar(journal, year, month, journal_filter, config)?;
+            let exclude_archived = !include_archived && journal_filter.is_none();
+            if heatmap {
+                let tz = config.display.timezone.as_deref();
+                let year = year.unwrap_or(to_local_dt(&Utc::now(), tz).year());
+                show_heatmap(journal, year, journal_filter, exclude_archived, tz)?;
+                return Ok(());
+            }
+            // A date picks its own year, unless --year says otherwise
+            let (date_year, month) = match month {
+                Some(month) => match month.trim().parse::<u32>() {
+                    Ok(number) => (None, Some(number)),
+                    Err(_) => {
+                        let date = parse_relative_date(&month)
+                            .map_err(|e| anyhow::anyhow!("Invalid month: {}", e))?;
+                        (Some(date.year()), Some(date.month()))
+                    }
+                },
+                None => (None, None),
+            };
+            show_calendar(
+                journal,
+                year.or(date_year),
+                month,
+                journal_filter,
+                exclude_archived,
+                config,
+            )?;
+        }
+        Commands::History { id, diff } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            print_history(journal, config, id, diff)?;
+        }
+        Commands::Undo => match journal.last_undo()? {
+            Some(action) => {
+                if confirm(&format!("Undo {}?", action.describe())) {
+                    journal.undo_last()?;
+                    println!("{}", format!("Undid {}", action.describe()).green());
+                } else {
+                    println!("{}", "Undo cancelled".yellow());
+                }
+            }
+            None => println!("{}", "Nothing to undo".yellow()),
+        },
+        Commands::Trash { action } => {
+            handle_trash_command(journal, config, db_path, action)?;
+        }
+        Commands::Journals { action } => {
+            handle_journals_command(journal, config, db_path, action, global_journal)?;
         }
         Commands::Config { action } => {
             handle_config_command(action, config)?;
         }
+        Commands::Profile { action } => {
+            handle_profile_command(action)?;
+        }
+        Commands::Template { action } => {
+            handle_template_command(journal, action, global_journal)?;
+        }
         Commands::Export {
             output,
             format,
@@ -399,49 +1611,201 @@ pub fn handle_command(
             since,
             until,
             journal: export_journal,
+            from_search,
+            tag,
+            include_drafts,
+            split_size,
+            split_by,
+            encrypt_to,
+            with_footer,
         } => {
             handle_export_command(
                 journal,
+                db_path,
                 output,
                 &format,
                 date,
                 since,
                 until,
                 export_journal.or_else(|| global_journal.map(str::to_string)),
+                from_search,
+                tag,
+                include_drafts,
                 config.display.timezone.clone(),
+                split_size,
+                split_by,
+                encrypt_to,
+                with_footer,
             )?;
         }
         Commands::Import {
             path,
             format,
             date,
+            since,
+            until,
             journal: import_journal,
+            directory,
+            recursive,
+            journal_from_file,
+            date_pattern,
+            date_format,
+            dry_run,
+        } => {
+            let journal_category = import_journal.or_else(|| global_journal.map(str::to_string));
+            let parse_date = |value: Option<String>, name: &str| {
+                value
+                    .as_deref()
+                    .map(parse_relative_date)
+                    .transpose()
+                    .map_err(|e| anyhow::anyhow!("Invalid {} filter: {}", name, e))
+            };
+            let date_filter = DateFilter {
+                date: parse_date(date, "date")?,
+                since: parse_date(since, "since")?,
+                until: parse_date(until, "until")?,
+            };
+            let format = match format {
+                Some(format) => format,
+                None if directory => "markdown".to_string(),
+                None if date_pattern.is_some() => "text".to_string(),
+                None => {
+                    let format = detect_format(std::path::Path::new(&path))?;
+                    println!("{}", format!("Detected format: {}", format).dimmed());
+                    format.to_string()
+                }
+            };
+            if directory || matches!(format.to_lowercase().as_str(), "markdown" | "md") {
+                handle_import_directory_command(
+                    journal,
+                    &path,
+                    date_filter,
+                    journal_category,
+                    recursive,
+                    dry_run,
+                )?;
+            } else if recursive {
+                return Err(anyhow::anyhow!(
+                    "--recursive only applies to markdown directory imports (--format markdown)"
+                ));
+            } else {
+                handle_import_command(
+                    journal,
+                    db_path,
+                    &path,
+                    &format,
+                    date_filter,
+                    journal_category,
+                    journal_from_file,
+                    date_pattern.as_deref(),
+                    date_format.as_deref(),
+                    dry_run,
+                )?;
+            }
+        }
+
+        Commands::Backup { output, keep } => {
+            handle_backup_command(config, db_path, output, keep)?;
+        }
+
+        Commands::Doctor => {
+            println!("{}", "Journal health check".cyan().bold());
+            println!("{}", "─".repeat(40).bright_blue());
+            let checks = doctor::run_checks(journal, config, db_path);
+            let problems = doctor::print_checks(&checks);
+            println!();
+            if problems == 0 {
+                println!("{}", "No problems found".green());
+            } else {
+                println!("{}", format!("{} problems found", problems).yellow());
+            }
+        }
+
+        Commands::Encrypt => {
+            handle_encrypt_command(config, db_path, true)?;
+        }
+        Commands::Decrypt => {
+            handle_encrypt_command(config, db_path, false)?;
+        }
+
+        Commands::Unlock { minutes } => {
+            if !is_encrypted(db_path) {
+                return Err(anyhow::anyhow!("{} is not encrypted", db_path.display()));
+            }
+            // The passphrase was asked for (and checked) when the database was opened
+            let key = encryption::key().context("No passphrase for the journal")?;
+            let minutes = minutes.unwrap_or(config.database.unlock_minutes);
+            let expires_at = Utc::now() + chrono::Duration::minutes(minutes as i64);
+            save_session(&session_dir(), db_path, key, expires_at)?;
+            println!(
+                "{}",
+                format!(
+                    "🔓 Journal unlocked until {}",
+                    to_local_dt(&expires_at, config.display.timezone.as_deref()).format("%H:%M")
+                )
+                .green()
+            );
+        }
+        Commands::Lock => {
+            handle_lock_command(db_path)?;
+        }
+
+        Commands::Restore { .. } => {
+            // handled in main.rs before the database is opened
+        }
+
+        Commands::Watch {
+            dir,
+            format,
+            journal: watch_journal,
+            recursive,
+            interval,
         } => {
-            handle_import_command(
+            let journal_category = watch_journal.or_else(|| global_journal.map(str::to_string));
+            handle_watch_command(
                 journal,
-                &path,
+                db_path,
+                &dir,
                 &format,
-                date,
-                import_journal.or_else(|| global_journal.map(str::to_string)),
+                journal_category,
+                recursive,
+                interval,
             )?;
         }
 
         Commands::Record {
+            id,
             journal: record_journal,
             no_transcribe,
             max_duration,
         } => {
+            let target = id
+                .map(|id| resolve_entry_ref(journal, &id, global_journal))
+                .transpose()?;
             handle_record_command(
                 journal,
                 config,
                 db_path,
+                target,
                 record_journal.or_else(|| global_journal.map(str::to_string)),
                 no_transcribe,
                 max_duration,
             )?;
         }
 
-        Commands::Play { id } => {
+        Commands::Attach { id, files } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            handle_attach_command(journal, db_path, id, &files)?;
+        }
+        Commands::Attachments { action } => {
+            handle_attachments_command(journal, db_path, action)?;
+        }
+        Commands::Open { id, nth } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
+            handle_open_command(journal, db_path, id, nth.map(|nth| nth as usize))?;
+        }
+        Commands::Play { id } => {
+            let id = resolve_entry_ref(journal, &id, global_journal)?;
             handle_play_command(journal, config, db_path, id)?;
         }
         Commands::Serve { .. } => {
@@ -452,18 +1816,100 @@ pub fn handle_command(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_export_command(
     journal: &Journal,
+    db_path: &std::path::Path,
     output_path: Option<String>,
     format: &str,
     date: Option<String>,
     since: Option<String>,
     until: Option<String>,
     journal_filter: Option<String>,
+    search: Option<String>,
+    tag: Option<String>,
+    include_drafts: bool,
     timezone: Option<String>,
+    split_size: Option<usize>,
+    split_by: Option<String>,
+    encrypt_to: Option<String>,
+    with_footer: bool,
 ) -> Result<()> {
-    let filters = create_export_filters(date, since, until, journal_filter);
-    let exporter = Exporter::new(journal, timezone);
+    let filters = create_export_filters(
+        date,
+        since,
+        until,
+        journal_filter,
+        search,
+        tag,
+        include_drafts,
+    );
+    let encrypted = encrypt_to.is_some();
+    let mut exporter = Exporter::new(journal, timezone)
+        .with_footer(with_footer)
+        .with_encryption(encrypt_to.as_deref().map(Encryption::parse));
+    if let Some(db_dir) = db_path.parent() {
+        exporter = exporter.with_attachments(db_dir);
+    }
+
+    if format.eq_ignore_ascii_case("site") {
+        let output_dir = output_path.context("--format site requires --output <directory>")?;
+        if split_size.is_some() || split_by.is_some() || encrypted {
+            return Err(anyhow::anyhow!(
+                "--split-size, --split-by and --encrypt-to are not supported with --format site"
+            ));
+        }
+        let count = exporter.export_to_site(&output_dir, filters)?;
+        println!(
+            "{}",
+            format!(
+                "Static site with {} entries generated in {}",
+                count, output_dir
+            )
+            .green()
+        );
+        return Ok(());
+    }
+
+    if format.eq_ignore_ascii_case("vault") {
+        let output_dir = output_path.context("--format vault requires --output <directory>")?;
+        if split_size.is_some() || split_by.is_some() || encrypted {
+            return Err(anyhow::anyhow!(
+                "--split-size, --split-by and --encrypt-to are not supported with --format vault"
+            ));
+        }
+        let count = exporter.export_to_vault(&output_dir, filters)?;
+        println!(
+            "{}",
+            format!(
+                "{} entries exported as markdown files to {}",
+                count, output_dir
+            )
+            .green()
+        );
+        return Ok(());
+    }
+
+    if split_size.is_some() || split_by.is_some() {
+        let output_path = output_path.context("--split-size and --split-by require --output")?;
+        let written = match split_size {
+            Some(split_size) => exporter.export_split(format, &output_path, filters, split_size)?,
+            // --split-by only accepts "journal"
+            None => exporter.export_by_journal(format, &output_path, filters)?,
+        };
+        if written.is_empty() {
+            println!("{}", "No entries to export".yellow());
+        } else {
+            println!(
+                "{}",
+                format!("Entries exported successfully to {} files:", written.len()).green()
+            );
+            for path in written {
+                println!("  {}", path);
+            }
+        }
+        return Ok(());
+    }
 
     match format.to_lowercase().as_str() {
         "json" => {
@@ -475,9 +1921,15 @@ fn handle_export_command(
         "org" => {
             exporter.export_to_org(output_path.clone(), filters)?;
         }
+        "txt" | "text" => {
+            exporter.export_to_text(output_path.clone(), filters)?;
+        }
+        "jrnl" => {
+            exporter.export_to_jrnl(output_path.clone(), filters)?;
+        }
         _ => {
             return Err(anyhow::anyhow!(
-                "Unsupported export format '{}'. Currently supported formats: json, markdown, org",
+                "Unsupported export format '{}'. Currently supported formats: json, markdown, org, txt, jrnl, site, vault",
                 format
             ));
         }
@@ -496,39 +1948,55 @@ fn handle_export_command(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_export_filters(
     date: Option<String>,
     since: Option<String>,
     until: Option<String>,
     journal_filter: Option<String>,
+    search: Option<String>,
+    tag: Option<String>,
+    include_drafts: bool,
 ) -> Option<ExportFilters> {
-    if date.is_some() || since.is_some() || until.is_some() || journal_filter.is_some() {
+    if date.is_some()
+        || since.is_some()
+        || until.is_some()
+        || journal_filter.is_some()
+        || search.is_some()
+        || tag.is_some()
+        || include_drafts
+    {
         Some(ExportFilters {
             date,
             since,
             until,
             journal: journal_filter,
+            search,
+            tag,
+            include_drafts,
         })
     } else {
         None
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_import_command(
     journal: &Journal,
+    db_path: &std::path::Path,
     file_path: &str,
     format: &str,
-    date: Option<String>,
+    date_filter: DateFilter,
     journal_category: Option<String>,
+    journal_from_file: bool,
+    date_pattern: Option<&str>,
+    date_format: Option<&str>,
+    dry_run: bool,
 ) -> Result<()> {
-    // Parse date filter if provided
-    let filter_date = date
-        .as_deref()
-        .map(parse_relative_date)
-        .transpose()
-        .map_err(|e| anyhow::anyhow!("Invalid date filter: {}", e))?;
-
-    let importer = Importer::new(journal);
+    let importer = Importer::new(journal)
+        .with_attachments(db_path)
+        .with_dry_run(dry_run)
+        .with_progress(std::io::stderr().is_terminal());
 
     match format.to_lowercase().as_str() {
         "org" => {
@@ -540,28 +2008,9 @@ fn handle_import_command(
             println!("{}", format!("Importing from {}...", file_path).cyan());
 
             let stats =
-                importer.import_from_org(file_path, journal_category.as_deref(), filter_date)?;
-
-            // Display results
-            println!();
-            println!("{}", "Import completed!".green().bold());
-            println!("  Total entries found: {}", stats.total);
-            println!(
-                "  Successfully imported: {}",
-                stats.imported.to_string().green()
-            );
-
-            if stats.skipped > 0 {
-                println!("  Skipped: {}", stats.skipped.to_string().yellow());
-            }
+                importer.import_from_org(file_path, journal_category.as_deref(), date_filter)?;
 
-            if !stats.errors.is_empty() {
-                println!();
-                println!("{}", "Errors encountered:".red().bold());
-                for error in &stats.errors {
-                    println!("  - {}", error.red());
-                }
-            }
+            print_import_stats(&stats, dry_run);
         }
         "dayone" => {
             println!(
@@ -571,33 +2020,79 @@ fn handle_import_command(
             );
             println!("{}", format!("Importing from {}...", file_path).cyan());
 
+            let stats = importer.import_from_dayone(
+                file_path,
+                journal_category.as_deref(),
+                date_filter,
+                journal_from_file,
+            )?;
+
+            print_import_stats(&stats, dry_run);
+        }
+        "jrnl" => {
+            println!("{}", format!("Importing from {}...", file_path).cyan());
+
             let stats =
-                importer.import_from_dayone(file_path, journal_category.as_deref(), filter_date)?;
+                importer.import_from_jrnl(file_path, journal_category.as_deref(), date_filter)?;
 
-            // Display results
-            println!();
-            println!("{}", "Import completed!".green().bold());
-            println!("  Total entries found: {}", stats.total);
-            println!(
-                "  Successfully imported: {}",
-                stats.imported.to_string().green()
-            );
+            print_import_stats(&stats, dry_run);
+        }
+        "diaro" => {
+            println!("{}", format!("Importing from {}...", file_path).cyan());
 
-            if stats.skipped > 0 {
-                println!("  Skipped: {}", stats.skipped.to_string().yellow());
-            }
+            let stats =
+                importer.import_from_diaro(file_path, journal_category.as_deref(), date_filter)?;
 
-            if !stats.errors.is_empty() {
-                println!();
-                println!("{}", "Errors encountered:".red().bold());
-                for error in &stats.errors {
-                    println!("  - {}", error.red());
-                }
-            }
+            print_import_stats(&stats, dry_run);
+        }
+        "enex" | "evernote" => {
+            println!("{}", format!("Importing from {}...", file_path).cyan());
+
+            let stats = importer.import_from_enex(
+                file_path,
+                journal_category.as_deref(),
+                date_filter,
+                journal_from_file,
+            )?;
+
+            print_import_stats(&stats, dry_run);
+        }
+        "text" | "txt" => {
+            let date_pattern = date_pattern.context("--format text requires --date-pattern")?;
+            println!("{}", format!("Importing from {}...", file_path).cyan());
+
+            let stats = importer.import_from_text(
+                file_path,
+                journal_category.as_deref(),
+                date_filter,
+                date_pattern,
+                date_format,
+            )?;
+
+            print_import_stats(&stats, dry_run);
+        }
+        "journey" => {
+            println!("{}", format!("Importing from {}...", file_path).cyan());
+
+            let stats = importer.import_from_journey(
+                file_path,
+                journal_category.as_deref(),
+                date_filter,
+            )?;
+
+            print_import_stats(&stats, dry_run);
+        }
+        "json" => {
+            println!("{}", format!("Importing from {}...", file_path).cyan());
+
+            let stats =
+                importer.import_from_json(file_path, journal_category.as_deref(), date_filter)?;
+
+            print_import_stats(&stats, dry_run);
         }
         _ => {
             return Err(anyhow::anyhow!(
-                "Unsupported import format '{}'. Currently supported formats: org, dayone",
+                "Unsupported import format '{}'. Currently supported formats: org, dayone, diaro, enex, jrnl, journey, json, markdown, text",
                 format
             ));
         }
@@ -606,133 +2101,1312 @@ fn handle_import_command(
     Ok(())
 }
 
-fn handle_record_command(
-    journal_obj: &Journal,
-    config: &Config,
-    db_path: &std::path::Path,
+fn handle_import_directory_command(
+    journal: &Journal,
+    dir_path: &str,
+    date_filter: DateFilter,
     journal_category: Option<String>,
-    no_transcribe: bool,
-    max_duration: Option<u64>,
+    recursive: bool,
+    dry_run: bool,
 ) -> Result<()> {
-    use crate::audio::{
-        ensure_audio_directory_exists, generate_audio_filename, get_audio_directory, record_audio,
-        transcribe_audio,
-    };
+    println!(
+        "{}",
+        format!("Importing markdown files from {}...", dir_path).cyan()
+    );
 
-    // Ensure audio directory exists
-    ensure_audio_directory_exists(db_path)?;
+    let importer = Importer::new(journal)
+        .with_dry_run(dry_run)
+        .with_progress(std::io::stderr().is_terminal());
+    let stats = importer.import_from_directory(
+        dir_path,
+        journal_category.as_deref(),
+        date_filter,
+        recursive,
+    )?;
 
-    // Generate filename
-    let audio_dir = get_audio_directory(db_path)?;
-    let filename = generate_audio_filename();
-    let full_path = audio_dir.join(&filename);
+    print_import_stats(&stats, dry_run);
 
-    // Get max duration from config or parameter
-    let max_duration_secs = max_duration.unwrap_or(config.audio.max_recording_seconds);
+    Ok(())
+}
 
-    // Record audio
-    let duration = record_audio(config, &full_path, max_duration_secs)?;
+fn handle_backup_command(
+    config: &Config,
+    db_path: &std::path::Path,
+    output: Option<String>,
+    keep: Option<u64>,
+) -> Result<()> {
+    let dir = output
+        .or_else(|| config.backup.dir.clone())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_backup_dir(db_path));
+    let mut retention = config.backup.clone();
+    if let Some(keep) = keep {
+        retention.keep = keep as usize;
+    }
 
-    // Transcribe audio (unless skipped)
-    let transcription = if no_transcribe {
+    // A connection of its own, read-only: the backup never writes to the journal
+    let db = Database::new_readonly(db_path)?;
+    let now = Local::now().naive_local();
+    let path = create_backup(&db, db_path, &dir, now)?;
+    println!(
+        "{}",
+        format!("✓ Backed up the journal to {}", path.display()).green()
+    );
+
+    let deleted = prune_backups(db_path, &dir, &retention, now)?;
+    if !deleted.is_empty() {
         println!(
             "{}",
-            "Skipping transcription (--no-transcribe flag)".yellow()
+            format!("Removed {} old backups", deleted.len()).bright_black()
         );
-        "[Audio entry - no transcription]".to_string()
+    }
+
+    Ok(())
+}
+
+/// Replace the database at `db_path` with an encrypted (or decrypted) copy of itself
+fn handle_encrypt_command(config: &Config, db_path: &std::path::Path, encrypt: bool) -> Result<()> {
+    let encrypted = is_encrypted(db_path);
+    if encrypt && encrypted {
+        return Err(anyhow::anyhow!(
+            "{} is already encrypted",
+            db_path.display()
+        ));
+    }
+    if !encrypt && !encrypted {
+        return Err(anyhow::anyhow!("{} is not encrypted", db_path.display()));
+    }
+
+    let converting = db_path.with_extension("db.converting");
+    if converting.exists() {
+        fs::remove_file(&converting)?;
+    }
+    let db = Database::new_with_config(db_path, &config.database)?;
+    let mut passphrase = None;
+    if encrypt {
+        let new_passphrase = read_new_passphrase()?;
+        db.export_encrypted(&converting, &new_passphrase)?;
+        passphrase = Some(new_passphrase);
     } else {
-        match transcribe_audio(config, &full_path) {
-            Ok(text) => {
-                // Display transcription to user
-                println!();
-                println!("{}", "─── Transcription ───".cyan().bold());
-                println!("{}", text);
-                println!("{}", "─────────────────────".cyan().bold());
-                println!();
-                text
-            }
-            Err(e) => {
-                println!(
-                    "{}",
-                    format!("Warning: Transcription failed: {}", e).yellow()
-                );
-                println!("{}", "Saving entry with audio only...".yellow());
-                "[Transcription failed - audio only]".to_string()
+        if !confirm("Store the journal unencrypted?") {
+            println!("{}", "Decryption cancelled.".yellow());
+            return Ok(());
+        }
+        db.export_decrypted(&converting)?;
+    }
+    // Nothing may be left in the write-ahead log of the file being replaced
+    db.checkpoint()?;
+    drop(db);
+
+    fs::rename(&converting, db_path)
+        .with_context(|| format!("Failed to replace {}", db_path.display()))?;
+    if let Some(passphrase) = passphrase {
+        println!("{}", format!("✓ Encrypted {}", db_path.display()).green());
+        if config.database.keychain {
+            match keychain::store_passphrase(db_path, &passphrase) {
+                Ok(()) => println!("{}", "Passphrase saved in the keychain".green()),
+                Err(e) => println!("{}", format!("Warning: {:#}", e).yellow()),
             }
         }
-    };
+        println!(
+            "{}",
+            format!(
+                "Backups made earlier are not encrypted; set {} to skip the passphrase prompt in this shell",
+                KEY_ENV
+            )
+            .bright_black()
+        );
+    } else {
+        println!("{}", format!("✓ Decrypted {}", db_path.display()).green());
+        clear_session(&session_dir(), db_path)?;
+        if keychain::delete_passphrase(db_path)? {
+            println!("{}", "Passphrase removed from the keychain".green());
+        }
+    }
 
-    println!("{}", "📝 Creating journal entry...".cyan());
+    Ok(())
+}
 
-    // Create entry with audio
-    // Store relative path: audio/filename.wav
-    let relative_path = format!("audio/{}", filename);
+fn handle_profile_command(action: ProfileAction) -> Result<()> {
+    match action {
+        ProfileAction::List => {
+            let active = active_profile().unwrap_or(DEFAULT_PROFILE);
+            let mut names = vec![DEFAULT_PROFILE.to_string()];
+            names.extend(Config::list_profiles()?);
+            for name in names {
+                if name == active {
+                    println!("{} {}", "*".green(), name.green().bold());
+                } else {
+                    println!("  {}", name);
+                }
+            }
+        }
+        ProfileAction::Create { name } => {
+            let path = Config::create_profile(&name)?;
+            println!("{}", format!("Created profile '{}'", name).green());
+            println!("  config: {}", path.display().to_string().bright_black());
+            println!(
+                "{}",
+                format!(
+                    "Use it with `cl --profile {} ...`, e.g. `cl --profile {} config set editor.command nano`",
+                    name, name
+                )
+                .bright_black()
+            );
+        }
+    }
+    Ok(())
+}
 
-    let entry_id = journal_obj.create_entry_with_audio(
-        None, // No title
-        &transcription,
-        journal_category.as_deref(),
-        Some(&relative_path),
-    )?;
+/// Forget the passphrase kept by `cl unlock`. Runs before the database is opened, so
+/// locking never asks for the passphrase.
+pub fn handle_lock_command(db_path: &std::path::Path) -> Result<()> {
+    if clear_session(&session_dir(), db_path)? {
+        println!("{}", "🔒 Journal locked".green());
+    } else {
+        println!("{}", "The journal was not unlocked".yellow());
+    }
+    Ok(())
+}
 
+/// Replace the journal with a backup. Runs before the database is opened, so no
+/// connection holds the journal or its WAL files while they are swapped.
+pub fn handle_restore_command(db_path: &std::path::Path, backup: &std::path::Path) -> Result<()> {
+    let backup_entries = validate_backup(backup)?;
     println!(
         "{}",
         format!(
-            "✓ Entry {} created successfully with audio attached",
-            entry_id
+            "Backup {} is valid: {} entries",
+            backup.display(),
+            backup_entries
         )
         .green()
     );
+    match validate_backup(db_path) {
+        Ok(current_entries) => println!(
+            "Current journal {}: {} entries",
+            db_path.display(),
+            current_entries
+        ),
+        Err(e) => println!(
+            "{}",
+            format!("Current journal {}: {}", db_path.display(), e).yellow()
+        ),
+    }
+
+    if !confirm("Replace the current journal with this backup?") {
+        println!("{}", "Restore cancelled.".yellow());
+        return Ok(());
+    }
+
+    let now = Local::now().naive_local();
+    let stash = restore_backup(backup, db_path, now)?;
     println!(
-        "  {}: {}",
-        "Duration".cyan(),
-        format!("{:.1}s", duration.as_secs_f64())
+        "{}",
+        format!("✓ Restored {} to {}", backup.display(), db_path.display()).green()
     );
-    println!("  {}: {}", "Audio".cyan(), relative_path.green());
+    if let Some(stash) = stash {
+        println!(
+            "{}",
+            format!("The replaced journal was kept as {}", stash.display()).bright_black()
+        );
+    }
 
     Ok(())
 }
 
-fn handle_play_command(
-    journal_obj: &Journal,
-    config: &Config,
+fn handle_watch_command(
+    journal: &Journal,
     db_path: &std::path::Path,
-    id: i64,
+    dir: &str,
+    format: &str,
+    journal_category: Option<String>,
+    recursive: bool,
+    interval: u64,
 ) -> Result<()> {
-    use crate::audio::{get_audio_full_path, play_audio};
-    use colored::Colorize;
-
-    // Get entry
-    let entry = journal_obj
-        .get_entry(id)?
-        .ok_or_else(|| anyhow::anyhow!("Entry {} not found", id))?;
-
-    // Check if entry has audio
-    let audio_path = entry
-        .audio_path
-        .ok_or_else(|| anyhow::anyhow!("Entry {} has no audio recording", id))?;
-
-    // Get full path
-    let full_path = get_audio_full_path(db_path, &audio_path)?;
-
-    // Check if file exists
-    if !full_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Audio file not found at {}. It may have been moved or deleted.",
-            full_path.display()
-        ));
-    }
+    // Ctrl-C ends the watch after the current file instead of killing the process
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = stop.clone();
+    thread::spawn(move || {
+        let mut signals = Signals::new([SIGINT]).expect("Failed to register signal handler");
+        if signals.forever().next().is_some() {
+            stop_clone.store(true, Ordering::Relaxed);
+        }
+    });
 
     println!(
         "{}",
-        format!("🎵 Playing audio from entry {}...", id).cyan()
+        format!("Watching {} for {} files (Ctrl-C to stop)...", dir, format).cyan()
     );
-    println!("  {}: {}", "Audio".cyan(), audio_path.green());
 
-    // Play audio
-    play_audio(config, &full_path)?;
-
-    println!("{}", "✓ Playback complete".green());
+    let importer = Importer::new(journal).with_attachments(db_path);
+    importer.watch(
+        std::path::Path::new(dir),
+        format,
+        journal_category.as_deref(),
+        recursive,
+        Duration::from_secs(interval.max(1)),
+        &stop,
+        |file, result| match result {
+            Ok(stats) => {
+                if stats.imported > 0 {
+                    println!(
+                        "{}",
+                        format!(
+                            "✓ Imported {} entries from {}",
+                            stats.imported,
+                            file.display()
+                        )
+                        .green()
+                    );
+                }
+                if stats.updated > 0 {
+                    println!(
+                        "{}",
+                        format!(
+                            "✓ Updated {} entries from {}",
+                            stats.updated,
+                            file.display()
+                        )
+                        .green()
+                    );
+                }
+                for error in &stats.errors {
+                    eprintln!("{} {}", "✗".red(), error);
+                }
+            }
+            Err(e) => eprintln!("{} {}: {}", "✗".red(), file.display(), e),
+        },
+    )?;
+
+    println!("{}", "Stopped watching.".dimmed());
+    Ok(())
+}
+
+fn print_import_stats(stats: &ImportStats, dry_run: bool) {
+    println!();
+    if dry_run {
+        println!("{}", "Dry run: nothing was imported.".yellow().bold());
+        println!("  Total entries found: {}", stats.total);
+        println!("  Would import: {}", stats.imported.to_string().green());
+        if let Some((first, last)) = stats.date_range {
+            println!(
+                "  Date range: {} to {}",
+                first.format("%Y-%m-%d"),
+                last.format("%Y-%m-%d")
+            );
+        }
+        if !stats.samples.is_empty() {
+            println!("  Samples:");
+            for sample in &stats.samples {
+                println!("    {}", sample.dimmed());
+            }
+        }
+    } else {
+        println!("{}", "Import completed!".green().bold());
+        println!("  Total entries found: {}", stats.total);
+        println!(
+            "  Successfully imported: {}",
+            stats.imported.to_string().green()
+        );
+    }
+
+    if stats.skipped > 0 {
+        println!("  Skipped: {}", stats.skipped.to_string().yellow());
+    }
+
+    if !stats.errors.is_empty() {
+        println!();
+        println!(
+            "{}",
+            format!("Errors encountered ({}):", stats.errors.len())
+                .red()
+                .bold()
+        );
+        for error in &stats.errors {
+            println!("  - {}", error.red());
+        }
+    }
+}
+
+fn handle_attach_command(
+    journal: &Journal,
+    db_path: &std::path::Path,
+    id: i64,
+    files: &[PathBuf],
+) -> Result<()> {
+    use crate::attachments::{check_image, store_attachment};
+
+    if journal.get_entry(id)?.is_none() {
+        println!("{}", format!("Entry {} not found", id).red());
+        return Ok(());
+    }
+    // Check every file before copying any, so a typo doesn't leave half the images attached
+    for file in files {
+        check_image(file)?;
+    }
+
+    let paths = files
+        .iter()
+        .map(|file| store_attachment(db_path, file))
+        .collect::<Result<Vec<_>>>()?;
+    journal.add_entry_images(id, &paths)?;
+
+    println!(
+        "{}",
+        format!("✓ Attached {} image(s) to entry {}", paths.len(), id).green()
+    );
+    for path in &paths {
+        println!("  {}", path.bright_black());
+    }
+    Ok(())
+}
+
+fn handle_open_command(
+    journal: &Journal,
+    db_path: &std::path::Path,
+    id: i64,
+    nth: Option<usize>,
+) -> Result<()> {
+    use crate::attachments::open_externally;
+
+    let entry = journal
+        .get_entry(id)?
+        .ok_or_else(|| anyhow::anyhow!("Entry {} not found", id))?;
+    let paths = entry.attachment_paths();
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("Entry {} has no attachments", id));
+    }
+
+    let selected = match nth {
+        Some(nth) => match paths.get(nth - 1) {
+            Some(path) => vec![*path],
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Entry {} has {} attachment(s); --nth must be between 1 and {}",
+                    id,
+                    paths.len(),
+                    paths.len()
+                ));
+            }
+        },
+        None => paths,
+    };
+
+    let db_dir = db_path
+        .parent()
+        .context("Failed to get database directory")?;
+    for path in selected {
+        let full_path = db_dir.join(path);
+        if !full_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Attachment not found at {}. It may have been moved or deleted.",
+                full_path.display()
+            ));
+        }
+        println!("{} {}", "Opening".cyan(), path.green());
+        open_externally(&full_path)?;
+    }
+    Ok(())
+}
+
+fn handle_record_command(
+    journal_obj: &Journal,
+    config: &Config,
+    db_path: &std::path::Path,
+    target: Option<i64>,
+    journal_category: Option<String>,
+    no_transcribe: bool,
+    max_duration: Option<u64>,
+) -> Result<()> {
+    use crate::audio::{
+        ensure_audio_directory_exists, generate_audio_filename, get_audio_directory, record_audio,
+        transcribe_audio,
+    };
+
+    // Check the target entry before recording anything
+    if let Some(id) = target {
+        let entry = journal_obj
+            .get_entry(id)?
+            .ok_or_else(|| anyhow::anyhow!("Entry {} not found", id))?;
+        if let Some(audio_path) = entry.audio_path {
+            return Err(anyhow::anyhow!(
+                "Entry {} already has an audio recording ({})",
+                id,
+                audio_path
+            ));
+        }
+    }
+
+    // Ensure audio directory exists
+    ensure_audio_directory_exists(db_path)?;
+
+    // Generate filename
+    let audio_dir = get_audio_directory(db_path)?;
+    let filename = generate_audio_filename();
+    let full_path = audio_dir.join(&filename);
+
+    // Get max duration from config or parameter
+    let max_duration_secs = max_duration.unwrap_or(config.audio.max_recording_seconds);
+
+    // Record audio
+    let duration = record_audio(config, &full_path, max_duration_secs)?;
+
+    // Transcribe audio (unless skipped); None when skipped or failed
+    let transcription = if no_transcribe {
+        println!(
+            "{}",
+            "Skipping transcription (--no-transcribe flag)".yellow()
+        );
+        None
+    } else {
+        match transcribe_audio(config, &full_path) {
+            Ok(text) => {
+                // Display transcription to user
+                println!();
+                println!("{}", "─── Transcription ───".cyan().bold());
+                println!("{}", text);
+                println!("{}", "─────────────────────".cyan().bold());
+                println!();
+                Some(text)
+            }
+            Err(e) => {
+                println!(
+                    "{}",
+                    format!("Warning: Transcription failed: {}", e).yellow()
+                );
+                println!("{}", "Saving entry with audio only...".yellow());
+                None
+            }
+        }
+    };
+
+    // Store relative path: audio/filename.wav
+    let relative_path = format!("audio/{}", filename);
+
+    match target {
+        Some(id) => {
+            journal_obj.set_entry_audio(id, &relative_path)?;
+            if let Some(text) = &transcription {
+                journal_obj.append_to_entry(id, text)?;
+            }
+            println!("{}", format!("✓ Audio attached to entry {}", id).green());
+        }
+        None => {
+            println!("{}", "📝 Creating journal entry...".cyan());
+
+            let content = transcription.unwrap_or_else(|| {
+                if no_transcribe {
+                    "[Audio entry - no transcription]".to_string()
+                } else {
+                    "[Transcription failed - audio only]".to_string()
+                }
+            });
+            let entry_id = journal_obj.create_entry_with_audio(
+                None, // No title
+                &content,
+                journal_category.as_deref(),
+                Some(&relative_path),
+            )?;
+
+            println!(
+                "{}",
+                format!(
+                    "✓ Entry {} created successfully with audio attached",
+                    entry_id
+                )
+                .green()
+            );
+        }
+    }
+    println!(
+        "  {}: {}",
+        "Duration".cyan(),
+        format!("{:.1}s", duration.as_secs_f64())
+    );
+    println!("  {}: {}", "Audio".cyan(), relative_path.green());
+
+    Ok(())
+}
+
+fn handle_play_command(
+    journal_obj: &Journal,
+    config: &Config,
+    db_path: &std::path::Path,
+    id: i64,
+) -> Result<()> {
+    use crate::audio::{get_audio_full_path, play_audio};
+    use colored::Colorize;
+
+    // Get entry
+    let entry = journal_obj
+        .get_entry(id)?
+        .ok_or_else(|| anyhow::anyhow!("Entry {} not found", id))?;
+
+    // Check if entry has audio
+    let audio_path = entry
+        .audio_path
+        .ok_or_else(|| anyhow::anyhow!("Entry {} has no audio recording", id))?;
+
+    // Get full path
+    let full_path = get_audio_full_path(db_path, &audio_path)?;
+
+    // Check if file exists
+    if !full_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Audio file not found at {}. It may have been moved or deleted.",
+            full_path.display()
+        ));
+    }
+
+    println!(
+        "{}",
+        format!("🎵 Playing audio from entry {}...", id).cyan()
+    );
+    println!("  {}: {}", "Audio".cyan(), audio_path.green());
+
+    // Play audio
+    play_audio(config, &full_path)?;
+
+    println!("{}", "✓ Playback complete".green());
+
+    Ok(())
+}
+
+/// `search --fuzzy`: rank every entry by similarity, then keep those matching `filter`
+fn handle_fuzzy_search(
+    journal: &Journal,
+    config: &Config,
+    query: &str,
+    filter: &EntryFilter,
+    ids_only: bool,
+) -> Result<()> {
+    let matches =
+        journal.fuzzy_search_entries(query, filter, FUZZY_SEARCH_THRESHOLD, FUZZY_SEARCH_LIMIT)?;
+    if ids_only {
+        for (entry, _) in &matches {
+            println!("{}", entry.id);
+        }
+    } else if matches.is_empty() {
+        println!(
+            "{}",
+            format!("No entries found resembling '{}'", query).yellow()
+        );
+    } else {
+        println!(
+            "{}",
+            format!("Found {} entries resembling '{}':", matches.len(), query)
+                .green()
+                .bold()
+        );
+        println!();
+        for (entry, score) in matches {
+            println!(
+                "{} {}",
+                format!("{:>3.0}%", score * 100.0).bright_black(),
+                format_entry_summary(
+                    &entry,
+                    config.display.stardate_mode,
+                    config.display.timezone.as_deref(),
+                    config.display.age_fade,
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// `search`: substring match on title and content, combined with `filter` in SQL
+fn handle_search(
+    journal: &Journal,
+    config: &Config,
+    query: &str,
+    filter: EntryFilter,
+    ids_only: bool,
+) -> Result<()> {
+    let entries = journal.search_entries_filtered(query, filter, config.display.sort_column())?;
+    // Negated terms never appear in results, so only the positive ones are highlighted
+    let parsed = SearchQuery::parse(query)?;
+    let terms = parsed.positive_terms();
+    if ids_only {
+        print_entry_ids(&entries);
+    } else if entries.is_empty() {
+        println!(
+            "{}",
+            format!("No entries found matching '{}'", query).yellow()
+        );
+    } else {
+        println!(
+            "{}",
+            format!("Found {} entries matching '{}':", entries.len(), query)
+                .green()
+                .bold()
+        );
+        println!();
+        for entry in entries {
+            let match_count: usize = terms
+                .iter()
+                .map(|term| {
+                    find_matches(&entry.content, term).len()
+                        + entry
+                            .title
+                            .as_deref()
+                            .map_or(0, |title| find_matches(title, term).len())
+                })
+                .sum();
+            println!(
+                "{} {}",
+                format_entry_summary(
+                    &entry,
+                    config.display.stardate_mode,
+                    config.display.timezone.as_deref(),
+                    config.display.age_fade,
+                ),
+                format!(
+                    "({} {})",
+                    match_count,
+                    if match_count == 1 { "match" } else { "matches" }
+                )
+                .bright_black()
+            );
+            if let Some(snippet) = terms
+                .iter()
+                .find_map(|term| search_snippet(&entry.content, term, SEARCH_SNIPPET_RADIUS))
+            {
+                println!("    {}", snippet);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Colors configured with `journals set <name> color`. Unknown color names are skipped,
+/// and so is everything if the table is unavailable (e.g. an old read-only database).
+fn load_journal_colors(journal: &Journal) -> HashMap<String, Color> {
+    journal
+        .list_journal_info()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|info| Some((info.name, info.color?.parse().ok()?)))
+        .collect()
+}
+
+/// A journal name in its configured color, magenta by default
+fn styled_journal_name(name: &str) -> ColoredString {
+    style_as_journal(name, name.to_string())
+}
+
+/// `text` (e.g. a padded name or a `[Name]` badge) in the color of `journal`
+fn style_as_journal(journal: &str, text: String) -> ColoredString {
+    match JOURNAL_COLORS.get().and_then(|colors| colors.get(journal)) {
+        Some(color) => text.color(*color).bold(),
+        None => text.magenta().bold(),
+    }
+}
+
+fn handle_journals_command(
+    journal: &Journal,
+    config: &Config,
+    db_path: &std::path::Path,
+    action: Option<JournalsAction>,
+    global_journal: Option<&str>,
+) -> Result<()> {
+    match action.unwrap_or(JournalsAction::List) {
+        JournalsAction::List => {
+            let usage = journal.list_journal_usage()?;
+            if usage.is_empty() {
+                println!("{}", "No journals found".yellow());
+                return Ok(());
+            }
+
+            // New entries go to the global --journal, if given, like with `new`
+            let default_journal = global_journal.unwrap_or(DEFAULT_JOURNAL);
+            let archived: Vec<String> = journal
+                .list_journal_info()?
+                .into_iter()
+                .filter(|info| info.archived)
+                .map(|info| info.name)
+                .collect();
+            let tz = config.display.timezone.as_deref();
+            let width = usage
+                .iter()
+                .map(|u| u.name.chars().count())
+                .max()
+                .unwrap_or(0);
+            for u in &usage {
+                let marker = if u.name == default_journal { "*" } else { " " };
+                let archived_marker = if archived.contains(&u.name) {
+                    " (archived)".bright_black().to_string()
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{} {}  {:>5} {}  {}{}",
+                    marker.green().bold(),
+                    style_as_journal(&u.name, format!("{:<width$}", u.name, width = width)),
+                    u.count,
+                    if u.count == 1 { "entry  " } else { "entries" },
+                    format!(
+                        "{} → {}",
+                        to_local_dt(&u.first_entry, tz).format("%Y-%m-%d"),
+                        to_local_dt(&u.last_entry, tz).format("%Y-%m-%d")
+                    )
+                    .bright_black(),
+                    archived_marker
+                );
+            }
+            println!();
+            println!("{} = default journal for new entries", "*".green().bold());
+        }
+        JournalsAction::Describe { name } => {
+            let info = journal.get_journal_info(&name)?;
+            let usage = journal
+                .list_journal_usage()?
+                .into_iter()
+                .find(|u| u.name == name);
+            if info.is_none() && usage.is_none() {
+                println!("{}", format!("Journal '{}' not found", name).red());
+                return Ok(());
+            }
+            let info = info.unwrap_or_default();
+            let unset = || "not set".bright_black();
+
+            println!("{}", styled_journal_name(&name));
+            println!("{}", "─".repeat(40).bright_blue());
+            match &info.description {
+                Some(description) => println!("  description: {}", description.green()),
+                None => println!("  description: {}", unset()),
+            }
+            match &info.color {
+                Some(color) => match color.parse::<Color>() {
+                    Ok(parsed) => println!("  color: {}", color.color(parsed)),
+                    Err(_) => println!("  color: {}", color),
+                },
+                None => println!("  color: {}", unset()),
+            }
+            match &info.default_template {
+                Some(template) => println!("  template: {}", template.green()),
+                None => println!("  template: {}", unset()),
+            }
+            if info.archived {
+                println!("  archived: {}", "yes".yellow());
+            }
+            match usage {
+                Some(u) => {
+                    let tz = config.display.timezone.as_deref();
+                    println!("  entries: {}", u.count.to_string().green());
+                    println!(
+                        "  first entry: {}",
+                        to_local_dt(&u.first_entry, tz).format("%Y-%m-%d")
+                    );
+                    println!(
+                        "  last entry: {}",
+                        to_local_dt(&u.last_entry, tz).format("%Y-%m-%d")
+                    );
+                }
+                None => println!("  entries: {}", "0".green()),
+            }
+        }
+        JournalsAction::Set { name, key, value } => {
+            let mut info = journal.get_journal_info(&name)?.unwrap_or(JournalInfo {
+                name: name.clone(),
+                ..Default::default()
+            });
+            let value = (!value.trim().is_empty()).then(|| value.trim().to_string());
+            match key.as_str() {
+                "description" => info.description = value.clone(),
+                "color" => {
+                    if let Some(color) = &value
+                        && color.parse::<Color>().is_err()
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Unknown color '{}'. Use a name like 'blue', 'cyan' or 'bright magenta'",
+                            color
+                        ));
+                    }
+                    info.color = value.clone();
+                }
+                "template" => {
+                    if let Some(template_name) = &value {
+                        template::load_template(template_name)?;
+                    }
+                    info.default_template = value.clone();
+                }
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Unknown journal setting '{}'. Available settings: description, color, template",
+                        key
+                    ));
+                }
+            }
+            journal.set_journal_info(&info)?;
+            match value {
+                Some(value) => println!(
+                    "{}",
+                    format!("Set {} of journal '{}' to '{}'", key, name, value).green()
+                ),
+                None => println!(
+                    "{}",
+                    format!("Cleared {} of journal '{}'", key, name).green()
+                ),
+            }
+        }
+        JournalsAction::Archive { name } => set_journal_archived(journal, &name, true)?,
+        JournalsAction::Unarchive { name } => set_journal_archived(journal, &name, false)?,
+        JournalsAction::Delete {
+            name,
+            move_to,
+            purge,
+        } => {
+            // A journal exists through its entries (trashed ones included) or its settings
+            let (live, trashed) = journal.count_journal_entries(&name)?;
+            let count = match trashed {
+                0 => format!("{} entries", live),
+                _ => format!("{} entries ({} more in the trash)", live, trashed),
+            };
+            if live + trashed == 0 {
+                if journal.get_journal_info(&name)?.is_none() {
+                    println!("{}", format!("Journal '{}' not found", name).red());
+                } else {
+                    journal.delete_journal(&name, None)?;
+                    println!("{}", format!("Journal '{}' deleted", name).green());
+                }
+                return Ok(());
+            }
+
+            if let Some(target) = move_to {
+                if target == name {
+                    return Err(anyhow::anyhow!(
+                        "Cannot move the entries of '{}' to itself",
+                        name
+                    ));
+                }
+                let moved = journal.delete_journal(&name, Some(&target))?;
+                println!(
+                    "{}",
+                    format!(
+                        "Journal '{}' deleted, {} entries moved to '{}'",
+                        name, moved, target
+                    )
+                    .green()
+                );
+            } else if purge {
+                println!(
+                    "{}",
+                    format!("Journal '{}' has {}", name, count).yellow().bold()
+                );
+                if confirm(&format!(
+                    "Permanently delete journal '{}' and all its entries?",
+                    name
+                )) {
+                    let deleted = journal.delete_journal(&name, None)?;
+                    println!(
+                        "{}",
+                        format!("Journal '{}' deleted with {} entries", name, deleted).green()
+                    );
+                    report_orphaned_attachments(journal, db_path)?;
+                } else {
+                    println!("{}", "Deletion cancelled".yellow());
+                }
+            } else {
+                return Err(anyhow::anyhow!(
+                    "Journal '{}' has {}; use --move-to <journal> to keep them or --purge to delete them",
+                    name,
+                    count
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Title and content as one text, the way revisions are compared
+fn revision_text(title: Option<&str>, content: &str) -> String {
+    match title {
+        Some(title) => format!("# {}\n\n{}", title, content),
+        None => content.to_string(),
+    }
+}
+
+fn print_history(journal: &Journal, config: &Config, id: i64, diff: Option<usize>) -> Result<()> {
+    let Some(entry) = journal.get_entry(id)? else {
+        println!("{}", format!("Entry {} not found", id).red());
+        return Ok(());
+    };
+    let revisions = journal.list_entry_revisions(id)?;
+    let tz = config.display.timezone.as_deref();
+
+    let Some(number) = diff else {
+        if revisions.is_empty() {
+            println!(
+                "{}",
+                format!("Entry {} has no earlier revisions", id).yellow()
+            );
+            return Ok(());
+        }
+        println!("{}", format!("Revisions of entry {}:", id).cyan().bold());
+        for revision in &revisions {
+            let summary = revision
+                .title
+                .clone()
+                .or_else(|| revision.content.lines().next().map(str::to_string))
+                .unwrap_or_default();
+            println!(
+                "  {} {} {}",
+                format!("#{}", revision.number).bright_blue().bold(),
+                format!(
+                    "replaced {}",
+                    to_local_dt(&revision.revised_at, tz).format("%Y-%m-%d %H:%M")
+                )
+                .bright_black(),
+                summary
+            );
+        }
+        return Ok(());
+    };
+
+    let Some(revision) = revisions.iter().find(|r| r.number == number) else {
+        return Err(anyhow::anyhow!(
+            "Entry {} has no revision #{} (it has {})",
+            id,
+            number,
+            revisions.len()
+        ));
+    };
+
+    let old = revision_text(revision.title.as_deref(), &revision.content);
+    let new = revision_text(entry.title.as_deref(), &entry.content);
+    println!(
+        "{}",
+        format!(
+            "--- revision #{} (replaced {})",
+            number,
+            to_local_dt(&revision.revised_at, tz).format("%Y-%m-%d %H:%M")
+        )
+        .red()
+    );
+    println!("{}", "+++ current".green());
+    for line in diff_lines(&old, &new) {
+        match line {
+            DiffLine::Same(text) => println!(" {}", text),
+            DiffLine::Removed(text) => println!("{}", format!("-{}", text).red()),
+            DiffLine::Added(text) => println!("{}", format!("+{}", text).green()),
+        }
+    }
+    Ok(())
+}
+
+/// Human-readable file size, e.g. `512 B`, `1.5 KB`, `2.0 MB`
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn handle_attachments_command(
+    journal: &Journal,
+    db_path: &std::path::Path,
+    action: Option<AttachmentsAction>,
+) -> Result<()> {
+    use crate::attachments::{list_attachment_files, remove_orphaned};
+
+    let files = list_attachment_files(db_path, &journal.list_attachment_references()?)?;
+    match action.unwrap_or(AttachmentsAction::List) {
+        AttachmentsAction::List => {
+            if files.is_empty() {
+                println!("{}", "No attachments".yellow());
+                return Ok(());
+            }
+            for file in &files {
+                let size = match file.size {
+                    Some(size) => format_size(size).normal(),
+                    None => "missing".red(),
+                };
+                let used_by = if file.is_orphaned() {
+                    "orphaned".yellow()
+                } else {
+                    let ids: Vec<String> = file.entry_ids.iter().map(i64::to_string).collect();
+                    format!("entry {}", ids.join(", ")).bright_blue()
+                };
+                println!("{:>9}  {}  {}", size, file.path, used_by);
+            }
+        }
+        AttachmentsAction::Gc => {
+            let orphaned: Vec<_> = files.iter().filter(|file| file.is_orphaned()).collect();
+            if orphaned.is_empty() {
+                println!("{}", "No orphaned attachments".green());
+                return Ok(());
+            }
+            for file in &orphaned {
+                println!("  {}", file.path);
+            }
+            let total: u64 = orphaned.iter().filter_map(|file| file.size).sum();
+            if confirm(&format!(
+                "Delete {} orphaned attachments ({})?",
+                orphaned.len(),
+                format_size(total)
+            )) {
+                let freed = remove_orphaned(db_path, &files)?;
+                println!(
+                    "{}",
+                    format!(
+                        "Deleted {} attachments, freed {}",
+                        orphaned.len(),
+                        format_size(freed)
+                    )
+                    .green()
+                );
+            } else {
+                println!("{}", "Deletion cancelled".yellow());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// After entries are deleted for good, point out the attachments left behind
+fn report_orphaned_attachments(journal: &Journal, db_path: &std::path::Path) -> Result<()> {
+    let files =
+        crate::attachments::list_attachment_files(db_path, &journal.list_attachment_references()?)?;
+    let orphaned = files.iter().filter(|file| file.is_orphaned()).count();
+    if orphaned > 0 {
+        println!(
+            "{}",
+            format!(
+                "{} attachments are no longer used; remove them with `cl attachments gc`",
+                orphaned
+            )
+            .bright_black()
+        );
+    }
+    Ok(())
+}
+
+fn handle_trash_command(
+    journal: &Journal,
+    config: &Config,
+    db_path: &std::path::Path,
+    action: TrashAction,
+) -> Result<()> {
+    match action {
+        TrashAction::List => {
+            let entries = journal.list_trashed_entries()?;
+            if entries.is_empty() {
+                println!("{}", "The trash is empty".yellow());
+                return Ok(());
+            }
+
+            let tz = config.display.timezone.as_deref();
+            for entry in &entries {
+                let deleted = entry
+                    .deleted_at
+                    .map(|deleted_at| to_local_dt(&deleted_at, tz).format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "{} {}",
+                    format_entry_summary(entry, config.display.stardate_mode, tz, false),
+                    format!("(deleted {})", deleted).bright_black()
+                );
+            }
+        }
+        TrashAction::Restore { id } => {
+            if journal.restore_entry(id)? {
+                println!("{}", format!("Entry {} restored", id).green());
+            } else {
+                println!("{}", format!("Entry {} is not in the trash", id).red());
+            }
+        }
+        TrashAction::Empty { older_than } => {
+            let deleted_before = older_than
+                .as_deref()
+                .map(parse_age)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid --older-than: {}", e))?
+                .map(|age| Utc::now() - age);
+            let count = journal
+                .list_trashed_entries()?
+                .iter()
+                .filter(|entry| {
+                    deleted_before.is_none_or(|before| entry.deleted_at.is_some_and(|d| d < before))
+                })
+                .count();
+            if count == 0 {
+                println!("{}", "Nothing to delete in the trash".yellow());
+                return Ok(());
+            }
+
+            if confirm(&format!("Permanently delete {} trashed entries?", count)) {
+                let deleted = journal.empty_trash(deleted_before)?;
+                println!(
+                    "{}",
+                    format!("Permanently deleted {} entries", deleted).green()
+                );
+                report_orphaned_attachments(journal, db_path)?;
+            } else {
+                println!("{}", "Deletion cancelled".yellow());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Entries shown in the preview of a bulk move before the rest are summarized
+const MOVE_PREVIEW_ENTRIES: usize = 10;
+
+/// Move the entries matching `filter` to journal `to` after previewing them
+fn move_filtered_entries(
+    journal: &Journal,
+    config: &Config,
+    filter: &EntryFilter,
+    to: &str,
+) -> Result<()> {
+    if filter.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Say which entries to move with --journal, --since, --until or --tag"
+        ));
+    }
+    if filter.journal == Some(to) {
+        return Err(anyhow::anyhow!(
+            "Cannot move the entries of '{}' to itself",
+            to
+        ));
+    }
+
+    let entries: Vec<Entry> = journal
+        .list_entries_filtered_with_order(filter, "timestamp", "ASC")?
+        .into_iter()
+        .filter(|entry| entry.journal != to)
+        .collect();
+    if entries.is_empty() {
+        println!("{}", "No entries to move".yellow());
+        return Ok(());
+    }
+
+    let tz = config.display.timezone.as_deref();
+    println!(
+        "{} {} will be moved to {}:",
+        entries.len().to_string().yellow().bold(),
+        if entries.len() == 1 {
+            "entry"
+        } else {
+            "entries"
+        },
+        styled_journal_name(to)
+    );
+    for entry in entries.iter().take(MOVE_PREVIEW_ENTRIES) {
+        println!(
+            "  {}",
+            format_entry_summary(entry, config.display.stardate_mode, tz, false)
+        );
+    }
+    if entries.len() > MOVE_PREVIEW_ENTRIES {
+        println!(
+            "  {}",
+            format!("... and {} more", entries.len() - MOVE_PREVIEW_ENTRIES).bright_black()
+        );
+    }
+    println!();
+
+    if confirm(&format!("Move {} entries to '{}'?", entries.len(), to)) {
+        let moved = journal.move_entries(filter, to)?;
+        println!("{}", format!("Moved {} entries to '{}'", moved, to).green());
+    } else {
+        println!("{}", "Move cancelled".yellow());
+    }
+    Ok(())
+}
+
+/// Archive or unarchive a journal, keeping its other settings
+fn set_journal_archived(journal: &Journal, name: &str, archived: bool) -> Result<()> {
+    let filter = EntryFilter {
+        journal: Some(name),
+        ..Default::default()
+    };
+    let info = journal.get_journal_info(name)?;
+    if info.is_none() && journal.count_entries(&filter)? == 0 {
+        println!("{}", format!("Journal '{}' not found", name).red());
+        return Ok(());
+    }
+
+    let mut info = info.unwrap_or(JournalInfo {
+        name: name.to_string(),
+        ..Default::default()
+    });
+    let state = if archived { "archived" } else { "not archived" };
+    if info.archived == archived {
+        println!(
+            "{}",
+            format!("Journal '{}' is already {}", name, state).yellow()
+        );
+        return Ok(());
+    }
+
+    info.archived = archived;
+    journal.set_journal_info(&info)?;
+    println!("{}", format!("Journal '{}' is now {}", name, state).green());
+    Ok(())
+}
+
+fn handle_template_command(
+    journal: &Journal,
+    action: TemplateAction,
+    global_journal: Option<&str>,
+) -> Result<()> {
+    match action {
+        TemplateAction::List => {
+            let names = template::list_templates()?;
+            if names.is_empty() {
+                println!(
+                    "{}",
+                    format!(
+                        "No templates found in {}",
+                        Config::get_templates_dir()?.display()
+                    )
+                    .yellow()
+                );
+            } else {
+                for name in names {
+                    println!("{}", name);
+                }
+            }
+        }
+        TemplateAction::Apply {
+            name,
+            date,
+            journal: template_journal,
+        } => {
+            let date =
+                parse_relative_date(&date).map_err(|e| anyhow::anyhow!("Invalid date: {}", e))?;
+            let rendered = template::render_template(&template::load_template(&name)?, date);
+            let (title, content) = parse_title_and_content(&rendered, false);
+
+            // Keep the current time of day so that several entries on the same date stay ordered
+            let local = date.and_time(Local::now().time());
+            let timestamp = Local
+                .from_local_datetime(&local)
+                .earliest()
+                .context("Invalid local time for the entry date")?
+                .naive_utc();
+
+            let journal_category = template_journal.as_deref().or(global_journal);
+            let id = journal.create_entry_with_timestamp(
+                title,
+                &content,
+                journal_category,
+                timestamp,
+            )?;
+            println!(
+                "{}",
+                format!("Entry {} created from template '{}'", id, name).green()
+            );
+        }
+    }
 
     Ok(())
 }
@@ -750,6 +3424,23 @@ fn handle_config_command(action: Option<ConfigAction>, config: &Config) -> Resul
             } else {
                 println!("  path: {} (default)", "auto".bright_black());
             }
+            println!("  journal_mode: {}", config.database.journal_mode.green());
+            println!(
+                "  busy_timeout_ms: {}",
+                config.database.busy_timeout_ms.to_string().green()
+            );
+            println!(
+                "  foreign_keys: {}",
+                config.database.foreign_keys.to_string().green()
+            );
+            println!(
+                "  keychain: {}",
+                config.database.keychain.to_string().green()
+            );
+            println!(
+                "  unlock_minutes: {}",
+                config.database.unlock_minutes.to_string().green()
+            );
 
             println!();
             println!("{}", "Editor:".yellow().bold());
@@ -761,215 +3452,846 @@ fn handle_config_command(action: Option<ConfigAction>, config: &Config) -> Resul
                     "auto".bright_black()
                 );
             }
-
-            println!();
-            println!("{}", "Display:".yellow().bold());
+
+            println!();
+            println!("{}", "Picker:".yellow().bold());
+            if let Some(command) = &config.picker.command {
+                println!("  command: {}", command.green());
+            } else {
+                println!("  command: {} (fzf)", "auto".bright_black());
+            }
+
+            println!();
+            println!("{}", "Display:".yellow().bold());
+            println!(
+                "  colors_enabled: {}",
+                config.display.colors_enabled.to_string().green()
+            );
+            println!("  date_format: {}", config.display.date_format.green());
+            println!(
+                "  stardate_mode: {}",
+                config.display.stardate_mode.to_string().green()
+            );
+            println!(
+                "  age_fade: {}",
+                config.display.age_fade.to_string().green()
+            );
+            println!("  compact: {}", config.display.compact.to_string().green());
+            println!(
+                "  default_sort: {} {}",
+                config.display.default_sort.green(),
+                "(timestamp = entry date, created = when the entry was added)".bright_black()
+            );
+            if let Some(entries_per_page) = config.display.entries_per_page {
+                println!(
+                    "  entries_per_page: {}",
+                    entries_per_page.to_string().green()
+                );
+            } else {
+                println!("  entries_per_page: {} (no limit)", "auto".bright_black());
+            }
+            if let Some(tz) = &config.display.timezone {
+                println!("  timezone: {}", tz.green());
+            } else {
+                println!("  timezone: {} (system local time)", "auto".bright_black());
+            }
+
+            println!();
+            println!("{}", "Journal:".yellow().bold());
+            println!(
+                "  title_from_first_line: {}",
+                config.journal.title_from_first_line.to_string().green()
+            );
+
+            println!();
+            println!("{}", "Backup:".yellow().bold());
+            if let Some(dir) = &config.backup.dir {
+                println!("  dir: {}", dir.green());
+            } else {
+                println!(
+                    "  dir: {} (backups/ next to the database)",
+                    "auto".bright_black()
+                );
+            }
+            println!("  keep: {}", config.backup.keep.to_string().green());
+            if let Some(days) = config.backup.keep_days {
+                println!("  keep_days: {}", days.to_string().green());
+            } else {
+                println!("  keep_days: {} (no age limit)", "none".bright_black());
+            }
+
+            println!();
+            println!("{}", "Encryption:".yellow().bold());
+            match &config.encryption.recipient {
+                Some(recipient) => println!("  recipient: {}", recipient.green()),
+                None => println!("  recipient: {}", "none".bright_black()),
+            }
+            match &config.encryption.identity {
+                Some(identity) => println!("  identity: {}", identity.green()),
+                None => println!("  identity: {}", "none".bright_black()),
+            }
+
+            println!();
+            println!("{}", "Saved searches:".yellow().bold());
+            if config.search.is_empty() {
+                println!("  {}", "none".bright_black());
+            }
+            for (name, args) in &config.search {
+                println!("  {}: {}", name, args.green());
+            }
+
+            println!();
+            println!("{}", "Audio:".yellow().bold());
+            if let Some(whisper_command) = &config.audio.whisper_command {
+                println!("  whisper_command: {}", whisper_command.green());
+            } else {
+                println!("  whisper_command: {} (auto-detect)", "auto".bright_black());
+            }
+            println!("  whisper_model: {}", config.audio.whisper_model.green());
+            if let Some(recording_tool) = &config.audio.recording_tool {
+                println!("  recording_tool: {}", recording_tool.green());
+            } else {
+                println!("  recording_tool: {} (auto-detect)", "auto".bright_black());
+            }
+            if let Some(playback_tool) = &config.audio.playback_tool {
+                println!("  playback_tool: {}", playback_tool.green());
+            } else {
+                println!("  playback_tool: {} (auto-detect)", "auto".bright_black());
+            }
+            println!(
+                "  max_recording_seconds: {}",
+                config.audio.max_recording_seconds.to_string().green()
+            );
+            println!(
+                "  sample_rate: {}",
+                config.audio.sample_rate.to_string().green()
+            );
+        }
+        Some(ConfigAction::Set { key, value }) => {
+            let mut new_config = config.clone();
+
+            set_config_value(&mut new_config, &key, value)?;
+
+            new_config.save()?;
+            println!(
+                "{}",
+                "Configuration saved successfully".bright_green().bold()
+            );
+        }
+        Some(ConfigAction::Unset { key }) => {
+            let mut new_config = config.clone();
+            new_config.unset(&key)?;
+            new_config.save()?;
+            if let Some(name) = key.strip_prefix("search.") {
+                println!("{}", format!("Removed saved search '{}'", name).green());
+            } else {
+                println!("{}", format!("Reset {} to its default", key).green());
+            }
+        }
+        Some(ConfigAction::Reset) => {
+            let config_path = Config::get_config_path()?;
+            if !confirm(&format!(
+                "Reset {} to the defaults (including the database path and saved searches)?",
+                config_path.display()
+            )) {
+                println!("{}", "Reset cancelled.".yellow());
+                return Ok(());
+            }
+            let mut backup_name = config_path.as_os_str().to_os_string();
+            backup_name.push(".bak");
+            let backup = std::path::PathBuf::from(backup_name);
+            let backed_up = config_path.exists();
+            if backed_up {
+                fs::copy(&config_path, &backup)
+                    .with_context(|| format!("Failed to copy the config to {:?}", backup))?;
+            }
+            Config::default().save()?;
+            println!("{}", "Configuration reset to the defaults".green());
+            if backed_up {
+                println!(
+                    "{}",
+                    format!("The previous configuration is kept at {}", backup.display())
+                        .bright_black()
+                );
+            }
+        }
+        Some(ConfigAction::Path) => {
+            let config_path = Config::get_config_path()?;
+            println!("{}", config_path.display());
+        }
+        Some(ConfigAction::Wizard) => {
+            run_config_wizard(config)?;
+        }
+        Some(ConfigAction::Edit) => {
+            edit_config_file(config)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate `value` and store it under the dotted `key` (e.g. `display.timezone`)
+fn set_config_value(config: &mut Config, key: &str, value: String) -> Result<()> {
+    match key {
+        "database.path" => {
+            config.database.path = Some(value);
+            println!(
+                "{}",
+                format!(
+                    "Set database.path to '{}'",
+                    config.database.path.as_ref().unwrap()
+                )
+                .green()
+            );
+        }
+        "database.journal_mode" => {
+            let mode = value.to_lowercase();
+            if !JOURNAL_MODES.contains(&mode.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "database.journal_mode must be one of: {}",
+                    JOURNAL_MODES.join(", ")
+                ));
+            }
+            println!(
+                "{}",
+                format!("Set database.journal_mode to {}", mode).green()
+            );
+            config.database.journal_mode = mode;
+        }
+        "database.busy_timeout_ms" => {
+            let timeout: u64 = value
+                .parse()
+                .context("database.busy_timeout_ms must be a number of milliseconds")?;
+            config.database.busy_timeout_ms = timeout;
+            println!(
+                "{}",
+                format!("Set database.busy_timeout_ms to {}", timeout).green()
+            );
+        }
+        "database.foreign_keys" => {
+            let enabled: bool = value
+                .parse()
+                .context("database.foreign_keys must be 'true' or 'false'")?;
+            config.database.foreign_keys = enabled;
+            println!(
+                "{}",
+                format!("Set database.foreign_keys to {}", enabled).green()
+            );
+        }
+        "database.keychain" => {
+            let enabled: bool = value
+                .parse()
+                .context("database.keychain must be 'true' or 'false'")?;
+            if enabled {
+                keychain::check_keychain()?;
+            } else if keychain::delete_passphrase(&config.get_database_path()?)? {
+                println!("{}", "Passphrase removed from the keychain".green());
+            }
+            config.database.keychain = enabled;
+            println!(
+                "{}",
+                format!("Set database.keychain to {}", enabled).green()
+            );
+        }
+        "database.unlock_minutes" => {
+            let minutes: u64 = value
+                .parse()
+                .context("database.unlock_minutes must be a number")?;
+            if minutes == 0 {
+                return Err(anyhow::anyhow!(
+                    "database.unlock_minutes must be at least 1"
+                ));
+            }
+            config.database.unlock_minutes = minutes;
+            println!(
+                "{}",
+                format!("Set database.unlock_minutes to {}", minutes).green()
+            );
+        }
+        "editor.command" => {
+            config.editor.command = Some(value);
+            println!(
+                "{}",
+                format!(
+                    "Set editor.command to '{}'",
+                    config.editor.command.as_ref().unwrap()
+                )
+                .green()
+            );
+        }
+        "picker.command" => {
+            if value == "auto" || value.is_empty() {
+                config.picker.command = None;
+                println!("{}", "Set picker.command to auto (fzf)".green());
+            } else {
+                config.picker.command = Some(value.clone());
+                println!("{}", format!("Set picker.command to '{}'", value).green());
+            }
+        }
+        "display.colors_enabled" => {
+            let enabled: bool = value
+                .parse()
+                .context("display.colors_enabled must be 'true' or 'false'")?;
+            config.display.colors_enabled = enabled;
             println!(
-                "  colors_enabled: {}",
-                config.display.colors_enabled.to_string().green()
+                "{}",
+                format!("Set display.colors_enabled to {}", enabled).green()
             );
-            println!("  date_format: {}", config.display.date_format.green());
+        }
+        "display.date_format" => {
+            config.display.date_format = value;
             println!(
-                "  stardate_mode: {}",
-                config.display.stardate_mode.to_string().green()
+                "{}",
+                format!(
+                    "Set display.date_format to '{}'",
+                    config.display.date_format
+                )
+                .green()
             );
-            if let Some(entries_per_page) = config.display.entries_per_page {
+        }
+        "display.stardate_mode" => {
+            let enabled: bool = value
+                .parse()
+                .context("display.stardate_mode must be 'true' or 'false'")?;
+            config.display.stardate_mode = enabled;
+            println!(
+                "{}",
+                format!("Set display.stardate_mode to {}", enabled).green()
+            );
+        }
+        "display.age_fade" => {
+            let enabled: bool = value
+                .parse()
+                .context("display.age_fade must be 'true' or 'false'")?;
+            config.display.age_fade = enabled;
+            println!("{}", format!("Set display.age_fade to {}", enabled).green());
+        }
+        "display.compact" => {
+            let enabled: bool = value
+                .parse()
+                .context("display.compact must be 'true' or 'false'")?;
+            config.display.compact = enabled;
+            println!("{}", format!("Set display.compact to {}", enabled).green());
+        }
+        "display.default_sort" => {
+            if value != "timestamp" && value != "created" {
+                return Err(anyhow::anyhow!(
+                    "display.default_sort must be 'timestamp' or 'created'"
+                ));
+            }
+            config.display.default_sort = value.clone();
+            println!(
+                "{}",
+                format!("Set display.default_sort to '{}'", value).green()
+            );
+        }
+        "display.entries_per_page" => {
+            if value == "auto" || value == "none" {
+                config.display.entries_per_page = None;
                 println!(
-                    "  entries_per_page: {}",
-                    entries_per_page.to_string().green()
+                    "{}",
+                    "Set display.entries_per_page to auto (no limit)".green()
                 );
             } else {
-                println!("  entries_per_page: {} (no limit)", "auto".bright_black());
+                let per_page: usize = value
+                    .parse()
+                    .context("display.entries_per_page must be a number or 'auto'")?;
+                config.display.entries_per_page = Some(per_page);
+                println!(
+                    "{}",
+                    format!("Set display.entries_per_page to {}", per_page).green()
+                );
             }
-            if let Some(tz) = &config.display.timezone {
-                println!("  timezone: {}", tz.green());
+        }
+        "display.timezone" => {
+            if value == "auto" || value == "none" || value.is_empty() {
+                config.display.timezone = None;
+                println!(
+                    "{}",
+                    "Set display.timezone to auto (system local time)".green()
+                );
             } else {
-                println!("  timezone: {} (system local time)", "auto".bright_black());
+                // Validate the timezone name
+                value.parse::<chrono_tz::Tz>().map_err(|_| {
+                    anyhow::anyhow!(
+                        "Unknown timezone '{}'. Use an IANA timezone name like 'Europe/Rome'",
+                        value
+                    )
+                })?;
+                config.display.timezone = Some(value.clone());
+                println!("{}", format!("Set display.timezone to '{}'", value).green());
             }
-
-            println!();
-            println!("{}", "Audio:".yellow().bold());
-            if let Some(whisper_command) = &config.audio.whisper_command {
-                println!("  whisper_command: {}", whisper_command.green());
+        }
+        "journal.title_from_first_line" => {
+            let enabled: bool = value
+                .parse()
+                .context("journal.title_from_first_line must be 'true' or 'false'")?;
+            config.journal.title_from_first_line = enabled;
+            println!(
+                "{}",
+                format!("Set journal.title_from_first_line to {}", enabled).green()
+            );
+        }
+        "backup.dir" => {
+            if value == "auto" || value.is_empty() {
+                config.backup.dir = None;
+                println!(
+                    "{}",
+                    "Set backup.dir to auto (backups/ next to the database)".green()
+                );
             } else {
-                println!("  whisper_command: {} (auto-detect)", "auto".bright_black());
+                config.backup.dir = Some(value.clone());
+                println!("{}", format!("Set backup.dir to '{}'", value).green());
             }
-            println!("  whisper_model: {}", config.audio.whisper_model.green());
-            if let Some(recording_tool) = &config.audio.recording_tool {
-                println!("  recording_tool: {}", recording_tool.green());
+        }
+        "backup.keep" => {
+            let keep: usize = value.parse().context("backup.keep must be a number")?;
+            if keep == 0 {
+                return Err(anyhow::anyhow!("backup.keep must be at least 1"));
+            }
+            config.backup.keep = keep;
+            println!("{}", format!("Set backup.keep to {}", keep).green());
+        }
+        "backup.keep_days" => {
+            if value == "none" || value.is_empty() {
+                config.backup.keep_days = None;
+                println!("{}", "Set backup.keep_days to none (no age limit)".green());
             } else {
-                println!("  recording_tool: {} (auto-detect)", "auto".bright_black());
+                let days: u64 = value
+                    .parse()
+                    .context("backup.keep_days must be a number or 'none'")?;
+                config.backup.keep_days = Some(days);
+                println!("{}", format!("Set backup.keep_days to {}", days).green());
             }
-            if let Some(playback_tool) = &config.audio.playback_tool {
-                println!("  playback_tool: {}", playback_tool.green());
+        }
+        "encryption.recipient" | "encryption.identity" => {
+            let setting = if key == "encryption.recipient" {
+                &mut config.encryption.recipient
             } else {
-                println!("  playback_tool: {} (auto-detect)", "auto".bright_black());
+                &mut config.encryption.identity
+            };
+            if value == "none" || value.is_empty() {
+                *setting = None;
+                println!("{}", format!("Removed {}", key).green());
+            } else {
+                *setting = Some(value.clone());
+                println!("{}", format!("Set {} to '{}'", key, value).green());
             }
+        }
+        "audio.whisper_command" => {
+            config.audio.whisper_command = Some(value.clone());
             println!(
-                "  max_recording_seconds: {}",
-                config.audio.max_recording_seconds.to_string().green()
+                "{}",
+                format!("Set audio.whisper_command to '{}'", value).green()
             );
+        }
+        "audio.whisper_model" => {
+            config.audio.whisper_model = value.clone();
             println!(
-                "  sample_rate: {}",
-                config.audio.sample_rate.to_string().green()
+                "{}",
+                format!("Set audio.whisper_model to '{}'", value).green()
             );
         }
-        Some(ConfigAction::Set { key, value }) => {
-            let mut new_config = config.clone();
+        "audio.recording_tool" => {
+            config.audio.recording_tool = Some(value.clone());
+            println!(
+                "{}",
+                format!("Set audio.recording_tool to '{}'", value).green()
+            );
+        }
+        "audio.playback_tool" => {
+            config.audio.playback_tool = Some(value.clone());
+            println!(
+                "{}",
+                format!("Set audio.playback_tool to '{}'", value).green()
+            );
+        }
+        "audio.max_recording_seconds" => {
+            let seconds: u64 = value
+                .parse()
+                .context("audio.max_recording_seconds must be a number")?;
+            config.audio.max_recording_seconds = seconds;
+            println!(
+                "{}",
+                format!("Set audio.max_recording_seconds to {}", seconds).green()
+            );
+        }
+        "audio.sample_rate" => {
+            let rate: u32 = value
+                .parse()
+                .context("audio.sample_rate must be a number")?;
+            config.audio.sample_rate = rate;
+            println!("{}", format!("Set audio.sample_rate to {}", rate).green());
+        }
+        _ if key.starts_with("search.") => {
+            let name = &key["search.".len()..];
+            if name.is_empty() {
+                return Err(anyhow::anyhow!("Saved search name cannot be empty"));
+            }
+            if value.is_empty() {
+                config.search.remove(name);
+                println!("{}", format!("Removed saved search '{}'", name).green());
+            } else {
+                // Validate now rather than when the search is first used
+                SavedSearch::from_args(&value)?;
+                println!(
+                    "{}",
+                    format!("Set saved search '{}' to '{}'", name, value).green()
+                );
+                config.search.insert(name.to_string(), value);
+            }
+        }
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unknown configuration key '{}'. Available keys: database.path, database.journal_mode, database.busy_timeout_ms, database.foreign_keys, database.keychain, database.unlock_minutes, editor.command, picker.command, display.colors_enabled, display.date_format, display.stardate_mode, display.age_fade, display.compact, display.default_sort, display.entries_per_page, display.timezone, journal.title_from_first_line, backup.dir, backup.keep, backup.keep_days, encryption.recipient, encryption.identity, audio.whisper_command, audio.whisper_model, audio.recording_tool, audio.playback_tool, audio.max_recording_seconds, audio.sample_rate, search.<name>",
+                key
+            ));
+        }
+    }
 
-            match key.as_str() {
-                "database.path" => {
-                    new_config.database.path = Some(value);
-                    println!(
-                        "{}",
-                        format!(
-                            "Set database.path to '{}'",
-                            new_config.database.path.as_ref().unwrap()
-                        )
-                        .green()
-                    );
-                }
-                "editor.command" => {
-                    new_config.editor.command = Some(value);
-                    println!(
-                        "{}",
-                        format!(
-                            "Set editor.command to '{}'",
-                            new_config.editor.command.as_ref().unwrap()
-                        )
-                        .green()
-                    );
-                }
-                "display.colors_enabled" => {
-                    let enabled: bool = value
-                        .parse()
-                        .context("display.colors_enabled must be 'true' or 'false'")?;
-                    new_config.display.colors_enabled = enabled;
-                    println!(
-                        "{}",
-                        format!("Set display.colors_enabled to {}", enabled).green()
-                    );
-                }
-                "display.date_format" => {
-                    new_config.display.date_format = value;
-                    println!(
-                        "{}",
-                        format!(
-                            "Set display.date_format to '{}'",
-                            new_config.display.date_format
-                        )
-                        .green()
-                    );
-                }
-                "display.stardate_mode" => {
-                    let enabled: bool = value
-                        .parse()
-                        .context("display.stardate_mode must be 'true' or 'false'")?;
-                    new_config.display.stardate_mode = enabled;
-                    println!(
-                        "{}",
-                        format!("Set display.stardate_mode to {}", enabled).green()
-                    );
-                }
-                "display.entries_per_page" => {
-                    if value == "auto" || value == "none" {
-                        new_config.display.entries_per_page = None;
-                        println!(
-                            "{}",
-                            "Set display.entries_per_page to auto (no limit)".green()
-                        );
-                    } else {
-                        let per_page: usize = value
-                            .parse()
-                            .context("display.entries_per_page must be a number or 'auto'")?;
-                        new_config.display.entries_per_page = Some(per_page);
-                        println!(
-                            "{}",
-                            format!("Set display.entries_per_page to {}", per_page).green()
-                        );
-                    }
-                }
-                "display.timezone" => {
-                    if value == "auto" || value == "none" || value.is_empty() {
-                        new_config.display.timezone = None;
-                        println!(
-                            "{}",
-                            "Set display.timezone to auto (system local time)".green()
-                        );
-                    } else {
-                        // Validate the timezone name
-                        value.parse::<chrono_tz::Tz>()
-                            .map_err(|_| anyhow::anyhow!("Unknown timezone '{}'. Use an IANA timezone name like 'Europe/Rome'", value))?;
-                        new_config.display.timezone = Some(value.clone());
-                        println!("{}", format!("Set display.timezone to '{}'", value).green());
-                    }
-                }
-                "audio.whisper_command" => {
-                    new_config.audio.whisper_command = Some(value.clone());
-                    println!(
-                        "{}",
-                        format!("Set audio.whisper_command to '{}'", value).green()
-                    );
-                }
-                "audio.whisper_model" => {
-                    new_config.audio.whisper_model = value.clone();
-                    println!(
-                        "{}",
-                        format!("Set audio.whisper_model to '{}'", value).green()
-                    );
-                }
-                "audio.recording_tool" => {
-                    new_config.audio.recording_tool = Some(value.clone());
-                    println!(
-                        "{}",
-                        format!("Set audio.recording_tool to '{}'", value).green()
-                    );
-                }
-                "audio.playback_tool" => {
-                    new_config.audio.playback_tool = Some(value.clone());
-                    println!(
-                        "{}",
-                        format!("Set audio.playback_tool to '{}'", value).green()
-                    );
-                }
-                "audio.max_recording_seconds" => {
-                    let seconds: u64 = value
-                        .parse()
-                        .context("audio.max_recording_seconds must be a number")?;
-                    new_config.audio.max_recording_seconds = seconds;
-                    println!(
-                        "{}",
-                        format!("Set audio.max_recording_seconds to {}", seconds).green()
-                    );
+    Ok(())
+}
+
+/// A `config wizard` setting: key, prompt, and how to read the current value
+type WizardSetting = (&'static str, &'static str, fn(&Config) -> String);
+
+/// Settings offered by `config wizard`
+const WIZARD_SETTINGS: &[WizardSetting] = &[
+    ("editor.command", "Editor command", |c| {
+        c.get_editor_command()
+    }),
+    (
+        "display.colors_enabled",
+        "Enable colors (true/false)",
+        |c| c.display.colors_enabled.to_string(),
+    ),
+    ("display.date_format", "Date format", |c| {
+        c.display.date_format.clone()
+    }),
+    (
+        "display.stardate_mode",
+        "Show stardates (true/false)",
+        |c| c.display.stardate_mode.to_string(),
+    ),
+    ("display.timezone", "Timezone (IANA name or 'auto')", |c| {
+        c.display
+            .timezone
+            .clone()
+            .unwrap_or_else(|| "auto".to_string())
+    }),
+];
+
+/// Edit a copy of the config file, replacing the file only once the copy parses. Parse
+/// errors (with their line and column) are shown and the copy can be edited again.
+fn edit_config_file(config: &Config) -> Result<()> {
+    let config_path = Config::get_config_path()?;
+    if !config_path.exists() {
+        config.save()?;
+    }
+    let original = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
+
+    // Same extension, so the editor highlights it and it is parsed the same way
+    let extension = config_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("toml");
+    let temp_file = env::temp_dir().join(format!(
+        "captains-log-config-{}.{}",
+        Local::now().format("%Y%m%d_%H%M%S"),
+        extension
+    ));
+    fs::write(&temp_file, &original)?;
+
+    let result = save_edited_config(config, &config_path, &temp_file, &original);
+    finish_with_temp_file(&temp_file, &original, result)
+}
+
+/// Open the editor on `temp_file` until it holds a valid config, then save it
+fn save_edited_config(
+    config: &Config,
+    config_path: &std::path::Path,
+    temp_file: &std::path::Path,
+    original: &str,
+) -> Result<()> {
+    loop {
+        open_editor(config, temp_file)?;
+        let edited = fs::read_to_string(temp_file)?;
+        if edited == original {
+            println!("{}", "No changes".yellow());
+            return Ok(());
+        }
+
+        match Config::load_from(temp_file) {
+            Ok(new_config) => {
+                fs::write(config_path, &edited)
+                    .with_context(|| format!("Failed to write config file at {:?}", config_path))?;
+                println!(
+                    "{}",
+                    format!("Configuration saved to {}", config_path.display()).green()
+                );
+                let problems: Vec<_> = doctor::check_config(&new_config)
+                    .into_iter()
+                    .filter(|check| check.status != doctor::Status::Ok)
+                    .collect();
+                doctor::print_checks(&problems);
+                return Ok(());
+            }
+            Err(e) => {
+                println!("{}", format!("Invalid configuration: {:#}", e).red());
+                if !std::io::stdin().is_terminal() || !confirm_default_yes("Edit it again?") {
+                    return Err(anyhow::anyhow!(
+                        "The configuration was not saved; {} is unchanged",
+                        config_path.display()
+                    ));
                 }
-                "audio.sample_rate" => {
-                    let rate: u32 = value
-                        .parse()
-                        .context("audio.sample_rate must be a number")?;
-                    new_config.audio.sample_rate = rate;
-                    println!("{}", format!("Set audio.sample_rate to {}", rate).green());
+            }
+        }
+    }
+}
+
+/// Interactively prompt for the most common settings, keeping the current value on
+/// an empty answer, and save the result
+fn run_config_wizard(config: &Config) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "The configuration wizard needs a terminal. Use `config set <key> <value>` instead"
+        ));
+    }
+
+    println!("{}", "Configuration wizard".cyan().bold());
+    println!(
+        "{}",
+        "Press Enter to keep the current value shown in brackets.".bright_black()
+    );
+    println!();
+
+    let mut new_config = config.clone();
+    for (key, prompt, current) in WIZARD_SETTINGS {
+        loop {
+            print!(
+                "{} [{}]: ",
+                prompt.cyan().bold(),
+                current(&new_config).green()
+            );
+            std::io::Write::flush(&mut std::io::stdout())?;
+
+            let mut input = String::new();
+            if std::io::stdin().read_line(&mut input)? == 0 {
+                return Err(anyhow::anyhow!("Configuration wizard aborted"));
+            }
+            let input = input.trim();
+            if input.is_empty() {
+                break;
+            }
+
+            match set_config_value(&mut new_config, key, input.to_string()) {
+                Ok(()) => break,
+                Err(e) => println!("{}", format!("{:#}", e).red()),
+            }
+        }
+    }
+
+    new_config.save()?;
+    println!();
+    println!(
+        "{}",
+        "Configuration saved successfully".bright_green().bold()
+    );
+
+    Ok(())
+}
+
+/// Resolve an entry reference given on the command line to an entry ID.
+///
+/// A reference is either a numeric ID or `last`, the most recent entry (within
+/// `journal_filter` when a journal scope is active).
+fn resolve_entry_ref(
+    journal: &Journal,
+    entry_ref: &str,
+    journal_filter: Option<&str>,
+) -> Result<i64> {
+    let entry_ref = entry_ref.trim();
+
+    if entry_ref.eq_ignore_ascii_case("last") {
+        return match journal.get_last_entry(journal_filter)? {
+            Some(entry) => Ok(entry.id),
+            None => match journal_filter {
+                Some(name) => Err(anyhow::anyhow!("No entries yet in journal '{}'", name)),
+                None => Err(anyhow::anyhow!("No entries yet")),
+            },
+        };
+    }
+
+    entry_ref.parse::<i64>().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid entry reference '{}': expected a numeric ID or 'last'",
+            entry_ref
+        )
+    })
+}
+
+/// Auto-confirm all destructive operations (global `--yes` flag).
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+pub fn set_editor_check(enabled: bool) {
+    EDITOR_CHECK.store(enabled, Ordering::Relaxed);
+}
+
+/// Ask the user to confirm a destructive operation. Every destructive command
+/// should go through this helper.
+///
+/// Returns true without prompting when `--yes` was given. Without `--yes`, the
+/// operation is refused when stdin is not a terminal, so scripts never hang on a
+/// prompt and must opt in explicitly.
+pub fn confirm(prompt: &str) -> bool {
+    ask_yes_no(prompt, false)
+}
+
+/// Ask a non-destructive yes/no question where an empty answer means yes.
+/// Auto-confirmed with `--yes` or when stdin is not a terminal.
+pub fn confirm_default_yes(prompt: &str) -> bool {
+    ask_yes_no(prompt, true)
+}
+
+fn ask_yes_no(prompt: &str, default_yes: bool) -> bool {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    if !std::io::stdin().is_terminal() {
+        if !default_yes {
+            println!(
+                "{}",
+                "Refusing to continue: stdin is not a terminal (use --yes to confirm)".red()
+            );
+        }
+        return default_yes;
+    }
+
+    if default_yes {
+        print!("{}", format!("{} [Y/n]: ", prompt).cyan().bold());
+    } else {
+        print!("{}", format!("{} (y/N): ", prompt).red().bold());
+    }
+    if std::io::Write::flush(&mut std::io::stdout()).is_err() {
+        return false;
+    }
+
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+
+    match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => true,
+        "" => default_yes,
+        _ => false,
+    }
+}
+
+/// Read a passphrase from the terminal without echoing it
+pub fn read_passphrase(prompt: &str) -> Result<String> {
+    eprint!("{}", prompt.cyan().bold());
+    std::io::stderr().flush()?;
+
+    let mut input = String::new();
+    {
+        let _echo_off = EchoOff::new();
+        std::io::stdin().read_line(&mut input)?;
+    }
+    eprintln!();
+
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Passphrase of the encrypted database at `db_path`: from `CL_DB_KEY`, an unlocked
+/// session (`cl unlock`), the keychain (`database.keychain`), or prompted. A prompted
+/// passphrase that opens the database is saved in the keychain, replacing a stale one.
+pub fn database_passphrase(config: &Config, db_path: &std::path::Path) -> Result<String> {
+    if let Ok(key) = env::var(KEY_ENV) {
+        return Ok(key);
+    }
+    if let Some(key) = load_session(&session_dir(), db_path, Utc::now())? {
+        return Ok(key);
+    }
+    if config.database.keychain {
+        match keychain::get_passphrase(db_path) {
+            Ok(Some(key)) if check_key(db_path, &key)? => return Ok(key),
+            Ok(Some(_)) => eprintln!(
+                "{}",
+                "The passphrase in the keychain no longer opens the journal".yellow()
+            ),
+            Ok(None) => {}
+            Err(e) => eprintln!("{}", format!("Warning: {:#}", e).yellow()),
+        }
+    }
+
+    let key = read_passphrase(&format!("Passphrase for {}: ", db_path.display()))?;
+    if config.database.keychain && check_key(db_path, &key)? {
+        match keychain::store_passphrase(db_path, &key) {
+            Ok(()) => eprintln!("{}", "Passphrase saved in the keychain".green()),
+            Err(e) => eprintln!("{}", format!("Warning: {:#}", e).yellow()),
+        }
+    }
+    Ok(key)
+}
+
+/// Ask for a new passphrase twice, refusing empty or mismatched ones
+fn read_new_passphrase() -> Result<String> {
+    let passphrase = read_passphrase("New passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("The passphrase cannot be empty"));
+    }
+    if read_passphrase("Repeat the passphrase: ")? != passphrase {
+        return Err(anyhow::anyhow!("The passphrases don't match"));
+    }
+    Ok(passphrase)
+}
+
+/// Turns terminal echo off until dropped (nothing happens when stdin isn't a terminal)
+struct EchoOff {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl EchoOff {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+            // tcgetattr fails, leaving termios unset, when stdin isn't a terminal
+            unsafe {
+                if libc::tcgetattr(libc::STDIN_FILENO, termios.as_mut_ptr()) != 0 {
+                    return EchoOff { original: None };
                 }
-                _ => {
-                    return Err(anyhow::anyhow!(
-                        "Unknown configuration key '{}'. Available keys: database.path, editor.command, display.colors_enabled, display.date_format, display.stardate_mode, display.entries_per_page, display.timezone, audio.whisper_command, audio.whisper_model, audio.recording_tool, audio.playback_tool, audio.max_recording_seconds, audio.sample_rate",
-                        key
-                    ));
+                let original = termios.assume_init();
+                let mut silent = original;
+                silent.c_lflag &= !libc::ECHO;
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent);
+                EchoOff {
+                    original: Some(original),
                 }
             }
-
-            new_config.save()?;
-            println!(
-                "{}",
-                "Configuration saved successfully".bright_green().bold()
-            );
         }
-        Some(ConfigAction::Path) => {
-            let config_path = Config::get_config_path()?;
-            println!("{}", config_path.display());
+        #[cfg(not(unix))]
+        {
+            EchoOff {}
         }
     }
+}
 
-    Ok(())
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = &self.original {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+            }
+        }
+    }
 }
 
 /// Convert a UTC timestamp to the configured (or system local) timezone.
@@ -982,9 +4304,59 @@ fn to_local_dt(utc: &DateTime<Utc>, timezone: Option<&str>) -> DateTime<FixedOff
     utc.with_timezone(&Local).fixed_offset()
 }
 
-fn print_entry(entry: &Entry, stardate_mode: bool, timezone: Option<&str>) {
+/// Distinct days of `timestamps` in the configured (or system local) timezone, oldest first
+fn local_dates(timestamps: &[DateTime<Utc>], timezone: Option<&str>) -> Vec<NaiveDate> {
+    let mut dates: Vec<NaiveDate> = timestamps
+        .iter()
+        .map(|timestamp| to_local_dt(timestamp, timezone).date_naive())
+        .collect();
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+/// Entries written on `day` in the configured (or system local) timezone, oldest first
+fn list_entries_on_local_day(
+    journal: &Journal,
+    day: NaiveDate,
+    journal_filter: Option<&str>,
+    timezone: Option<&str>,
+    order_field: &str,
+) -> Result<Vec<Entry>> {
+    // SQLite dates are UTC, so a local day spans parts of the UTC days around it
+    let filter = EntryFilter {
+        since: day.pred_opt(),
+        until: day.succ_opt(),
+        journal: journal_filter,
+        ..Default::default()
+    };
+    let mut entries = Vec::new();
+    journal.for_each_entry(&filter, order_field, "ASC", |entry| {
+        if to_local_dt(&entry.timestamp, timezone).date_naive() == day {
+            entries.push(entry);
+        }
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+/// Whether `timestamp` falls on the month and day of `today`, in an earlier year
+fn is_anniversary(timestamp: &DateTime<Utc>, today: NaiveDate, timezone: Option<&str>) -> bool {
+    let date = to_local_dt(timestamp, timezone).date_naive();
+    date.month() == today.month() && date.day() == today.day() && date.year() < today.year()
+}
+
+/// Print a full entry. The `compact` layout draws a single thin separator and no blank
+/// lines, so that several entries read back-to-back stay dense.
+fn print_entry(entry: &Entry, stardate_mode: bool, timezone: Option<&str>, compact: bool) {
     let width = get_wrap_width();
-    println!("{}", "─".repeat(width as usize).bright_blue());
+    let separator = if compact {
+        "┄".repeat(width as usize).bright_black()
+    } else {
+        "─".repeat(width as usize).bright_blue()
+    };
+
+    println!("{}", separator);
     println!(
         "{}: {}",
         "ID".cyan().bold(),
@@ -1009,30 +4381,72 @@ fn print_entry(entry: &Entry, stardate_mode: bool, timezone: Option<&str>) {
     println!(
         "{}: {}",
         "Journal".cyan().bold(),
-        entry.journal.magenta().bold()
+        styled_journal_name(&entry.journal)
     );
     if let Some(title) = &entry.title {
         println!("{}: {}", "Title".cyan().bold(), title.green().bold());
     }
+    if entry.is_draft() {
+        println!("{}: {}", "Status".cyan().bold(), STATUS_DRAFT.yellow());
+    }
+    if entry.starred {
+        println!("{}: ⭐", "Starred".cyan().bold());
+    }
 
     // Display audio info if available
     if let Some(audio_path) = &entry.audio_path {
         println!("{}: {}", "Audio".cyan().bold(), audio_path.green());
     }
+    if !entry.image_paths.is_empty() {
+        println!(
+            "{}: {}",
+            "Images".cyan().bold(),
+            entry.image_paths.len().to_string().green()
+        );
+        for (n, path) in entry.image_paths.iter().enumerate() {
+            println!("  {}. {}", n + 1, path.green());
+        }
+    }
 
-    let content = render_markdown(&entry.content);
+    let content = if entry.is_encrypted() {
+        match decrypt_entry_content(entry) {
+            Ok(plaintext) => render_markdown(&plaintext),
+            Err(e) => format!("🔒 {}", format!("Encrypted: {:#}", e).yellow()),
+        }
+    } else {
+        render_markdown(&entry.content)
+    };
     let wrapped_content = wrap_text(&content, width);
 
-    println!("{}", "─".repeat(width as usize).bright_blue());
+    if compact {
+        println!("{}", wrapped_content);
+        return;
+    }
+
+    println!("{}", separator);
     println!();
     println!("{}", wrapped_content);
     println!();
-    println!("{}", "─".repeat(width as usize).bright_blue());
+    println!("{}", separator);
+}
+
+/// Print bare entry IDs, one per line, for piping into other commands
+fn print_entry_ids(entries: &[Entry]) {
+    for entry in entries {
+        println!("{}", entry.id);
+    }
 }
 
-fn format_entry_summary(entry: &Entry, stardate_mode: bool, timezone: Option<&str>) -> String {
+fn format_entry_summary(
+    entry: &Entry,
+    stardate_mode: bool,
+    timezone: Option<&str>,
+    age_fade: bool,
+) -> String {
     // Strip newlines and limit content preview to 40 chars.
-    let content_preview = if entry.content.len() > 40 {
+    let content_preview = if entry.is_encrypted() {
+        "encrypted".bright_black().to_string()
+    } else if entry.content.len() > 40 {
         format!("{}...", &entry.content[..40].replace('\n', " "))
     } else {
         entry.content.replace('\n', " ")
@@ -1044,158 +4458,674 @@ fn format_entry_summary(entry: &Entry, stardate_mode: bool, timezone: Option<&st
         let stardate = entry.timestamp.to_stardate();
         format_stardate(stardate)
     } else {
-        to_local_dt(&entry.timestamp, timezone)
-            .format("%Y-%m-%d %H:%M")
-            .to_string()
-            .white()
-            .to_string()
-    };
+        let date = to_local_dt(&entry.timestamp, timezone)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        if age_fade {
+            fade_by_age(&date, &entry.timestamp).to_string()
+        } else {
+            date.white().to_string()
+        }
+    };
+
+    let journal = style_as_journal(&entry.journal, format!("[{}]", entry.journal));
+
+    let star_indicator = if entry.starred { " ⭐" } else { "" };
+    let lock_indicator = if entry.is_encrypted() { " 🔒" } else { "" };
+
+    // Add audio indicator if entry has audio
+    let audio_indicator = if entry.audio_path.is_some() {
+        " 🎤"
+    } else {
+        ""
+    };
+    let draft_indicator = if entry.is_draft() {
+        format!(" {}", "(draft)".yellow())
+    } else {
+        String::new()
+    };
+
+    if let Some(title) = &entry.title {
+        format!(
+            "{} {} {} - {} - {}{}{}{}{}",
+            id,
+            date,
+            journal,
+            title.green().bold(),
+            content_preview.normal(),
+            star_indicator,
+            lock_indicator,
+            audio_indicator,
+            draft_indicator
+        )
+    } else {
+        format!(
+            "{} {} {} - {}{}{}{}{}",
+            id,
+            date,
+            journal,
+            content_preview.normal(),
+            star_indicator,
+            lock_indicator,
+            audio_indicator,
+            draft_indicator
+        )
+    }
+}
+
+/// Dim text according to the age of the entry: bright for the last week,
+/// normal for the last month, and gray for anything older.
+fn fade_by_age(text: &str, timestamp: &DateTime<Utc>) -> ColoredString {
+    let age = Utc::now() - *timestamp;
+    if age < chrono::Duration::days(7) {
+        text.bright_white()
+    } else if age < chrono::Duration::days(30) {
+        text.normal()
+    } else {
+        text.bright_black()
+    }
+}
+
+fn format_stardate(stardate: f64) -> String {
+    let stardate_string = format!("{:.5}", stardate);
+
+    // Split into head and last two characters safely
+    let chars: Vec<char> = stardate_string.chars().collect();
+    let (head, tail) = if chars.len() >= 2 {
+        let head: String = chars[..chars.len() - 2].iter().collect();
+        let tail: String = chars[chars.len() - 2..].iter().collect();
+        (head, tail)
+    } else {
+        (stardate_string, String::new())
+    };
+
+    format!("{}{}", head.white(), tail.bright_black())
+}
+
+/// How `cl new` saves the entry
+#[derive(Debug, Clone, Copy)]
+struct NewEntryOptions {
+    dry_run: bool,
+    draft: bool,
+    encrypted: bool,
+}
+
+/// Encrypt entry content to the configured `encryption.recipient`
+fn encrypt_entry_content(config: &Config, content: &str) -> Result<String> {
+    let recipient = config.encryption.recipient.as_deref().context(
+        "No key to encrypt entries to; set one with `cl config set encryption.recipient <age recipient or GPG key>`",
+    )?;
+    Encryption::parse(recipient).encrypt_text(content)
+}
+
+/// Plaintext of an encrypted entry, using `encryption.identity` for age
+fn decrypt_entry_content(entry: &Entry) -> Result<String> {
+    let identity = ENCRYPTION_IDENTITY.get().cloned().flatten();
+    decrypt_text(&entry.content, identity.as_deref())
+}
+
+fn new_entry(
+    journal: &Journal,
+    journal_category: Option<&str>,
+    config: &Config,
+    options: NewEntryOptions,
+) -> Result<()> {
+    // Create a temporary file for the new entry. The name is unique so that a draft
+    // preserved by a previous failed session is never overwritten.
+    let temp_dir = env::temp_dir();
+    let temp_file = temp_dir.join(format!(
+        "captains-log-new-{}.md",
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+
+    // Start from the journal's default template, if it has one
+    let default_template = journal
+        .get_journal_info(journal_category.unwrap_or(DEFAULT_JOURNAL))?
+        .and_then(|info| info.default_template);
+    let template_content = match default_template {
+        Some(name) => {
+            template::render_template(&template::load_template(&name)?, Local::now().date_naive())
+        }
+        None => "# \n\n".to_string(),
+    };
+    fs::write(&temp_file, &template_content)?;
+
+    // Open editor
+    let result = open_editor(config, &temp_file).and_then(|()| {
+        create_entry_from_file(journal, journal_category, config, &temp_file, options)
+    });
+    finish_with_temp_file(&temp_file, &template_content, result)
+}
+
+/// Open the editor on an empty file and append what was written to entry `id`
+fn append_from_editor(journal: &Journal, config: &Config, id: i64, timestamp: bool) -> Result<()> {
+    let temp_file = env::temp_dir().join(format!(
+        "captains-log-append-{}.md",
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    fs::write(&temp_file, "")?;
+
+    let result = open_editor(config, &temp_file).and_then(|()| {
+        let text = fs::read_to_string(&temp_file)?;
+        append_text(journal, config, id, &text, timestamp)
+    });
+    finish_with_temp_file(&temp_file, "", result)
+}
+
+/// Append `text` to entry `id`, optionally after a line with the current date and time
+fn append_text(
+    journal: &Journal,
+    config: &Config,
+    id: i64,
+    text: &str,
+    timestamp: bool,
+) -> Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        println!("{}", "Nothing to append".yellow());
+        return Ok(());
+    }
+
+    let text = if timestamp {
+        let now = to_local_dt(&Utc::now(), config.display.timezone.as_deref());
+        format!("--- {} ---\n{}", now.format("%Y-%m-%d %H:%M"), text)
+    } else {
+        text.to_string()
+    };
+    journal.append_to_entry(id, &text)?;
+    println!(
+        "{}",
+        format!("Appended to entry {} — {}", id, format_text_stats(&text)).green()
+    );
+
+    Ok(())
+}
+
+/// Parse the edited temp file and create the new entry from it
+fn create_entry_from_file(
+    journal: &Journal,
+    journal_category: Option<&str>,
+    config: &Config,
+    temp_file: &std::path::Path,
+    options: NewEntryOptions,
+) -> Result<()> {
+    // Read the edited content
+    let edited_content = fs::read_to_string(temp_file)?;
+    create_entry_from_text(journal, journal_category, config, &edited_content, options)
+}
+
+/// Create a new entry from editor-style text, where a first `# ` line is the title
+fn create_entry_from_text(
+    journal: &Journal,
+    journal_category: Option<&str>,
+    config: &Config,
+    text: &str,
+    options: NewEntryOptions,
+) -> Result<()> {
+    let NewEntryOptions {
+        dry_run,
+        draft,
+        encrypted,
+    } = options;
+    let (title, content) = parse_title_and_content(text, config.journal.title_from_first_line);
+
+    // Check if the content is empty
+    if content.is_empty() && (title.is_none() || title.as_ref().unwrap().is_empty()) {
+        println!(
+            "{}",
+            "Entry creation cancelled - no content provided".yellow()
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        print_new_entry_preview(title, &content, journal_category, draft, config);
+        return Ok(());
+    }
+
+    // Create the entry
+    let stored_content = if encrypted {
+        encrypt_entry_content(config, &content)?
+    } else {
+        content.clone()
+    };
+    let id = journal.create_entry(title, &stored_content, journal_category)?;
+    if draft {
+        journal.set_entry_status(id, STATUS_DRAFT)?;
+    }
+    println!(
+        "{}",
+        format!(
+            "Entry {} created successfully — {}",
+            id,
+            format_text_stats(&content)
+        )
+        .green()
+    );
+
+    Ok(())
+}
+
+/// Print what `new` would save, for `--dry-run`
+fn print_new_entry_preview(
+    title: Option<&str>,
+    content: &str,
+    journal_category: Option<&str>,
+    draft: bool,
+    config: &Config,
+) {
+    println!(
+        "{}",
+        "Dry run - the entry would be saved as:".yellow().bold()
+    );
+    println!(
+        "{}: {}",
+        "Title".cyan().bold(),
+        title.unwrap_or("(none)").green().bold()
+    );
+    println!(
+        "{}: {}",
+        "Journal".cyan().bold(),
+        styled_journal_name(journal_category.unwrap_or(DEFAULT_JOURNAL))
+    );
+    println!(
+        "{}: {}",
+        "Status".cyan().bold(),
+        if draft { STATUS_DRAFT } else { STATUS_FINAL }.white()
+    );
+    println!(
+        "{}: {}",
+        "Timestamp".cyan().bold(),
+        to_local_dt(&Utc::now(), config.display.timezone.as_deref())
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+            .white()
+    );
+    println!("{}:", "Content".cyan().bold());
+    println!("{}", content);
+    println!();
+    println!("{}", "Nothing was saved".yellow());
+}
+
+fn edit_entry(journal: &Journal, id: i64, config: &Config) -> Result<()> {
+    // Get the existing entry
+    let entry = journal.get_entry(id)?.context("Entry not found")?;
+
+    // Create a temporary file with the current content
+    let temp_dir = env::temp_dir();
+    let temp_file = temp_dir.join(format!(
+        "captains-log-edit-{}-{}.md",
+        id,
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+
+    // Encrypted entries are edited as plaintext and encrypted again when saved
+    let encrypted = entry.is_encrypted();
+    let content = if encrypted {
+        decrypt_entry_content(&entry).with_context(|| format!("Cannot edit entry {}", id))?
+    } else {
+        entry.content.clone()
+    };
+
+    // Format content with title if present
+    let body_content = if let Some(title) = &entry.title {
+        format!("# {}\n\n{}", title, content)
+    } else {
+        content.clone()
+    };
+
+    // Tags that don't come from a hashtag in the content go in the frontmatter,
+    // so that they survive the edit
+    let hashtags = extract_tags(&content);
+    let extra_tags: Vec<String> = journal
+        .get_entry_tags(id)?
+        .into_iter()
+        .filter(|tag| !hashtags.contains(tag))
+        .collect();
+
+    // Write current content with YAML frontmatter to temp file
+    let content_with_frontmatter =
+        format_entry_with_frontmatter(&entry.journal, entry.timestamp, &extra_tags, &body_content)?;
+    fs::write(&temp_file, &content_with_frontmatter)?;
+
+    // Open editor
+    let result = open_editor(config, &temp_file)
+        .and_then(|()| update_entry_from_file(journal, id, config, &temp_file, encrypted));
+    finish_with_temp_file(&temp_file, &content_with_frontmatter, result)
+}
+
+/// Parse the edited temp file and update the entry from it
+fn update_entry_from_file(
+    journal: &Journal,
+    id: i64,
+    config: &Config,
+    temp_file: &std::path::Path,
+    encrypted: bool,
+) -> Result<()> {
+    // Read the edited content
+    let edited_content = fs::read_to_string(temp_file)?;
+
+    // Parse frontmatter and content
+    let (metadata, body) = parse_frontmatter(&edited_content).context(
+        "Failed to parse entry. Make sure the YAML frontmatter is properly formatted with '---' delimiters",
+    )?;
+
+    let (title, content) = parse_title_and_content(&body, config.journal.title_from_first_line);
+    let stored_content = if encrypted {
+        encrypt_entry_content(config, &content)?
+    } else {
+        content.clone()
+    };
+
+    // Update the entry with metadata
+    if journal.update_entry_with_metadata(
+        id,
+        title,
+        &stored_content,
+        &metadata.journal,
+        metadata.timestamp,
+    )? {
+        // Hashtags of an encrypted entry would reveal its content, only explicit tags are kept
+        if !metadata.tags.is_empty() {
+            let mut tags = if encrypted {
+                Vec::new()
+            } else {
+                extract_tags(&content)
+            };
+            tags.extend(metadata.tags);
+            journal.set_entry_tags(id, &tags)?;
+        }
+        println!(
+            "{}",
+            format!(
+                "Entry {} updated successfully — {}",
+                id,
+                format_text_stats(&content)
+            )
+            .green()
+        );
+    } else {
+        println!("{}", format!("Failed to update entry {}", id).red());
+    }
 
-    let journal = format!("[{}]", entry.journal).magenta().bold();
+    Ok(())
+}
 
-    // Add audio indicator if entry has audio
-    let audio_indicator = if entry.audio_path.is_some() {
-        " 🎤"
-    } else {
-        ""
+/// Print the report of `cl streak`
+fn print_streak(streak: &Streak) {
+    let Some(days_since_last) = streak.days_since_last else {
+        println!("{}", "No entries yet - start your streak today".yellow());
+        return;
     };
 
-    if let Some(title) = &entry.title {
-        format!(
-            "{} {} {} - {} - {}{}",
-            id,
-            date,
-            journal,
-            title.green().bold(),
-            content_preview.normal(),
-            audio_indicator
-        )
-    } else {
-        format!(
-            "{} {} {} - {}{}",
-            id,
-            date,
-            journal,
-            content_preview.normal(),
-            audio_indicator
-        )
+    let days = |n: usize| format!("{} {}", n, if n == 1 { "day" } else { "days" });
+    let current = days(streak.current);
+    println!(
+        "  current streak: {}",
+        if streak.current > 0 {
+            current.green().bold()
+        } else {
+            current.yellow()
+        }
+    );
+    println!("  longest streak: {}", days(streak.longest).green());
+    match days_since_last {
+        0 => println!("  last entry: {}", "today".green()),
+        1 => println!("  last entry: {}", "yesterday".yellow()),
+        n => println!("  last entry: {}", format!("{} days ago", n).red()),
     }
 }
 
-fn format_stardate(stardate: f64) -> String {
-    let stardate_string = format!("{:.5}", stardate);
+/// Print the report of `cl stats`
+fn print_stats(journal: &Journal, stats: &JournalStats, config: &Config) -> Result<()> {
+    if stats.total_entries == 0 {
+        println!("{}", "No entries found".yellow());
+        return Ok(());
+    }
 
-    // Split into head and last two characters safely
-    let chars: Vec<char> = stardate_string.chars().collect();
-    let (head, tail) = if chars.len() >= 2 {
-        let head: String = chars[..chars.len() - 2].iter().collect();
-        let tail: String = chars[chars.len() - 2..].iter().collect();
-        (head, tail)
-    } else {
-        (stardate_string, String::new())
-    };
+    let tz = config.display.timezone.as_deref();
+    let format_date =
+        |timestamp: &DateTime<Utc>| to_local_dt(timestamp, tz).format("%Y-%m-%d").to_string();
 
-    format!("{}{}", head.white(), tail.bright_black())
-}
+    println!("{}", "Writing Statistics:".cyan().bold());
+    println!("{}", "─".repeat(40).bright_blue());
+    println!();
+    println!(
+        "  total entries: {}",
+        stats.total_entries.to_string().green()
+    );
+    if let (Some(first), Some(last)) = (&stats.first_entry, &stats.last_entry) {
+        println!("  first entry: {}", format_date(first).green());
+        println!("  last entry: {}", format_date(last).green());
+    }
+    println!(
+        "  average words per entry: {}",
+        format!("{:.1}", stats.average_words()).green()
+    );
+    if let Some((id, words)) = stats.longest_entry
+        && let Some(entry) = journal.get_entry(id)?
+    {
+        println!(
+            "  longest entry: {} {}",
+            format!("{} words", words).green(),
+            format!(
+                "(#{} {}, {})",
+                id,
+                entry.title.as_deref().unwrap_or("untitled"),
+                format_date(&entry.timestamp)
+            )
+            .bright_black()
+        );
+    }
 
-fn new_entry(journal: &Journal, journal_category: Option<&str>, config: &Config) -> Result<()> {
-    // Create a temporary file for the new entry
-    let temp_dir = env::temp_dir();
-    let temp_file = temp_dir.join("captains-log-new.md");
+    println!();
+    println!("{}", "Entries per journal:".yellow().bold());
+    let width = stats
+        .per_journal
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, count) in &stats.per_journal {
+        println!("  {:<width$}  {}", name, count.to_string().green());
+    }
 
-    // Write template content to temp file
-    let template_content = "# \n\n";
-    fs::write(&temp_file, template_content)?;
+    println!();
+    println!("{}", "Entries per month:".yellow().bold());
+    for (month, count) in &stats.per_month {
+        println!("  {}  {}", month, count.to_string().green());
+    }
 
-    // Get editor from config
-    let editor = config.get_editor_command();
+    Ok(())
+}
 
-    // Open editor
-    let status = Command::new(&editor)
-        .arg(&temp_file)
-        .status()
-        .context("Failed to launch editor")?;
+/// Print anniversary entries under one heading per year, newest year first
+fn print_on_this_day(entries: &[Entry], today: NaiveDate, config: &Config) {
+    let date_label = today.format("%B %-d");
+    if entries.is_empty() {
+        println!(
+            "{}",
+            format!("Nothing written on {} in previous years", date_label).yellow()
+        );
+        return;
+    }
 
-    if !status.success() {
-        return Err(anyhow::anyhow!("Editor exited with error"));
+    println!("{}", format!("On this day, {}", date_label).green().bold());
+    let mut current_year = None;
+    for entry in entries {
+        let year = to_local_dt(&entry.timestamp, config.display.timezone.as_deref()).year();
+        if current_year != Some(year) {
+            current_year = Some(year);
+            let years_ago = today.year() - year;
+            println!();
+            println!(
+                "{} {}",
+                year.to_string().cyan().bold(),
+                format!(
+                    "— {} {} ago",
+                    years_ago,
+                    if years_ago == 1 { "year" } else { "years" }
+                )
+                .bright_black()
+            );
+        }
+        println!(
+            "  {}",
+            format_entry_summary(
+                entry,
+                config.display.stardate_mode,
+                config.display.timezone.as_deref(),
+                config.display.age_fade,
+            )
+        );
     }
+}
 
-    // Read the edited content
-    let edited_content = fs::read_to_string(&temp_file)?;
-    let lines: Vec<&str> = edited_content.lines().collect();
+/// Print a day's entries in full, one section per journal, then the total word count
+fn print_day_summary(entries: &[Entry], config: &Config, compact: bool) {
+    if entries.is_empty() {
+        println!("{}", "No entries today".yellow());
+        return;
+    }
 
-    // Parse title and content
-    let (title, content) = if lines.is_empty() {
-        (None, String::new())
-    } else {
-        let first_line = lines[0].trim();
-        if first_line.is_empty() && lines.len() == 1 {
-            // If the only line is empty, treat as empty content
-            (None, String::new())
-        } else if first_line.starts_with("# ") && lines.len() == 1 {
-            // If only title is present
-            (
-                Some(first_line.strip_prefix("# ").unwrap().trim()),
-                String::new(),
-            )
-        } else if first_line.starts_with("# ") && lines.len() > 1 {
-            // If title and content are present
-            let title = first_line.strip_prefix("# ").unwrap().trim();
-            let content = lines[1..].join("\n").trim().to_string();
-            (Some(title), content)
-        } else {
-            // No title, all content
-            (None, edited_content.trim().to_string())
-        }
-    };
+    let mut by_journal: std::collections::BTreeMap<&str, Vec<&Entry>> =
+        std::collections::BTreeMap::new();
+    for entry in entries {
+        by_journal.entry(&entry.journal).or_default().push(entry);
+    }
 
-    // Check if the content is empty
-    if content.is_empty() && (title.is_none() || title.as_ref().unwrap().is_empty()) {
+    for (journal_name, journal_entries) in by_journal {
         println!(
             "{}",
-            "Entry creation cancelled - no content provided".yellow()
+            format!("{} ({})", journal_name, journal_entries.len())
+                .cyan()
+                .bold()
         );
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_file);
-        return Ok(());
+        for entry in journal_entries {
+            print_entry(
+                entry,
+                config.display.stardate_mode,
+                config.display.timezone.as_deref(),
+                compact,
+            );
+            if !compact {
+                println!();
+            }
+        }
     }
 
-    // Create the entry
-    let id = journal.create_entry(title, &content, journal_category)?;
-    println!("{}", format!("Entry {} created successfully", id).green());
+    let words: usize = entries
+        .iter()
+        .map(|entry| entry.content.split_whitespace().count())
+        .sum();
+    println!(
+        "{}",
+        format!(
+            "{} {}, {} words",
+            entries.len(),
+            if entries.len() == 1 {
+                "entry"
+            } else {
+                "entries"
+            },
+            words
+        )
+        .bright_black()
+    );
+}
 
-    // Clean up temp file
-    let _ = fs::remove_file(&temp_file);
+/// Word and character count of an entry body, e.g. `342 words, 1870 characters`
+fn format_text_stats(content: &str) -> String {
+    format!(
+        "{} words, {} characters",
+        content.split_whitespace().count(),
+        content.chars().count()
+    )
+}
 
-    Ok(())
+/// One line per entry for the picker, starting with the entry ID and a tab
+fn picker_line(entry: &Entry, timezone: Option<&str>) -> String {
+    let text = entry.title.as_deref().unwrap_or_else(|| {
+        entry
+            .content
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+    });
+    format!(
+        "{}\t{}\t[{}]\t{}",
+        entry.id,
+        to_local_dt(&entry.timestamp, timezone).format("%Y-%m-%d"),
+        entry.journal,
+        text.trim().chars().take(100).collect::<String>()
+    )
 }
 
-fn edit_entry(journal: &Journal, id: i64, config: &Config) -> Result<()> {
-    // Get the existing entry
-    let entry = journal.get_entry(id)?.context("Entry not found")?;
+/// Run the configured picker over `entries` and return the selected entry ID,
+/// or `None` if the selection was cancelled
+fn pick_entry(config: &Config, entries: &[Entry]) -> Result<Option<i64>> {
+    let command_line = config.get_picker_command();
+    let mut words = command_line.split_whitespace();
+    let program = words
+        .next()
+        .context("picker.command is empty; set it with `cl config set picker.command fzf`")?;
+    if which::which(program).is_err() {
+        return Err(anyhow::anyhow!(
+            "Picker '{}' not found on PATH; install fzf or set one with `cl config set picker.command <command>`",
+            program
+        ));
+    }
 
-    // Create a temporary file with the current content
-    let temp_dir = env::temp_dir();
-    let temp_file = temp_dir.join(format!("captains-log-edit-{}.md", id));
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to launch picker '{}'", program))?;
 
-    // Format content with title if present
-    let body_content = if let Some(title) = &entry.title {
-        format!("# {}\n\n{}", title, entry.content)
-    } else {
-        entry.content.clone()
-    };
+    if let Some(mut stdin) = child.stdin.take() {
+        for entry in entries {
+            // The picker may exit before reading everything; its exit status tells what happened
+            if writeln!(
+                stdin,
+                "{}",
+                picker_line(entry, config.display.timezone.as_deref())
+            )
+            .is_err()
+            {
+                break;
+            }
+        }
+    }
 
-    // Write current content with YAML frontmatter to temp file
-    let content_with_frontmatter =
-        format_entry_with_frontmatter(&entry.journal, entry.timestamp, &body_content)?;
-    fs::write(&temp_file, content_with_frontmatter)?;
+    let output = child.wait_with_output().context("Picker failed")?;
+    if !output.status.success() {
+        // fzf exits with 1 when nothing matched and 130 when cancelled
+        return Ok(None);
+    }
+
+    let selection = String::from_utf8_lossy(&output.stdout);
+    Ok(selection
+        .lines()
+        .next()
+        .and_then(|line| line.split('\t').next())
+        .and_then(|id| id.trim().parse().ok()))
+}
 
+/// Launch the configured editor on `file` and wait for it to exit
+fn open_editor(config: &Config, file: &std::path::Path) -> Result<()> {
     // Get editor from config
     let editor = config.get_editor_command();
 
-    // Open editor
+    if EDITOR_CHECK.load(Ordering::Relaxed) && which::which(&editor).is_err() {
+        return Err(anyhow::anyhow!(
+            "Editor '{}' not found on PATH; set one with `cl config set editor.command <command>` (or skip this check with --no-editor-check)",
+            editor
+        ));
+    }
+
     let status = Command::new(&editor)
-        .arg(&temp_file)
+        .arg(file)
         .status()
         .context("Failed to launch editor")?;
 
@@ -1203,18 +5133,61 @@ fn edit_entry(journal: &Journal, id: i64, config: &Config) -> Result<()> {
         return Err(anyhow::anyhow!("Editor exited with error"));
     }
 
-    // Read the edited content
-    let edited_content = fs::read_to_string(&temp_file)?;
+    Ok(())
+}
 
-    // Parse frontmatter and content
-    let (metadata, body) = parse_frontmatter(&edited_content).context(
-        "Failed to parse entry. Make sure the YAML frontmatter is properly formatted with '---' delimiters",
-    )?;
+/// Delete the editor temp file unless something failed after the user changed it.
+/// In that case the file is kept and its path printed so no writing is lost.
+fn finish_with_temp_file(
+    temp_file: &std::path::Path,
+    original_content: &str,
+    result: Result<()>,
+) -> Result<()> {
+    let unchanged = fs::read_to_string(temp_file).is_ok_and(|content| content == original_content);
+
+    match result {
+        Ok(()) => {
+            let _ = fs::remove_file(temp_file);
+            Ok(())
+        }
+        Err(e) if unchanged => {
+            let _ = fs::remove_file(temp_file);
+            Err(e)
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                format!("Your text has been preserved at {}", temp_file.display()).yellow()
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Split editor text into an optional `# ` title line and the remaining content.
+///
+/// With `title_from_first_line`, the first non-empty line becomes the title even
+/// without the `# ` heading marker.
+fn parse_title_and_content(text: &str, title_from_first_line: bool) -> (Option<&str>, String) {
+    if title_from_first_line {
+        // Skip blank lines and the empty `# ` heading left over from the new-entry template
+        let mut lines = text
+            .lines()
+            .skip_while(|line| matches!(line.trim(), "" | "#"));
+        return match lines.next() {
+            Some(first_line) => {
+                let first_line = first_line.trim();
+                let title = first_line.strip_prefix("# ").unwrap_or(first_line).trim();
+                let content = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+                (Some(title), content)
+            }
+            None => (None, String::new()),
+        };
+    }
 
-    let lines: Vec<&str> = body.lines().collect();
+    let lines: Vec<&str> = text.lines().collect();
 
-    // Parse title and content from the body
-    let (title, content) = if lines.is_empty() {
+    if lines.is_empty() {
         (None, String::new())
     } else {
         let first_line = lines[0].trim();
@@ -1234,25 +5207,120 @@ fn edit_entry(journal: &Journal, id: i64, config: &Config) -> Result<()> {
             (Some(title), content)
         } else {
             // No title, all content
-            (None, body.trim().to_string())
+            (None, text.trim().to_string())
+        }
+    }
+}
+
+/// Intensity levels of the heatmap cells, from no entries to the busiest days
+const HEATMAP_COLORS: [(u8, u8, u8); 5] = [
+    (60, 60, 60),
+    (14, 68, 41),
+    (0, 109, 50),
+    (38, 166, 65),
+    (57, 211, 83),
+];
+
+/// Number of `timestamps` on each day of `year` in the configured (or system local)
+/// timezone, for the days that have any
+fn count_entries_per_day(
+    timestamps: &[DateTime<Utc>],
+    year: i32,
+    timezone: Option<&str>,
+) -> std::collections::HashMap<NaiveDate, usize> {
+    let mut counts = std::collections::HashMap::new();
+    for timestamp in timestamps {
+        let date = to_local_dt(timestamp, timezone).date_naive();
+        if date.year() == year {
+            *counts.entry(date).or_insert(0) += 1;
         }
+    }
+    counts
+}
+
+/// Print a GitHub-style grid of entries per day for `year`: one row per weekday,
+/// one column per week, brighter cells for busier days
+fn show_heatmap(
+    journal: &Journal,
+    year: i32,
+    journal_filter: Option<&str>,
+    exclude_archived: bool,
+    timezone: Option<&str>,
+) -> Result<()> {
+    let counts = count_entries_per_day(
+        &journal.list_entry_timestamps(journal_filter, exclude_archived)?,
+        year,
+        timezone,
+    );
+    let max = counts.values().copied().max().unwrap_or(0);
+    let total: usize = counts.values().sum();
+
+    let first_day = NaiveDate::from_ymd_opt(year, 1, 1).context("Invalid year")?;
+    let last_day = NaiveDate::from_ymd_opt(year, 12, 31).context("Invalid year")?;
+    // Columns start on Mondays, so the first one may begin in the previous year
+    let grid_start =
+        first_day - chrono::Duration::days(first_day.weekday().num_days_from_monday() as i64);
+    let weeks = ((last_day - grid_start).num_days() / 7 + 1) as usize;
+
+    let cell = |level: usize| {
+        let (r, g, b) = HEATMAP_COLORS[level];
+        "■".truecolor(r, g, b)
     };
 
-    // Update the entry with metadata
-    if journal.update_entry_with_metadata(
-        id,
-        title,
-        &content,
-        &metadata.journal,
-        metadata.timestamp,
-    )? {
-        println!("{}", format!("Entry {} updated successfully", id).green());
-    } else {
-        println!("{}", format!("Failed to update entry {}", id).red());
+    println!();
+    println!("{}", year.to_string().cyan().bold());
+
+    // Month labels above the week where each month starts
+    let mut header = vec![' '; weeks * 2];
+    for month in 1..=12 {
+        let start = NaiveDate::from_ymd_opt(year, month, 1).context("Invalid date")?;
+        let column = ((start - grid_start).num_days() / 7) as usize * 2;
+        let label = start.format("%b").to_string();
+        if header[column..].len() >= label.len()
+            && header[column.saturating_sub(1)..column + label.len()]
+                .iter()
+                .all(|c| *c == ' ')
+        {
+            for (i, c) in label.chars().enumerate() {
+                header[column + i] = c;
+            }
+        }
+    }
+    println!("   {}", header.iter().collect::<String>().trim_end());
+
+    for (row, label) in ["Mo", "  ", "We", "  ", "Fr", "  ", "Su"]
+        .iter()
+        .enumerate()
+    {
+        print!("{} ", label);
+        for week in 0..weeks {
+            let day = grid_start + chrono::Duration::days((week * 7 + row) as i64);
+            if day.year() != year {
+                print!("  ");
+                continue;
+            }
+            let count = counts.get(&day).copied().unwrap_or(0);
+            // Scale to the busiest day, so any entry shows at least the first level
+            let level = if count == 0 {
+                0
+            } else {
+                (count * 4).div_ceil(max).clamp(1, 4)
+            };
+            print!("{} ", cell(level));
+        }
+        println!();
     }
 
-    // Clean up temp file
-    let _ = fs::remove_file(&temp_file);
+    println!();
+    print!("{} ", "Less".bright_black());
+    for level in 0..HEATMAP_COLORS.len() {
+        print!("{} ", cell(level));
+    }
+    println!("{}", "More".bright_black());
+    println!(
+        "{}",
+        format!("{} entries on {} days in {}", total, counts.len(), year).bright_black()
+    );
 
     Ok(())
 }
@@ -1262,6 +5330,7 @@ fn show_calendar(
     year: Option<i32>,
     month: Option<u32>,
     journal_filter: Option<&str>,
+    exclude_archived: bool,
     config: &Config,
 ) -> Result<()> {
     let now = Local::now();
@@ -1274,7 +5343,8 @@ fn show_calendar(
     }
 
     // Get entries for the month
-    let entries = journal.list_entries_for_month_filtered(year, month, journal_filter)?;
+    let entries =
+        journal.list_entries_for_month_filtered(year, month, journal_filter, exclude_archived)?;
 
     // Create a map of day -> entry count
     let tz = config.display.timezone.as_deref();
@@ -1358,10 +5428,240 @@ fn show_calendar(
         for entry in entries {
             println!(
                 "{}",
-                format_entry_summary(&entry, config.display.stardate_mode, tz)
+                format_entry_summary(
+                    &entry,
+                    config.display.stardate_mode,
+                    tz,
+                    config.display.age_fade,
+                )
             );
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn test_journal() -> Journal {
+        Journal::new(Database::new_in_memory().unwrap())
+    }
+
+    #[test]
+    fn test_format_text_stats() {
+        assert_eq!(
+            format_text_stats("Captain's log,\nstardate 41153.7"),
+            "4 words, 31 characters"
+        );
+        assert_eq!(format_text_stats(""), "0 words, 0 characters");
+    }
+
+    #[test]
+    fn test_local_dates() {
+        let at = |d, h| {
+            NaiveDate::from_ymd_opt(2025, 9, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let day = |d| NaiveDate::from_ymd_opt(2025, 9, d).unwrap();
+        let timestamps = [at(1, 2), at(1, 9), at(2, 2)];
+
+        assert_eq!(local_dates(&timestamps, Some("UTC")), vec![day(1), day(2)]);
+        // 02:00 UTC is still the previous evening in New York
+        assert_eq!(
+            local_dates(&timestamps, Some("America/New_York")),
+            vec![NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(), day(1)]
+        );
+    }
+
+    #[test]
+    fn test_list_entries_on_local_day() {
+        let journal = test_journal();
+        let at = |d, h| {
+            NaiveDate::from_ymd_opt(2025, 9, d)
+                .unwrap()
+                .and_hms_opt(h, 30, 0)
+                .unwrap()
+        };
+        // 03:30 UTC on the 7th is 20:30 on the 6th in Los Angeles
+        let late = journal
+            .create_entry_with_timestamp(None, "Late night log", None, at(7, 3))
+            .unwrap();
+        let noon = journal
+            .create_entry_with_timestamp(None, "Noon log", None, at(7, 12))
+            .unwrap();
+        let ids_on = |d, tz| -> Vec<i64> {
+            list_entries_on_local_day(&journal, at(d, 0).date(), None, Some(tz), "timestamp")
+                .unwrap()
+                .iter()
+                .map(|entry| entry.id)
+                .collect()
+        };
+
+        assert_eq!(ids_on(7, "UTC"), vec![late, noon]);
+        assert_eq!(ids_on(6, "America/Los_Angeles"), vec![late]);
+        assert_eq!(ids_on(7, "America/Los_Angeles"), vec![noon]);
+        assert!(ids_on(8, "Asia/Tokyo").is_empty());
+    }
+
+    #[test]
+    fn test_is_anniversary() {
+        let at = |y, m, d, h| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+
+        assert!(is_anniversary(&at(2024, 3, 14, 12), today, Some("UTC")));
+        assert!(is_anniversary(&at(2022, 3, 14, 12), today, Some("UTC")));
+        assert!(!is_anniversary(&at(2024, 3, 15, 12), today, Some("UTC")));
+        assert!(!is_anniversary(&at(2025, 3, 14, 12), today, Some("UTC")));
+        // 02:00 UTC on the 15th is still the 14th in New York
+        assert!(is_anniversary(
+            &at(2024, 3, 15, 2),
+            today,
+            Some("America/New_York")
+        ));
+        assert!(!is_anniversary(
+            &at(2024, 3, 15, 2),
+            today,
+            Some("Europe/Rome")
+        ));
+    }
+
+    #[test]
+    fn test_count_entries_per_day() {
+        let at = |y, d, h| {
+            NaiveDate::from_ymd_opt(y, 9, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let timestamps = [
+            at(2024, 1, 9),
+            at(2025, 1, 9),
+            at(2025, 1, 18),
+            at(2025, 3, 2),
+        ];
+
+        let counts = count_entries_per_day(&timestamps, 2025, Some("UTC"));
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&at(2025, 1, 0).date_naive()], 2);
+        assert_eq!(counts[&at(2025, 3, 0).date_naive()], 1);
+
+        // 02:00 UTC on the 3rd is still the 2nd in New York
+        let counts = count_entries_per_day(&timestamps, 2025, Some("America/New_York"));
+        assert_eq!(counts[&at(2025, 2, 0).date_naive()], 1);
+        assert!(!counts.contains_key(&at(2025, 3, 0).date_naive()));
+    }
+
+    #[test]
+    fn test_picker_line_uses_title_or_first_line() {
+        let journal = test_journal();
+        let titled = journal
+            .create_entry(Some("Warp core"), "Body", Some("Work"))
+            .unwrap();
+        let untitled = journal
+            .create_entry(None, "\n  First line\nSecond", None)
+            .unwrap();
+
+        let line = picker_line(&journal.get_entry(titled).unwrap().unwrap(), None);
+        assert!(line.starts_with(&format!("{}\t", titled)));
+        assert!(line.ends_with("\t[Work]\tWarp core"));
+
+        let line = picker_line(&journal.get_entry(untitled).unwrap().unwrap(), None);
+        assert!(line.ends_with("\t[Personal]\tFirst line"));
+
+        // 03:30 UTC is still the previous evening in Los Angeles
+        let late = NaiveDate::from_ymd_opt(2025, 9, 7)
+            .unwrap()
+            .and_hms_opt(3, 30, 0)
+            .unwrap();
+        let id = journal
+            .create_entry_with_timestamp(None, "Late", None, late)
+            .unwrap();
+        let entry = journal.get_entry(id).unwrap().unwrap();
+        assert!(picker_line(&entry, Some("UTC")).contains("\t2025-09-07\t"));
+        assert!(picker_line(&entry, Some("America/Los_Angeles")).contains("\t2025-09-06\t"));
+    }
+
+    #[test]
+    fn test_parse_title_and_content_heading() {
+        let (title, content) = parse_title_and_content("# Title\n\nBody", false);
+        assert_eq!(title, Some("Title"));
+        assert_eq!(content, "Body");
+
+        let (title, content) = parse_title_and_content("Plain first line\nBody", false);
+        assert_eq!(title, None);
+        assert_eq!(content, "Plain first line\nBody");
+    }
+
+    #[test]
+    fn test_parse_title_and_content_first_line() {
+        let (title, content) = parse_title_and_content("\nPlain first line\n\nBody", true);
+        assert_eq!(title, Some("Plain first line"));
+        assert_eq!(content, "Body");
+
+        let (title, content) = parse_title_and_content("# Heading\nBody", true);
+        assert_eq!(title, Some("Heading"));
+        assert_eq!(content, "Body");
+
+        let (title, content) = parse_title_and_content("# \n\nTyped below template", true);
+        assert_eq!(title, Some("Typed below template"));
+        assert_eq!(content, "");
+
+        assert_eq!(parse_title_and_content("  \n", true), (None, String::new()));
+    }
+
+    #[test]
+    fn test_resolve_entry_ref_numeric() {
+        let journal = test_journal();
+        assert_eq!(resolve_entry_ref(&journal, "42", None).unwrap(), 42);
+        assert!(resolve_entry_ref(&journal, "latest", None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_last_on_empty_database() {
+        let journal = test_journal();
+        let err = resolve_entry_ref(&journal, "last", None).unwrap_err();
+        assert_eq!(err.to_string(), "No entries yet");
+
+        journal
+            .create_entry(None, "Work entry", Some("Work"))
+            .unwrap();
+        let err = resolve_entry_ref(&journal, "last", Some("Personal")).unwrap_err();
+        assert_eq!(err.to_string(), "No entries yet in journal 'Personal'");
+    }
+
+    #[test]
+    fn test_resolve_last_respects_journal_and_deletion() {
+        let journal = test_journal();
+        let personal = journal.create_entry(None, "Personal entry", None).unwrap();
+        let first_work = journal.create_entry(None, "Work 1", Some("Work")).unwrap();
+        let second_work = journal.create_entry(None, "Work 2", Some("Work")).unwrap();
+
+        assert_eq!(
+            resolve_entry_ref(&journal, "last", None).unwrap(),
+            second_work
+        );
+        assert_eq!(
+            resolve_entry_ref(&journal, "last", Some("Personal")).unwrap(),
+            personal
+        );
+
+        journal.delete_entry(second_work).unwrap();
+        assert_eq!(
+            resolve_entry_ref(&journal, "LAST", Some("Work")).unwrap(),
+            first_work
+        );
+    }
+}
@@ -0,0 +1,133 @@
+/// Module for saved searches.
+///
+/// A saved search is a named set of filters stored in the config under the `search`
+/// section, written in the same syntax as the command line, e.g.
+/// `cl config set search.worklog '--journal Work --since "last month" deadline'`.
+/// Flags given on the command line take precedence over the saved ones.
+use anyhow::{Result, anyhow};
+use clap::Parser;
+
+use crate::config::Config;
+
+#[derive(Debug, Default, PartialEq, Parser)]
+#[command(no_binary_name = true)]
+pub struct SavedSearch {
+    #[arg(long)]
+    pub date: Option<String>,
+
+    #[arg(long)]
+    pub since: Option<String>,
+
+    #[arg(long)]
+    pub until: Option<String>,
+
+    #[arg(long)]
+    pub journal: Option<String>,
+
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Search query words, joined with spaces
+    pub query: Vec<String>,
+}
+
+impl SavedSearch {
+    /// Parse the stored argument string of a saved search
+    pub fn from_args(args: &str) -> Result<Self> {
+        let words = split_args(args)?;
+        SavedSearch::try_parse_from(words).map_err(|e| {
+            // Keep only clap's first line, the usage text refers to a command that doesn't exist
+            let rendered = e.render().to_string();
+            let reason = rendered.lines().next().unwrap_or_default();
+            anyhow!(
+                "Invalid saved search '{}': {}",
+                args,
+                reason.trim_start_matches("error: ")
+            )
+        })
+    }
+
+    /// Look up and parse the saved search called `name`
+    pub fn load(config: &Config, name: &str) -> Result<Self> {
+        let args = config.search.get(name).ok_or_else(|| {
+            anyhow!(
+                "No saved search named '{}'. Define one with `cl config set search.{} '<filters>'`",
+                name,
+                name
+            )
+        })?;
+        Self::from_args(args)
+    }
+
+    /// The saved query, if any
+    pub fn query(&self) -> Option<String> {
+        (!self.query.is_empty()).then(|| self.query.join(" "))
+    }
+}
+
+/// Split a command line into words, honouring single and double quotes
+fn split_args(args: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in args.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(anyhow!(
+            "Invalid saved search '{}': unterminated quote",
+            args
+        ));
+    }
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_args_handles_quotes() {
+        assert_eq!(
+            split_args(r#"--journal Work --since "last month" 'warp core'"#).unwrap(),
+            vec!["--journal", "Work", "--since", "last month", "warp core"]
+        );
+        assert!(split_args("--since \"last month").is_err());
+    }
+
+    #[test]
+    fn test_parse_saved_search() {
+        let saved =
+            SavedSearch::from_args(r#"--journal Work --since "last month" deadline"#).unwrap();
+        assert_eq!(saved.journal.as_deref(), Some("Work"));
+        assert_eq!(saved.since.as_deref(), Some("last month"));
+        assert_eq!(saved.query(), Some("deadline".to_string()));
+
+        assert_eq!(SavedSearch::from_args("--tag work").unwrap().query(), None);
+        assert!(SavedSearch::from_args("--unknown flag").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+/// Module for entry templates.
+///
+/// Templates are plain Markdown files stored in the `templates` directory next to the
+/// config file (e.g. `~/.config/captains-log/templates/standup.md`). The first `# `
+/// heading becomes the entry title, like in the editor, and `{{date}}` is replaced by
+/// the entry date (YYYY-MM-DD).
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+const TEMPLATE_EXTENSION: &str = "md";
+
+/// Path of the named template. Names are plain file stems, without directories.
+pub fn template_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(anyhow::anyhow!("Invalid template name '{}'", name));
+    }
+
+    Ok(Config::get_templates_dir()?.join(format!("{}.{}", name, TEMPLATE_EXTENSION)))
+}
+
+/// Read the named template from the templates directory
+pub fn load_template(name: &str) -> Result<String> {
+    let path = template_path(name)?;
+    fs::read_to_string(&path)
+        .with_context(|| format!("Template '{}' not found at {}", name, path.display()))
+}
+
+/// Names of the available templates, sorted alphabetically
+pub fn list_templates() -> Result<Vec<String>> {
+    let dir = Config::get_templates_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read templates directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == TEMPLATE_EXTENSION)
+        })
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .collect();
+    names.sort();
+
+    Ok(names)
+}
+
+/// Substitute the template variables for an entry dated `date`
+pub fn render_template(template: &str, date: NaiveDate) -> String {
+    template.replace("{{date}}", &date.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template_substitutes_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 9, 9).unwrap();
+        let rendered = render_template("# Standup {{date}}\n\n- Done ({{date}}):\n", date);
+        assert_eq!(rendered, "# Standup 2025-09-09\n\n- Done (2025-09-09):\n");
+    }
+
+    #[test]
+    fn test_template_path_rejects_directories() {
+        assert!(template_path("../secrets").is_err());
+        assert!(template_path("").is_err());
+    }
+}
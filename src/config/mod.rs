@@ -1,8 +1,25 @@
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Profile selected with `--profile`, set once by `main`
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Config file given with `--config`, set once by `main`
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Config file name in the config directory
+const CONFIG_FILE: &str = "config.toml";
+
+/// Config file written by earlier versions, migrated to `CONFIG_FILE` when loaded
+const LEGACY_CONFIG_FILE: &str = "config.json";
+
+/// Name of the profile using the config and database outside `profiles/`
+pub const DEFAULT_PROFILE: &str = "default";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -11,11 +28,42 @@ pub struct Config {
     pub display: DisplayConfig,
     #[serde(default)]
     pub audio: AudioConfig,
+    #[serde(default)]
+    pub journal: JournalConfig,
+    #[serde(default)]
+    pub picker: PickerConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    /// Saved searches: name -> filter arguments (e.g. `--journal Work --since "last month"`)
+    #[serde(default)]
+    pub search: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub path: Option<String>,
+
+    /// SQLite journal mode: "wal" lets readers work while another `cl` writes.
+    #[serde(default = "default_journal_mode")]
+    pub journal_mode: String,
+
+    /// Milliseconds to wait for a lock held by another `cl` before failing.
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+
+    /// Enforce the `REFERENCES` constraints between entries, tags and revisions.
+    #[serde(default = "default_true")]
+    pub foreign_keys: bool,
+
+    /// Remember the passphrase of an encrypted database in the OS keychain.
+    #[serde(default)]
+    pub keychain: bool,
+
+    /// Minutes `cl unlock` keeps the passphrase of an encrypted database.
+    #[serde(default = "default_unlock_minutes")]
+    pub unlock_minutes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +71,41 @@ pub struct EditorConfig {
     pub command: Option<String>,
 }
 
+/// External fuzzy finder used by `cl pick`: reads entry lines on stdin and
+/// prints the selected one on stdout (fzf, skim, ...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PickerConfig {
+    pub command: Option<String>,
+}
+
+/// Where `cl backup` writes its snapshots, and how many it keeps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Directory for the backups. If absent, `backups/` next to the database is used.
+    #[serde(default)]
+    pub dir: Option<String>,
+
+    /// Number of most recent backups kept.
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+
+    /// Also delete backups older than this many days (the newest one is always kept).
+    #[serde(default)]
+    pub keep_days: Option<u64>,
+}
+
+/// Keys for encrypted entries (`cl encrypt-entry`, `cl new --encrypted`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// age recipient (`age1...`) or GPG key ID/email the entries are encrypted to.
+    #[serde(default)]
+    pub recipient: Option<String>,
+
+    /// age identity file used to decrypt them (gpg finds its keys itself).
+    #[serde(default)]
+    pub identity: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayConfig {
     pub colors_enabled: bool,
@@ -32,6 +115,19 @@ pub struct DisplayConfig {
     #[serde(default)]
     pub stardate_mode: bool,
 
+    /// Dim the dates of older entries in list output.
+    #[serde(default)]
+    pub age_fade: bool,
+
+    /// Use the dense layout (thin separator, no blank lines) for `show`.
+    #[serde(default)]
+    pub compact: bool,
+
+    /// Order of entry listings: "timestamp" (the entry date, which can be backfilled
+    /// by imports or edits) or "created" (when the entry was added to the database).
+    #[serde(default = "default_sort")]
+    pub default_sort: String,
+
     /// IANA timezone name for export timestamps (e.g. "Europe/Rome").
     /// If absent, the system local time is used.
     #[serde(default)]
@@ -59,6 +155,41 @@ pub struct AudioConfig {
     pub sample_rate: u32,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalConfig {
+    /// Use the first non-empty line of an edited entry as its title,
+    /// even when it does not start with `# `.
+    #[serde(default)]
+    pub title_from_first_line: bool,
+}
+
+fn default_sort() -> String {
+    "timestamp".to_string()
+}
+
+/// SQLite journal modes accepted for `database.journal_mode`
+pub const JOURNAL_MODES: &[&str] = &["wal", "delete", "truncate", "persist", "memory", "off"];
+
+fn default_journal_mode() -> String {
+    "wal".to_string()
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_unlock_minutes() -> u64 {
+    15
+}
+
+fn default_backup_keep() -> usize {
+    10
+}
+
 fn default_whisper_model() -> String {
     "base.en".to_string()
 }
@@ -71,6 +202,16 @@ fn default_sample_rate() -> u32 {
     16000
 }
 
+impl DisplayConfig {
+    /// Database column used to order entry listings, based on `default_sort`.
+    pub fn sort_column(&self) -> &'static str {
+        match self.default_sort.as_str() {
+            "created" => "created_at",
+            _ => "timestamp",
+        }
+    }
+}
+
 impl Default for AudioConfig {
     fn default() -> Self {
         AudioConfig {
@@ -84,12 +225,35 @@ impl Default for AudioConfig {
     }
 }
 
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            path: None,
+            journal_mode: default_journal_mode(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            foreign_keys: true,
+            keychain: false,
+            unlock_minutes: default_unlock_minutes(),
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            dir: None,
+            keep: default_backup_keep(),
+            keep_days: None,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         // Find default project directories
 
         Config {
-            database: DatabaseConfig { path: None },
+            database: DatabaseConfig::default(),
             editor: EditorConfig {
                 command: Some("vim".into()),
             },
@@ -98,24 +262,142 @@ impl Default for Config {
                 date_format: "%Y-%m-%d %H:%M:%S".to_string(),
                 entries_per_page: None,
                 stardate_mode: false,
+                age_fade: false,
+                compact: false,
+                default_sort: default_sort(),
                 timezone: None,
             },
             audio: AudioConfig::default(),
+            journal: JournalConfig::default(),
+            picker: PickerConfig::default(),
+            backup: BackupConfig::default(),
+            encryption: EncryptionConfig::default(),
+            search: BTreeMap::new(),
         }
     }
 }
 
+/// Use the config and database of profile `name` for the rest of the process
+pub fn set_profile(name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    if name != DEFAULT_PROFILE {
+        let _ = PROFILE.set(name.to_string());
+    }
+    Ok(())
+}
+
+/// Read and write the config at `path` instead of the one in the project directory
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_PATH.set(path);
+}
+
+/// The profile selected with `--profile`, if any
+pub fn active_profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// Profile names are directory names: letters, digits, `-` and `_`
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("", "", "captains-log").context("Failed to get project directories")
+}
+
+/// The config file in `dir`: `config.toml`, or a `config.json` not migrated yet
+fn config_file_in(dir: &Path) -> PathBuf {
+    let legacy = dir.join(LEGACY_CONFIG_FILE);
+    let path = dir.join(CONFIG_FILE);
+    if !path.exists() && legacy.exists() {
+        legacy
+    } else {
+        path
+    }
+}
+
+/// Config files are TOML, except `.json` ones (written by earlier versions or given with
+/// `--config`)
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// `base`, or its `profiles/<name>` subdirectory for a named profile
+fn profile_dir(base: &Path, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => base.join("profiles").join(name),
+        None => base.to_path_buf(),
+    }
+}
+
 impl Config {
+    /// Config file of a profile (`None` for the default profile)
+    pub fn profile_config_path(profile: Option<&str>) -> Result<PathBuf> {
+        Ok(config_file_in(&profile_dir(
+            project_dirs()?.config_dir(),
+            profile,
+        )))
+    }
+
+    /// Whether profile `name` has been created
+    pub fn profile_exists(name: &str) -> Result<bool> {
+        Ok(name == DEFAULT_PROFILE || Self::profile_config_path(Some(name))?.exists())
+    }
+
+    /// Names of the created profiles, sorted, without the default one
+    pub fn list_profiles() -> Result<Vec<String>> {
+        let dir = project_dirs()?.config_dir().join("profiles");
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read profiles directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| config_file_in(path).is_file())
+            .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Create profile `name` with the default configuration. Returns its config file.
+    pub fn create_profile(name: &str) -> Result<PathBuf> {
+        validate_profile_name(name)?;
+        if Self::profile_exists(name)? {
+            return Err(anyhow::anyhow!("Profile '{}' already exists", name));
+        }
+        let path = Self::profile_config_path(Some(name))?;
+        Config::default().save_to(&path)?;
+        Ok(path)
+    }
+
     pub fn load() -> Result<Self> {
         let config_path = Self::get_config_path()?;
 
         if config_path.exists() {
-            let content = fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
-
-            let config: Config = serde_json::from_str(&content)
-                .with_context(|| "Failed to parse config file as JSON")?;
-
+            let config = Self::load_from(&config_path)?;
+            if CONFIG_PATH.get().is_none()
+                && config_path
+                    .file_name()
+                    .is_some_and(|name| name == LEGACY_CONFIG_FILE)
+            {
+                let migrated = config.migrate_legacy(&config_path)?;
+                eprintln!(
+                    "Note: the configuration moved to {} (the old file is kept as {}.bak)",
+                    migrated.display(),
+                    LEGACY_CONFIG_FILE
+                );
+            }
             Ok(config)
         } else {
             let config = Config::default();
@@ -124,37 +406,101 @@ impl Config {
         }
     }
 
+    /// Put the dotted `key` (e.g. `editor.command`) back to its default value. For a saved
+    /// search (`search.<name>`) the search is removed.
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        if let Some(name) = key.strip_prefix("search.") {
+            return match self.search.remove(name) {
+                Some(_) => Ok(()),
+                None => Err(anyhow::anyhow!("No saved search named '{}'", name)),
+            };
+        }
+
+        let unknown_key = || anyhow::anyhow!("Unknown configuration key '{}'", key);
+        let (section, field) = key.split_once('.').ok_or_else(unknown_key)?;
+        let defaults = serde_json::to_value(Config::default())?;
+        let default = defaults
+            .get(section)
+            .and_then(|values| values.get(field))
+            .ok_or_else(unknown_key)?;
+
+        let mut values = serde_json::to_value(&*self)?;
+        values[section][field] = default.clone();
+        *self = serde_json::from_value(values)?;
+        Ok(())
+    }
+
+    /// Read the config file at `path`, TOML or JSON according to its extension
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file at {:?}", path))?;
+
+        if is_json(path) {
+            serde_json::from_str(&content).context("Failed to parse config file as JSON")
+        } else {
+            toml::from_str(&content).context("Failed to parse config file as TOML")
+        }
+    }
+
+    /// Write this config, read from the JSON file `legacy_path`, as `config.toml` next to
+    /// it, keeping the JSON file as `config.json.bak`. Returns the new file.
+    fn migrate_legacy(&self, legacy_path: &Path) -> Result<PathBuf> {
+        let path = legacy_path.with_file_name(CONFIG_FILE);
+        self.save_to(&path)?;
+        let backup = legacy_path.with_file_name(format!("{}.bak", LEGACY_CONFIG_FILE));
+        fs::rename(legacy_path, &backup)
+            .with_context(|| format!("Failed to move {:?} aside", legacy_path))?;
+        Ok(path)
+    }
+
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::get_config_path()?;
+        self.save_to(&Self::get_config_path()?)
+    }
 
+    fn save_to(&self, config_path: &Path) -> Result<()> {
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create config directory {:?}", parent))?;
         }
 
-        let content =
-            serde_json::to_string_pretty(self).context("Failed to serialize config to JSON")?;
+        let content = if is_json(config_path) {
+            serde_json::to_string_pretty(self).context("Failed to serialize config to JSON")?
+        } else {
+            toml::to_string_pretty(self).context("Failed to serialize config to TOML")?
+        };
 
-        fs::write(&config_path, content)
+        fs::write(config_path, content)
             .with_context(|| format!("Failed to write config file at {:?}", config_path))?;
 
         Ok(())
     }
 
+    /// Config file given with `--config`, or that of the active profile
     pub fn get_config_path() -> Result<PathBuf> {
-        let proj_dirs = ProjectDirs::from("", "", "captains-log")
-            .context("Failed to get project directories")?;
+        match CONFIG_PATH.get() {
+            Some(path) => Ok(path.clone()),
+            None => Self::profile_config_path(active_profile()),
+        }
+    }
 
-        Ok(proj_dirs.config_dir().join("config.json"))
+    /// Directory holding the entry templates used by `cl template`, shared by all profiles
+    pub fn get_templates_dir() -> Result<PathBuf> {
+        Ok(project_dirs()?.config_dir().join("templates"))
+    }
+
+    /// Picker command line for `cl pick`, `fzf` unless configured
+    pub fn get_picker_command(&self) -> String {
+        self.picker
+            .command
+            .clone()
+            .unwrap_or_else(|| "fzf".to_string())
     }
 
     pub fn get_database_path(&self) -> Result<PathBuf> {
         if let Some(custom_path) = &self.database.path {
             Ok(PathBuf::from(custom_path))
         } else {
-            let proj_dirs = ProjectDirs::from("", "", "captains-log")
-                .context("Failed to get project directories")?;
-            Ok(proj_dirs.data_dir().join("journal.db"))
+            Ok(profile_dir(project_dirs()?.data_dir(), active_profile()).join("journal.db"))
         }
     }
 
@@ -166,3 +512,83 @@ impl Config {
             .unwrap_or_else(|| "vim".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_profile_dirs() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("side-project_2").is_ok());
+        for name in ["", "../work", "my work", "."] {
+            assert!(validate_profile_name(name).is_err(), "{}", name);
+        }
+
+        let base = Path::new("/home/picard/.config/captains-log");
+        assert_eq!(profile_dir(base, None), base);
+        assert_eq!(
+            profile_dir(base, Some("work")),
+            base.join("profiles").join("work")
+        );
+    }
+
+    #[test]
+    fn test_unset() {
+        let mut config = Config::default();
+        config.editor.command = Some("nano".to_string());
+        config.display.timezone = Some("Europe/Rome".to_string());
+        config.backup.keep = 3;
+        config
+            .search
+            .insert("work".to_string(), "--journal Work".to_string());
+
+        config.unset("editor.command").unwrap();
+        assert_eq!(config.editor.command.as_deref(), Some("vim"));
+        config.unset("display.timezone").unwrap();
+        assert_eq!(config.display.timezone, None);
+        config.unset("backup.keep").unwrap();
+        assert_eq!(config.backup.keep, 10);
+        config.unset("search.work").unwrap();
+        assert!(config.search.is_empty());
+
+        for key in ["search.work", "editor", "editor.colour", "warp.factor"] {
+            assert!(config.unset(key).is_err(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_toml_config_and_migration() {
+        let dir = TempDir::new("config");
+        assert_eq!(config_file_in(&dir), dir.join(CONFIG_FILE));
+
+        let mut config = Config::default();
+        config.editor.command = Some("nano".to_string());
+        config.display.timezone = Some("Europe/Rome".to_string());
+        config
+            .search
+            .insert("work".to_string(), "--journal Work".to_string());
+
+        // A JSON config from an earlier version
+        let legacy = dir.join(LEGACY_CONFIG_FILE);
+        config.save_to(&legacy).unwrap();
+        assert!(fs::read_to_string(&legacy).unwrap().starts_with('{'));
+        assert_eq!(config_file_in(&dir), legacy);
+
+        let loaded = Config::load_from(&legacy).unwrap();
+        let migrated = loaded.migrate_legacy(&legacy).unwrap();
+        assert_eq!(migrated, dir.join(CONFIG_FILE));
+        assert_eq!(config_file_in(&dir), migrated);
+        assert!(dir.join("config.json.bak").exists());
+        assert!(!legacy.exists());
+
+        let content = fs::read_to_string(&migrated).unwrap();
+        assert!(content.contains("[editor]"));
+        let reloaded = Config::load_from(&migrated).unwrap();
+        assert_eq!(reloaded.editor.command.as_deref(), Some("nano"));
+        assert_eq!(reloaded.display.timezone.as_deref(), Some("Europe/Rome"));
+        assert_eq!(reloaded.search.get("work").unwrap(), "--journal Work");
+        assert_eq!(reloaded.database.path, None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Encryption with the `age` or `gpg` command-line tools: encrypted exports (see
+//! `export`) and encrypted entries (`cl encrypt-entry`), whose content is stored
+//! ASCII-armored.
+use anyhow::{Context, Result, anyhow};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// `--encrypt-to` value asking for a passphrase instead of a recipient
+const PASSPHRASE: &str = "passphrase";
+
+/// First line of content encrypted by `age --armor`
+pub const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+/// First line of content encrypted by `gpg --armor`
+pub const PGP_ARMOR_HEADER: &str = "-----BEGIN PGP MESSAGE-----";
+
+/// How an export is encrypted, from the `--encrypt-to` value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Encryption {
+    /// An age recipient (`age1...`) or SSH public key
+    Age(String),
+    /// A GPG key ID, fingerprint or email
+    Gpg(String),
+    /// A passphrase prompted by age, or by gpg when age isn't installed
+    Passphrase,
+}
+
+impl Encryption {
+    pub fn parse(encrypt_to: &str) -> Self {
+        let encrypt_to = encrypt_to.trim();
+        if encrypt_to.eq_ignore_ascii_case(PASSPHRASE) {
+            Encryption::Passphrase
+        } else if encrypt_to.starts_with("age1") || encrypt_to.starts_with("ssh-") {
+            Encryption::Age(encrypt_to.to_string())
+        } else {
+            Encryption::Gpg(encrypt_to.to_string())
+        }
+    }
+
+    /// The encryption command, writing the ciphertext to `output`, or ASCII-armored to
+    /// its standard output
+    pub(crate) fn command(&self, output: Option<&Path>) -> Result<Command> {
+        let use_age = match self {
+            Encryption::Age(_) => true,
+            Encryption::Gpg(_) => false,
+            Encryption::Passphrase => which::which("age").is_ok(),
+        };
+        let program = if use_age { "age" } else { "gpg" };
+        if which::which(program).is_err() {
+            return Err(anyhow!(
+                "'{}' not found on PATH; install it to encrypt exports",
+                program
+            ));
+        }
+
+        let mut command = Command::new(program);
+        match output {
+            Some(output) => command.arg("--output").arg(output),
+            None => command.arg("--armor"),
+        };
+        match self {
+            Encryption::Age(recipient) => {
+                command.arg("--recipient").arg(recipient);
+            }
+            Encryption::Gpg(recipient) => {
+                command
+                    .args(["--yes", "--encrypt", "--recipient"])
+                    .arg(recipient);
+            }
+            Encryption::Passphrase if use_age => {
+                command.arg("--passphrase");
+            }
+            Encryption::Passphrase => {
+                command.args(["--yes", "--symmetric"]);
+            }
+        }
+        Ok(command)
+    }
+
+    /// Encrypt `plaintext` to the recipient, ASCII-armored so it can be stored as text
+    pub fn encrypt_text(&self, plaintext: &str) -> Result<String> {
+        if *self == Encryption::Passphrase {
+            return Err(anyhow!(
+                "Entries are encrypted to a key: set an age recipient or GPG key, not a passphrase"
+            ));
+        }
+        run_filter(self.command(None)?, plaintext).context("Encryption failed")
+    }
+}
+
+/// Whether `content` was encrypted by `Encryption::encrypt_text`
+pub fn is_encrypted_text(content: &str) -> bool {
+    content.starts_with(AGE_ARMOR_HEADER) || content.starts_with(PGP_ARMOR_HEADER)
+}
+
+/// Decrypt content encrypted by `Encryption::encrypt_text`. age needs the `identity` file
+/// holding the private key; gpg finds its keys itself.
+pub fn decrypt_text(armored: &str, identity: Option<&str>) -> Result<String> {
+    let command = if armored.starts_with(AGE_ARMOR_HEADER) {
+        let identity = identity.ok_or_else(|| {
+            anyhow!("No age identity to decrypt with; set encryption.identity to your key file")
+        })?;
+        let mut command = Command::new("age");
+        command.arg("--decrypt").arg("--identity").arg(identity);
+        command
+    } else if armored.starts_with(PGP_ARMOR_HEADER) {
+        let mut command = Command::new("gpg");
+        command.args(["--quiet", "--decrypt"]);
+        command
+    } else {
+        return Err(anyhow!("Not encrypted content"));
+    };
+    run_filter(command, armored).context("Decryption failed")
+}
+
+/// Run `command` with `input` on its standard input, returning its standard output
+fn run_filter(mut command: Command, input: &str) -> Result<String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start '{}'; is it installed?", program))?;
+
+    // Written from another thread, so a large output can't fill the pipe and block both
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Failed to open the command's input")?;
+    let input = input.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!("'{}' failed ({})", program, output.status));
+    }
+    writer
+        .join()
+        .map_err(|_| anyhow!("Failed to write to '{}'", program))??;
+    String::from_utf8(output.stdout).context("The output is not UTF-8 text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_encryption() {
+        assert_eq!(Encryption::parse("passphrase"), Encryption::Passphrase);
+        assert_eq!(
+            Encryption::parse("age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p"),
+            Encryption::Age(
+                "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p".to_string()
+            )
+        );
+        assert_eq!(
+            Encryption::parse("picard@enterprise.example"),
+            Encryption::Gpg("picard@enterprise.example".to_string())
+        );
+    }
+
+    #[test]
+    fn test_encrypted_text() {
+        assert!(is_encrypted_text(&format!("{}\nYWdl\n", AGE_ARMOR_HEADER)));
+        assert!(is_encrypted_text(&format!(
+            "{}\n\nhQEM\n",
+            PGP_ARMOR_HEADER
+        )));
+        assert!(!is_encrypted_text("Captain's log, stardate 41153.7"));
+        assert!(decrypt_text("Captain's log", None).is_err());
+        assert!(
+            Encryption::Passphrase
+                .encrypt_text("Captain's log")
+                .is_err()
+        );
+    }
+}
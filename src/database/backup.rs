@@ -0,0 +1,282 @@
+//! Database backups (`cl backup`): snapshots taken with SQLite's online backup API, named
+//! after the time they were taken and pruned according to the `backup` config section.
+//! `cl restore` puts one back in place of the database.
+use super::{Database, encryption};
+use crate::config::BackupConfig;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration as ChronoDuration, NaiveDateTime};
+use rusqlite::Connection;
+use rusqlite::backup::Backup;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Timestamp in backup file names, which sorts oldest first
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Directory for backups when none is configured: `backups/` next to the database
+pub fn default_backup_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new("")).join("backups")
+}
+
+impl Database {
+    /// Copy the database to `dest` with SQLite's online backup API, so the copy is
+    /// consistent even while another `cl` process writes to the database.
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        let mut dest_conn = Connection::open(dest)
+            .with_context(|| format!("Failed to create backup at {:?}", dest))?;
+        encryption::key_like(self, &dest_conn)?;
+        let backup = Backup::new(&self.conn, &mut dest_conn)?;
+        backup
+            .run_to_completion(256, Duration::from_millis(10), None)
+            .with_context(|| format!("Failed to write backup to {:?}", dest))?;
+        Ok(())
+    }
+}
+
+/// Snapshot the database at `db_path` into `dir` as `<name>-YYYYMMDD-HHMMSS.db`
+pub fn create_backup(
+    db: &Database,
+    db_path: &Path,
+    dir: &Path,
+    now: NaiveDateTime,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!(
+        "{}-{}.db",
+        backup_prefix(db_path),
+        now.format(BACKUP_TIME_FORMAT)
+    ));
+    if path.exists() {
+        return Err(anyhow!("Backup {} already exists", path.display()));
+    }
+    db.backup_to(&path)?;
+    Ok(path)
+}
+
+/// Backups of the database at `db_path` found in `dir`, with their times, newest first
+pub fn list_backups(db_path: &Path, dir: &Path) -> Result<Vec<(PathBuf, NaiveDateTime)>> {
+    let prefix = format!("{}-", backup_prefix(db_path));
+    let mut backups = Vec::new();
+    if !dir.is_dir() {
+        return Ok(backups);
+    }
+    for dir_entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = dir_entry?.path();
+        let time = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix)?.strip_suffix(".db"))
+            .and_then(|time| NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok());
+        if let Some(time) = time {
+            backups.push((path, time));
+        }
+    }
+    backups.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+    Ok(backups)
+}
+
+/// Delete the backups in `dir` beyond the `keep` most recent ones, and those older than
+/// `keep_days` when set. The most recent backup is never deleted. Returns the deleted files.
+pub fn prune_backups(
+    db_path: &Path,
+    dir: &Path,
+    retention: &BackupConfig,
+    now: NaiveDateTime,
+) -> Result<Vec<PathBuf>> {
+    let cutoff = retention
+        .keep_days
+        .map(|days| now - ChronoDuration::days(days as i64));
+
+    let mut deleted = Vec::new();
+    for (index, (path, time)) in list_backups(db_path, dir)?.into_iter().enumerate() {
+        let too_many = index >= retention.keep.max(1);
+        let too_old = index > 0 && cutoff.is_some_and(|cutoff| time < cutoff);
+        if too_many || too_old {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            deleted.push(path);
+        }
+    }
+    Ok(deleted)
+}
+
+/// Columns a database must have to be restored; older ones are migrated when next opened
+const REQUIRED_ENTRY_COLUMNS: &[&str] = &["id", "timestamp", "title", "content"];
+
+/// Check that `backup` is an intact journal database. Returns its number of entries.
+pub fn validate_backup(backup: &Path) -> Result<usize> {
+    if !backup.is_file() {
+        return Err(anyhow!("Backup not found: {}", backup.display()));
+    }
+    let db = Database::new_readonly(backup)?;
+    let conn = db.connection();
+
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .with_context(|| format!("{} is not a SQLite database", backup.display()))?;
+    if check != "ok" {
+        return Err(anyhow!(
+            "{} is damaged (integrity check: {})",
+            backup.display(),
+            check
+        ));
+    }
+
+    if !db.has_table("entries")? {
+        return Err(anyhow!(
+            "{} is not a Captain's Log database (no entries table)",
+            backup.display()
+        ));
+    }
+    for column in REQUIRED_ENTRY_COLUMNS {
+        if !db.has_column("entries", column)? {
+            return Err(anyhow!(
+                "{} is not a Captain's Log database (entries have no {} column)",
+                backup.display(),
+                column
+            ));
+        }
+    }
+
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))?;
+    Ok(count as usize)
+}
+
+/// Replace the database at `db_path` with a copy of `backup`, moving the current database
+/// aside as `<name>.pre-restore-YYYYMMDD-HHMMSS.db`. Returns where it was moved, if it existed.
+pub fn restore_backup(
+    backup: &Path,
+    db_path: &Path,
+    now: NaiveDateTime,
+) -> Result<Option<PathBuf>> {
+    // Copy first, so a failure leaves the current database untouched
+    let restoring = db_path.with_extension("db.restoring");
+    if restoring.exists() {
+        fs::remove_file(&restoring)?;
+    }
+    Database::new_readonly(backup)?.backup_to(&restoring)?;
+
+    let stash = if db_path.exists() {
+        let stash = db_path.with_file_name(format!(
+            "{}.pre-restore-{}.db",
+            backup_prefix(db_path),
+            now.format(BACKUP_TIME_FORMAT)
+        ));
+        fs::rename(db_path, &stash)
+            .with_context(|| format!("Failed to move {} aside", db_path.display()))?;
+        // The write-ahead log belongs to the moved database, not to the restored one
+        for suffix in ["-wal", "-shm"] {
+            let sidecar = with_suffix(db_path, suffix);
+            if sidecar.exists() {
+                fs::rename(&sidecar, with_suffix(&stash, suffix))?;
+            }
+        }
+        Some(stash)
+    } else {
+        None
+    };
+    fs::rename(&restoring, db_path)
+        .with_context(|| format!("Failed to move the backup to {}", db_path.display()))?;
+    Ok(stash)
+}
+
+/// `path` with `suffix` appended to its file name (`journal.db` -> `journal.db-wal`)
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Backup file names start with the database file name (`journal` for `journal.db`)
+fn backup_prefix(db_path: &Path) -> String {
+    db_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("journal")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::NaiveDate;
+
+    fn at(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 9, day)
+            .and_then(|date| date.and_hms_opt(8, 0, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backup_and_prune() {
+        let dir = TempDir::new("backup");
+        let db_path = dir.join("journal.db");
+        let db = Database::new_with_path(&db_path).unwrap();
+        db.connection()
+            .execute(
+                "INSERT INTO entries (content) VALUES ('Captain''s log')",
+                [],
+            )
+            .unwrap();
+
+        let backups_dir = default_backup_dir(&db_path);
+        for day in 1..=4 {
+            create_backup(&db, &db_path, &backups_dir, at(day)).unwrap();
+        }
+        let newest = backups_dir.join("journal-20250904-080000.db");
+        let count: i64 = Connection::open(&newest)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let retention = BackupConfig {
+            dir: None,
+            keep: 3,
+            keep_days: Some(2),
+        };
+        let deleted = prune_backups(&db_path, &backups_dir, &retention, at(5)).unwrap();
+        assert_eq!(deleted.len(), 2);
+        let kept: Vec<_> = list_backups(&db_path, &backups_dir)
+            .unwrap()
+            .into_iter()
+            .map(|(_, time)| time)
+            .collect();
+        assert_eq!(kept, vec![at(4), at(3)]);
+    }
+
+    #[test]
+    fn test_validate_and_restore_backup() {
+        let dir = TempDir::new("restore");
+        let db_path = dir.join("journal.db");
+        let db = Database::new_with_path(&db_path).unwrap();
+        let add_entry = || {
+            db.connection()
+                .execute(
+                    "INSERT INTO entries (content) VALUES ('Captain''s log')",
+                    [],
+                )
+                .unwrap()
+        };
+        add_entry();
+        let backup = create_backup(&db, &db_path, &dir, at(1)).unwrap();
+        add_entry();
+        assert_eq!(validate_backup(&backup).unwrap(), 1);
+
+        let not_a_journal = dir.join("notes.db");
+        Connection::open(&not_a_journal)
+            .unwrap()
+            .execute("CREATE TABLE notes (body TEXT)", [])
+            .unwrap();
+        assert!(validate_backup(&not_a_journal).is_err());
+
+        let stash = restore_backup(&backup, &db_path, at(2)).unwrap().unwrap();
+        assert_eq!(stash, dir.join("journal.pre-restore-20250902-080000.db"));
+        assert_eq!(validate_backup(&db_path).unwrap(), 1);
+        assert_eq!(validate_backup(&stash).unwrap(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+//! Encryption at rest with SQLCipher, available when built with `--features sqlcipher`.
+//!
+//! An encrypted database is recognised by the missing plain SQLite header. Its passphrase
+//! is read once per process (from `CL_DB_KEY`, or prompted) and applied to every
+//! connection opened on it, including backups, which stay encrypted with the same key.
+use super::Database;
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, OpenFlags};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable holding the passphrase, so a shell session is prompted only once
+pub const KEY_ENV: &str = "CL_DB_KEY";
+
+/// First bytes of every unencrypted SQLite database
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Passphrase of the encrypted databases opened by this process
+static KEY: OnceLock<String> = OnceLock::new();
+
+pub fn set_key(key: String) {
+    let _ = KEY.set(key);
+}
+
+/// Passphrase given for this process, if any
+pub fn key() -> Option<&'static str> {
+    KEY.get().map(String::as_str)
+}
+
+/// Whether the database at `path` exists and is encrypted (not a plain SQLite file)
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut header = [0; SQLITE_HEADER.len()];
+    match File::open(path).and_then(|mut file| file.read(&mut header)) {
+        Ok(0) | Err(_) => false,
+        Ok(read) => header[..read] != SQLITE_HEADER[..read],
+    }
+}
+
+fn check_sqlcipher() -> Result<()> {
+    if cfg!(feature = "sqlcipher") {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "This build has no database encryption support; rebuild with `cargo build --features sqlcipher`"
+        ))
+    }
+}
+
+/// Unlock `conn` when the database at `path` is encrypted. Returns whether it is.
+pub(super) fn unlock(conn: &Connection, path: &Path) -> Result<bool> {
+    if !is_encrypted(path) {
+        return Ok(false);
+    }
+    check_sqlcipher().with_context(|| format!("{} is encrypted", path.display()))?;
+    let key = key().ok_or_else(|| {
+        anyhow!(
+            "{} is encrypted and no passphrase was given (set {})",
+            path.display(),
+            KEY_ENV
+        )
+    })?;
+
+    conn.pragma_update(None, "key", key)?;
+    // The key is only checked when the database is first read
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|_| anyhow!("Wrong passphrase for {}", path.display()))?;
+    Ok(true)
+}
+
+/// Whether `key` opens the encrypted database at `path`
+pub fn check_key(path: &Path, key: &str) -> Result<bool> {
+    check_sqlcipher()?;
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.pragma_update(None, "key", key)?;
+    Ok(conn
+        .query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .is_ok())
+}
+
+/// Key a new connection the way `db` is keyed, for copies of an encrypted database
+pub(super) fn key_like(db: &Database, conn: &Connection) -> Result<()> {
+    if db.encrypted
+        && let Some(key) = key()
+    {
+        conn.pragma_update(None, "key", key)?;
+    }
+    Ok(())
+}
+
+impl Database {
+    /// Write an encrypted copy of the database to `dest`, keyed with `passphrase`
+    pub fn export_encrypted(&self, dest: &Path, passphrase: &str) -> Result<()> {
+        check_sqlcipher()?;
+        self.export_with_key(dest, passphrase)
+    }
+
+    /// Write an unencrypted copy of the (encrypted) database to `dest`
+    pub fn export_decrypted(&self, dest: &Path) -> Result<()> {
+        check_sqlcipher()?;
+        self.export_with_key(dest, "")
+    }
+
+    /// `sqlcipher_export` into a database attached with `key` (empty for no encryption)
+    fn export_with_key(&self, dest: &Path, key: &str) -> Result<()> {
+        let dest_str = dest
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid path: {}", dest.display()))?;
+        self.conn
+            .execute("ATTACH DATABASE ?1 AS export KEY ?2", [dest_str, key])?;
+        let exported = self
+            .conn
+            .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()));
+        self.conn.execute("DETACH DATABASE export", [])?;
+        exported.with_context(|| format!("Failed to write {}", dest.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_is_encrypted() {
+        let dir = TempDir::new("encryption");
+        let db_path = dir.join("journal.db");
+        Database::new_with_path(&db_path).unwrap();
+        assert!(!is_encrypted(&db_path));
+        assert!(!is_encrypted(&dir.join("missing.db")));
+
+        let scrambled = dir.join("scrambled.db");
+        fs::write(&scrambled, [0x5a; 64]).unwrap();
+        assert!(is_encrypted(&scrambled));
+    }
+}
@@ -0,0 +1,117 @@
+//! Database health checks for `cl doctor`.
+use super::Database;
+use anyhow::Result;
+
+/// Tables and columns written by the migrations of the current version
+const EXPECTED_SCHEMA: &[(&str, &[&str])] = &[
+    (
+        "entries",
+        &[
+            "id",
+            "timestamp",
+            "title",
+            "content",
+            "audio_path",
+            "image_paths",
+            "journal",
+            "status",
+            "starred",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+        ],
+    ),
+    ("tags", &["id", "name"]),
+    ("entry_tags", &["entry_id", "tag_id"]),
+    (
+        "entry_revisions",
+        &["id", "entry_id", "title", "content", "revised_at"],
+    ),
+    ("undo_log", &["id", "action", "recorded_at"]),
+    (
+        "journals",
+        &[
+            "name",
+            "description",
+            "color",
+            "default_template",
+            "archived",
+        ],
+    ),
+];
+
+impl Database {
+    /// Problems reported by `PRAGMA integrity_check`; empty when the database is intact
+    pub fn integrity_problems(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows.into_iter().filter(|row| row != "ok").collect())
+    }
+
+    /// Tables and columns (`table.column`) the migrations would add to this database
+    pub fn missing_schema(&self) -> Result<Vec<String>> {
+        let mut missing = Vec::new();
+        for (table, columns) in EXPECTED_SCHEMA {
+            if !self.has_table(table)? {
+                missing.push(table.to_string());
+                continue;
+            }
+            for column in *columns {
+                if !self.has_column(table, column)? {
+                    missing.push(format!("{}.{}", table, column));
+                }
+            }
+        }
+        Ok(missing)
+    }
+
+    /// IDs of the entries whose timestamp is missing or not a date SQLite can read
+    pub fn invalid_timestamp_ids(&self) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM entries
+             WHERE timestamp IS NULL OR datetime(timestamp) IS NULL
+             ORDER BY id",
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_checks() {
+        let db = Database::new_with_path(":memory:").unwrap();
+        assert!(db.integrity_problems().unwrap().is_empty());
+        assert!(db.missing_schema().unwrap().is_empty());
+        assert!(db.ensure_migrated().is_ok());
+
+        let conn = db.connection();
+        conn.execute(
+            "INSERT INTO entries (timestamp, content) VALUES ('2025-09-07 08:00:00.000+00:00', 'Fine')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO entries (timestamp, content) VALUES ('stardate 41153.7', 'Odd')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO entries (timestamp, content) VALUES (NULL, 'Lost')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(db.invalid_timestamp_ids().unwrap(), vec![2, 3]);
+
+        conn.execute("DROP TABLE undo_log", []).unwrap();
+        assert_eq!(db.missing_schema().unwrap(), vec!["undo_log"]);
+        assert!(db.ensure_migrated().is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Passphrases of encrypted databases kept in the OS keychain (macOS Keychain, Secret
+//! Service, Windows Credential Manager), available when built with `--features keychain`.
+//!
+//! Each database has its own keychain item, named after its absolute path.
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+/// Keychain service the passphrases are stored under
+#[cfg(feature = "keychain")]
+const SERVICE: &str = "captains-log";
+
+pub fn check_keychain() -> Result<()> {
+    if cfg!(feature = "keychain") {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "This build has no keychain support; rebuild with `cargo build --features keychain`"
+        ))
+    }
+}
+
+/// Keychain account of the database at `db_path`
+#[cfg_attr(not(feature = "keychain"), allow(dead_code))]
+fn account(db_path: &Path) -> String {
+    db_path
+        .canonicalize()
+        .unwrap_or_else(|_| db_path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+#[cfg(feature = "keychain")]
+fn keychain_entry(db_path: &Path) -> Result<keyring::Entry> {
+    Ok(keyring::Entry::new(SERVICE, &account(db_path))?)
+}
+
+/// The stored passphrase of the database at `db_path`, if any
+pub fn get_passphrase(db_path: &Path) -> Result<Option<String>> {
+    check_keychain()?;
+    #[cfg(feature = "keychain")]
+    {
+        match keychain_entry(db_path)?.get_password() {
+            Ok(passphrase) => Ok(Some(passphrase)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow!("Cannot read the keychain: {}", e)),
+        }
+    }
+    #[cfg(not(feature = "keychain"))]
+    {
+        let _ = db_path;
+        Ok(None)
+    }
+}
+
+/// Store (or replace) the passphrase of the database at `db_path`
+pub fn store_passphrase(db_path: &Path, passphrase: &str) -> Result<()> {
+    check_keychain()?;
+    #[cfg(feature = "keychain")]
+    keychain_entry(db_path)?
+        .set_password(passphrase)
+        .map_err(|e| anyhow!("Cannot write to the keychain: {}", e))?;
+    #[cfg(not(feature = "keychain"))]
+    let _ = (db_path, passphrase);
+    Ok(())
+}
+
+/// Remove the stored passphrase of the database at `db_path`. Returns whether there was one.
+pub fn delete_passphrase(db_path: &Path) -> Result<bool> {
+    if check_keychain().is_err() {
+        return Ok(false);
+    }
+    #[cfg(feature = "keychain")]
+    {
+        match keychain_entry(db_path)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(anyhow!("Cannot write to the keychain: {}", e)),
+        }
+    }
+    #[cfg(not(feature = "keychain"))]
+    {
+        let _ = db_path;
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account() {
+        assert_eq!(
+            account(Path::new("/nonexistent/journal.db")),
+            "/nonexistent/journal.db"
+        );
+        let dir = std::env::temp_dir();
+        assert!(Path::new(&account(&dir)).is_absolute());
+    }
+}
@@ -1,36 +1,161 @@
-use crate::config::Config;
+use crate::config::{Config, DatabaseConfig};
+use crate::journal::extract_tags;
 use anyhow::{Context, Result};
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub mod backup;
+pub mod encryption;
+mod health;
+pub mod keychain;
+pub mod session;
+
+/// Database path (and `-d` value) of a journal kept in memory and discarded on exit
+pub const IN_MEMORY: &str = ":memory:";
+
+/// Whether `db_path` names an in-memory database rather than a file
+pub fn is_in_memory(db_path: &Path) -> bool {
+    db_path.as_os_str() == IN_MEMORY
+}
+
+/// SQLite URI opening `db_path` as immutable: no locks, no -wal or -shm files
+fn immutable_uri(db_path: &Path) -> String {
+    let path = db_path
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23");
+    format!("file:{}?immutable=1", path)
+}
 
 pub struct Database {
     conn: Connection,
+    /// Encrypted with SQLCipher, and unlocked with the process passphrase
+    encrypted: bool,
 }
 
 impl Database {
     pub fn new(config: &Config) -> Result<Self> {
         let db_path = config.get_database_path()?;
 
-        Self::new_with_path(&db_path)
+        Self::new_with_config(&db_path, &config.database)
     }
 
+    /// Create an empty journal in memory, with the full schema, discarded when dropped
+    pub fn new_in_memory() -> Result<Self> {
+        Self::new_with_path(IN_MEMORY)
+    }
+
+    /// Open (or create) the database at `db_path` with the default connection settings
     pub fn new_with_path<P: AsRef<std::path::Path>>(db_path: P) -> Result<Self> {
+        Self::new_with_config(db_path, &DatabaseConfig::default())
+    }
+
+    /// Open (or create) the database at `db_path` with the journal mode, busy timeout and
+    /// foreign key enforcement of `settings`
+    pub fn new_with_config<P: AsRef<std::path::Path>>(
+        db_path: P,
+        settings: &DatabaseConfig,
+    ) -> Result<Self> {
         let db_path = db_path.as_ref();
 
         // Create directory if it doesn't exist
-        if let Some(parent) = db_path.parent() {
+        if !is_in_memory(db_path)
+            && let Some(parent) = db_path.parent()
+        {
             fs::create_dir_all(parent)?;
         }
 
         let conn = Connection::open(db_path)
             .with_context(|| format!("Failed to open database at {:?}", db_path))?;
+        let encrypted = encryption::unlock(&conn, db_path)?;
 
-        let mut db = Database { conn };
+        let mut db = Database { conn, encrypted };
+        db.configure(settings)?;
         db.run_migrations()?;
 
         Ok(db)
     }
 
+    /// Open an existing database without write access.
+    ///
+    /// Migrations are not run and no directories or journal files are created, so the
+    /// database must already exist (e.g. a backup or a journal on read-only media).
+    pub fn new_readonly<P: AsRef<std::path::Path>>(db_path: P) -> Result<Self> {
+        let db_path = db_path.as_ref();
+        if is_in_memory(db_path) {
+            return Err(anyhow::anyhow!(
+                "An in-memory database starts empty and cannot be opened read-only"
+            ));
+        }
+
+        // Reading a WAL database needs its -shm file, which cannot be created on
+        // read-only media; such a database is then opened as immutable
+        Self::open_readonly(db_path, false)
+            .or_else(|e| Self::open_readonly(db_path, true).map_err(|_| e))
+    }
+
+    fn open_readonly(db_path: &Path, immutable: bool) -> Result<Self> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let conn = if immutable {
+            Connection::open_with_flags(immutable_uri(db_path), flags | OpenFlags::SQLITE_OPEN_URI)
+        } else {
+            Connection::open_with_flags(db_path, flags)
+        }
+        .with_context(|| format!("Failed to open database read-only at {:?}", db_path))?;
+        let encrypted = encryption::unlock(&conn, db_path)?;
+        conn.busy_timeout(Duration::from_millis(
+            DatabaseConfig::default().busy_timeout_ms,
+        ))?;
+        // Opening is lazy: read the schema so a missing -shm file fails here
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .with_context(|| format!("Failed to read database at {:?}", db_path))?;
+
+        Ok(Database { conn, encrypted })
+    }
+
+    /// Fail unless the migrations of this version were applied, which a read-only
+    /// connection cannot do, so entry queries don't trip over missing columns
+    pub fn ensure_migrated(&self) -> Result<()> {
+        let missing = self.missing_schema()?;
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The database was written by an older version of cl (missing {}); run any command once without --readonly to upgrade it",
+                missing.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply the connection settings. Concurrent `cl` processes (e.g. `cl watch` next to
+    /// interactive use) wait for each other's locks instead of failing with
+    /// "database is locked", and WAL lets them read while another one writes.
+    fn configure(&self, settings: &DatabaseConfig) -> Result<()> {
+        self.conn
+            .busy_timeout(Duration::from_millis(settings.busy_timeout_ms))?;
+        self.conn
+            .pragma_update(None, "foreign_keys", settings.foreign_keys)?;
+        // In-memory databases keep their "memory" mode whatever is asked
+        self.conn
+            .pragma_update_and_check(None, "journal_mode", &settings.journal_mode, |row| {
+                row.get::<_, String>(0)
+            })
+            .with_context(|| format!("Failed to set journal mode '{}'", settings.journal_mode))?;
+        Ok(())
+    }
+
+    /// Move the write-ahead log into the database file, so the file alone holds everything
+    /// (before it is replaced or copied by file operations)
+    pub fn checkpoint(&self) -> Result<()> {
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
     fn run_migrations(&mut self) -> Result<()> {
         // Create entries table
         self.conn.execute(
@@ -42,12 +167,100 @@ impl Database {
                 audio_path TEXT,
                 image_paths TEXT,
                 journal TEXT DEFAULT 'Personal',
+                status TEXT NOT NULL DEFAULT 'final',
+                starred INTEGER NOT NULL DEFAULT 0,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                deleted_at DATETIME
+            )",
+            [],
+        )?;
+
+        // Databases created before entry drafts lack the status column
+        if !self.has_column("entries", "status")? {
+            self.conn.execute(
+                "ALTER TABLE entries ADD COLUMN status TEXT NOT NULL DEFAULT 'final'",
+                [],
+            )?;
+        }
+
+        if !self.has_column("entries", "starred")? {
+            self.conn.execute(
+                "ALTER TABLE entries ADD COLUMN starred INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Deleted entries stay in the trash until it is emptied
+        if !self.has_column("entries", "deleted_at")? {
+            self.conn
+                .execute("ALTER TABLE entries ADD COLUMN deleted_at DATETIME", [])?;
+        }
+
+        // Tags parsed from `#hashtags` in the content (and the `tags:` frontmatter field)
+        let backfill_tags = !self.has_table("entry_tags")?;
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS tags (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS entry_tags (
+                entry_id INTEGER NOT NULL REFERENCES entries(id),
+                tag_id INTEGER NOT NULL REFERENCES tags(id),
+                PRIMARY KEY (entry_id, tag_id)
             )",
             [],
         )?;
 
+        if backfill_tags {
+            self.backfill_tags()?;
+        }
+
+        // Earlier versions of entries, saved before each edit
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS entry_revisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_id INTEGER NOT NULL REFERENCES entries(id),
+                title TEXT,
+                content TEXT NOT NULL,
+                revised_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // How to reverse the last delete, edit or move (a single row, see `cl undo`)
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS undo_log (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                action TEXT NOT NULL,
+                recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Optional per-journal settings; journals themselves exist through their entries
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS journals (
+                name TEXT PRIMARY KEY,
+                description TEXT,
+                color TEXT,
+                default_template TEXT,
+                archived INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        if !self.has_column("journals", "archived")? {
+            self.conn.execute(
+                "ALTER TABLE journals ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
         // Create indexes for better performance
         self.conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_entries_timestamp ON entries(timestamp)",
@@ -59,10 +272,148 @@ impl Database {
             [],
         )?;
 
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_entry_tags_tag_id ON entry_tags(tag_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_entry_revisions_entry_id ON entry_revisions(entry_id)",
+            [],
+        )?;
+
         Ok(())
     }
 
+    fn has_table(&self, table: &str) -> Result<bool> {
+        let exists = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            [table],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Tag the entries written before tags existed from the hashtags in their content
+    fn backfill_tags(&self) -> Result<()> {
+        let mut stmt = self.conn.prepare("SELECT id, content FROM entries")?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (id, content) in entries {
+            for tag in extract_tags(&content) {
+                self.conn
+                    .execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [&tag])?;
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id)
+                     SELECT ?1, id FROM tags WHERE name = ?2",
+                    rusqlite::params![id, tag],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn has_column(&self, table: &str, column: &str) -> Result<bool> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(names.iter().any(|name| name == column))
+    }
+
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_connection_settings() {
+        let dir = TempDir::new("connection");
+        let settings_of = |db: &Database| -> (String, i64, i64) {
+            let conn = db.connection();
+            (
+                conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))
+                    .unwrap(),
+                conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+                    .unwrap(),
+                conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+                    .unwrap(),
+            )
+        };
+
+        let db = Database::new_with_path(dir.join("journal.db")).unwrap();
+        assert_eq!(settings_of(&db), ("wal".to_string(), 1, 5000));
+        db.checkpoint().unwrap();
+
+        let settings = DatabaseConfig {
+            journal_mode: "delete".to_string(),
+            busy_timeout_ms: 250,
+            foreign_keys: false,
+            ..DatabaseConfig::default()
+        };
+        let db = Database::new_with_config(dir.join("other.db"), &settings).unwrap();
+        assert_eq!(settings_of(&db), ("delete".to_string(), 0, 250));
+    }
+
+    #[test]
+    fn test_in_memory_database() {
+        assert!(is_in_memory(Path::new(IN_MEMORY)));
+        assert!(!is_in_memory(Path::new("memory.db")));
+
+        // In-memory databases keep their "memory" journal mode
+        let db = Database::new_in_memory().unwrap();
+        let mode: String = db
+            .connection()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "memory");
+        assert!(db.missing_schema().unwrap().is_empty());
+
+        assert!(Database::new_readonly(IN_MEMORY).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_readonly_wal_database_on_readonly_media() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("readonly");
+        let db_path = dir.join("journal #1.db");
+        {
+            let db = Database::new_with_path(&db_path).unwrap();
+            db.connection()
+                .execute("INSERT INTO entries (content) VALUES ('Kept')", [])
+                .unwrap();
+        }
+        // Closing the last connection removes the -wal and -shm files
+        assert!(!dir.join("journal #1.db-shm").exists());
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let count_of = |db: &Database| -> i64 {
+            db.connection()
+                .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+                .unwrap()
+        };
+        // The fallback for when the -shm file cannot be created (root ignores the mode)
+        let db = Database::open_readonly(&db_path, true).unwrap();
+        assert_eq!(count_of(&db), 1);
+        assert!(!dir.join("journal #1.db-shm").exists());
+        drop(db);
+        let db = Database::new_readonly(&db_path).unwrap();
+        assert_eq!(count_of(&db), 1);
+        drop(db);
+    }
+}
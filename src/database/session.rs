@@ -0,0 +1,153 @@
+//! Unlocked sessions (`cl unlock`): the passphrase of an encrypted database cached in a
+//! file only its owner can read, until it expires or `cl lock` removes it.
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize)]
+struct Session {
+    db_path: String,
+    key: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Directory of the session files: the user's runtime directory (in memory on most
+/// Linux systems), or the temporary directory
+pub fn session_dir() -> PathBuf {
+    ProjectDirs::from("", "", "captains-log")
+        .and_then(|dirs| dirs.runtime_dir().map(Path::to_path_buf))
+        .unwrap_or_else(|| std::env::temp_dir().join("captains-log"))
+}
+
+/// Absolute path of the database, identifying its session
+fn database_id(db_path: &Path) -> String {
+    db_path
+        .canonicalize()
+        .unwrap_or_else(|_| db_path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn session_file(dir: &Path, db_path: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    database_id(db_path).hash(&mut hasher);
+    dir.join(format!("session-{:016x}.json", hasher.finish()))
+}
+
+/// Cache `key` for the database at `db_path` until `expires_at`
+pub fn save_session(
+    dir: &Path,
+    db_path: &Path,
+    key: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<PathBuf> {
+    create_private_dir(dir)?;
+    let path = session_file(dir, db_path);
+    let session = Session {
+        db_path: database_id(db_path),
+        key: key.to_string(),
+        expires_at,
+    };
+
+    // Replaced rather than truncated, so the new file gets the restricted permissions
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(serde_json::to_string(&session)?.as_bytes())?;
+    Ok(path)
+}
+
+/// The cached key of the database at `db_path`, if its session hasn't expired at `now`.
+/// An expired session is removed.
+pub fn load_session(dir: &Path, db_path: &Path, now: DateTime<Utc>) -> Result<Option<String>> {
+    let path = session_file(dir, db_path);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    match serde_json::from_str::<Session>(&content) {
+        Ok(session) if session.db_path == database_id(db_path) && session.expires_at > now => {
+            Ok(Some(session.key))
+        }
+        _ => {
+            fs::remove_file(&path)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Remove the session of the database at `db_path`. Returns whether there was one.
+pub fn clear_session(dir: &Path, db_path: &Path) -> Result<bool> {
+    let path = session_file(dir, db_path);
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(true)
+}
+
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::Duration;
+
+    #[test]
+    fn test_session_lifecycle() {
+        let dir = TempDir::new("session");
+        let db_path = dir.join("journal.db");
+        let other_db = dir.join("work.db");
+        let now = Utc::now();
+
+        assert_eq!(load_session(&dir, &db_path, now).unwrap(), None);
+        let path = save_session(&dir, &db_path, "engage", now + Duration::minutes(15)).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(
+            load_session(&dir, &db_path, now).unwrap().as_deref(),
+            Some("engage")
+        );
+        assert_eq!(load_session(&dir, &other_db, now).unwrap(), None);
+
+        // Expired sessions are removed when read
+        let later = now + Duration::minutes(16);
+        assert_eq!(load_session(&dir, &db_path, later).unwrap(), None);
+        assert!(!path.exists());
+
+        save_session(&dir, &db_path, "engage", now + Duration::minutes(15)).unwrap();
+        assert!(clear_session(&dir, &db_path).unwrap());
+        assert!(!clear_session(&dir, &db_path).unwrap());
+    }
+}
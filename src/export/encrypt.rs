@@ -0,0 +1,66 @@
+//! Encrypted exports: the document is piped through `age` or `gpg` (see `crypto`), so
+//! the plaintext never touches the disk.
+use crate::crypto::Encryption;
+use anyhow::{Context, Result, anyhow};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Stdio};
+
+/// Destination of an exported file: written directly, or piped into an encryption command
+pub(super) enum Output {
+    File(BufWriter<File>),
+    Encrypted(Child, BufWriter<ChildStdin>),
+}
+
+impl Output {
+    pub(super) fn create(path: &Path, encryption: Option<&Encryption>) -> Result<Self> {
+        match encryption {
+            None => Ok(Output::File(BufWriter::new(File::create(path)?))),
+            Some(encryption) => {
+                let mut child = encryption
+                    .command(Some(path))?
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .context("Failed to start the encryption command")?;
+                let stdin = child
+                    .stdin
+                    .take()
+                    .context("Failed to open the encryption command's input")?;
+                Ok(Output::Encrypted(child, BufWriter::new(stdin)))
+            }
+        }
+    }
+
+    /// Flush the file, or close the encryption command's input and wait for it to finish
+    pub(super) fn close(self) -> Result<()> {
+        match self {
+            Output::File(mut file) => file.flush()?,
+            Output::Encrypted(mut child, stdin) => {
+                // Dropping stdin closes the pipe, ending the encryption
+                drop(stdin.into_inner().map_err(|e| e.into_error())?);
+                let status = child.wait()?;
+                if !status.success() {
+                    return Err(anyhow!("Encryption failed ({})", status));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::File(file) => file.write(buf),
+            Output::Encrypted(_, stdin) => stdin.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::File(file) => file.flush(),
+            Output::Encrypted(_, stdin) => stdin.flush(),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::cli::dateparser::parse_relative_date;
-use crate::journal::{Entry, Journal};
+use crate::crypto::Encryption;
+use crate::journal::{Entry, EntryFilter, Journal, STATUS_FINAL};
 use anyhow::{Context, Result};
 use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
 use chrono_tz::Tz;
@@ -7,7 +8,19 @@ use pulldown_cmark::{Event, Options, Tag, TagEnd};
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+mod encrypt;
+mod site;
+mod vault;
+mod writer;
+
+use encrypt::Output;
+use writer::{DocumentWriter, Format};
+
+/// Directory, next to the output file, that attachments are copied into
+const ASSETS_DIR: &str = "assets";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportData {
@@ -19,11 +32,41 @@ pub struct ExportData {
 pub struct Exporter<'a> {
     journal: &'a Journal,
     timezone: Option<String>,
+    with_footer: bool,
+    /// Directory the attachment paths of entries are relative to (the database directory)
+    attachments_root: Option<PathBuf>,
+    /// Encrypt the files written (not stdout)
+    encryption: Option<Encryption>,
 }
 
 impl<'a> Exporter<'a> {
     pub fn new(journal: &'a Journal, timezone: Option<String>) -> Self {
-        Self { journal, timezone }
+        Self {
+            journal,
+            timezone,
+            with_footer: false,
+            attachments_root: None,
+            encryption: None,
+        }
+    }
+
+    /// Pipe exported files through age or gpg instead of writing plaintext.
+    pub fn with_encryption(mut self, encryption: Option<Encryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Copy attachments (relative to `root`) into an `assets/` directory next to
+    /// markdown and org files written with an output path, and link them from the entries.
+    pub fn with_attachments(mut self, root: &Path) -> Self {
+        self.attachments_root = Some(root.to_path_buf());
+        self
+    }
+
+    /// Append a provenance footer (version, date, entry count, filters) to text exports.
+    pub fn with_footer(mut self, enabled: bool) -> Self {
+        self.with_footer = enabled;
+        self
     }
 
     /// Convert a UTC timestamp to the configured (or system local) timezone.
@@ -45,133 +88,258 @@ impl<'a> Exporter<'a> {
         output_path: Option<String>,
         filters: Option<ExportFilters>,
     ) -> Result<()> {
-        let entries = self.get_entries_for_export(filters)?;
+        self.export_document(Format::Json, output_path, filters)
+    }
 
-        let export_data = ExportData {
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            exported_at: chrono::Utc::now(),
-            entries,
-        };
+    pub fn export_to_markdown(
+        &self,
+        output_path: Option<String>,
+        filters: Option<ExportFilters>,
+    ) -> Result<()> {
+        self.export_document(Format::Markdown, output_path, filters)
+    }
 
-        let json_content = serde_json::to_string_pretty(&export_data)
-            .context("Failed to serialize entries to JSON")?;
+    pub fn export_to_org(
+        &self,
+        output_path: Option<String>,
+        filters: Option<ExportFilters>,
+    ) -> Result<()> {
+        self.export_document(Format::Org, output_path, filters)
+    }
 
-        self.write_output(
-            output_path,
-            json_content,
-            "Failed to write JSON file".to_string(),
-        )
+    pub fn export_to_text(
+        &self,
+        output_path: Option<String>,
+        filters: Option<ExportFilters>,
+    ) -> Result<()> {
+        self.export_document(Format::Text, output_path, filters)
     }
 
-    pub fn export_to_markdown(
+    pub fn export_to_jrnl(
         &self,
         output_path: Option<String>,
         filters: Option<ExportFilters>,
     ) -> Result<()> {
-        let entries = self.get_entries_for_export(filters)?;
-        let grouped_entries = self.group_entries_by_date(&entries);
-
-        let mut md_content = String::new();
-        for (date, entries) in grouped_entries {
-            let formatted_date = date.format("%A, %d %B %Y").to_string();
-            md_content.push_str(&format!("## {}\n\n", formatted_date));
-
-            for entry in &entries {
-                let date_str = self.to_local(&entry.timestamp).format("%H:%M").to_string();
-                if let Some(title) = &entry.title {
-                    md_content.push_str(&format!("### {} - {}\n\n", date_str, title));
-                } else {
-                    md_content.push_str(&format!("### {}\n\n", date_str));
-                }
-                md_content.push_str(&format!("{}\n\n", entry.content));
+        self.export_document(Format::Jrnl, output_path, filters)
+    }
+
+    /// Stream the entries matching `filters` into a single document written to
+    /// `output_path`, or to stdout
+    fn export_document(
+        &self,
+        format: Format,
+        output_path: Option<String>,
+        filters: Option<ExportFilters>,
+    ) -> Result<()> {
+        let footer = self.footer_text(&filters);
+        match output_path {
+            Some(path) => {
+                let mut writer = self.file_writer(format, &path)?;
+                self.for_each_entry_for_export(filters, |entry| writer.write_entry(&entry))?;
+                writer.finish(footer.as_deref())?.close()?;
+            }
+            None => {
+                let mut writer = DocumentWriter::new(self, format, std::io::stdout().lock(), None)?;
+                self.for_each_entry_for_export(filters, |entry| writer.write_entry(&entry))?;
+                writeln!(writer.finish(footer.as_deref())?)?;
             }
         }
+        Ok(())
+    }
 
-        self.write_output(
-            output_path,
-            md_content,
-            "Failed to write Markdown file".to_string(),
-        )
+    /// Writer of a document to the file at `path`, creating its directory if needed.
+    /// Attachments are copied into the assets directory next to it, unless encrypting.
+    fn file_writer(&self, format: Format, path: &str) -> Result<DocumentWriter<'_, Output>> {
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        fs::create_dir_all(dir).context("Failed to create output directory")?;
+        let output = Output::create(Path::new(path), self.encryption.as_ref())
+            .context(format.write_error())?;
+        let assets_dir = self.encryption.is_none().then(|| dir.join(ASSETS_DIR));
+        DocumentWriter::new(self, format, output, assets_dir)
     }
 
-    pub fn export_to_org(
+    /// Export entries into several files of at most `split_size` entries each.
+    ///
+    /// Files are named after `output_path` with a numeric suffix
+    /// (e.g. `entries.md` becomes `entries-001.md`, `entries-002.md`, ...).
+    /// Returns the paths of the written files.
+    pub fn export_split(
         &self,
-        output_path: Option<String>,
+        format: &str,
+        output_path: &str,
         filters: Option<ExportFilters>,
-    ) -> Result<()> {
-        let entries = self.get_entries_for_export(filters)?;
-        let grouped_entries = self.group_entries_by_date(&entries);
-
-        let mut org_content = String::new();
-        for (date, entries) in grouped_entries {
-            let created_date = entries
-                .first()
-                .map(|e| self.to_local(&e.timestamp).format("%Y%m%d").to_string())
-                .unwrap_or_default();
-            let formatted_date = date.format("%A, %d/%m/%Y").to_string();
-            org_content.push_str(&format!("* {}\n", formatted_date));
-            org_content.push_str(&format!(
-                ":PROPERTIES:\n:CREATED:  {}\n:END:\n",
-                created_date
-            ));
-            for entry in entries {
-                let time = self.to_local(&entry.timestamp).format("%H:%M").to_string();
-                if let Some(title) = &entry.title {
-                    org_content.push_str(&format!("** {} {}\n", time, title));
-                } else {
-                    org_content.push_str(&format!("** {} \n", time));
+        split_size: usize,
+    ) -> Result<Vec<String>> {
+        if split_size == 0 {
+            return Err(anyhow::anyhow!("Split size must be greater than zero"));
+        }
+
+        let format = multi_file_format(format)?;
+        let footer = self.footer_text(&filters);
+
+        let mut written = Vec::new();
+        let mut current = None;
+        self.for_each_entry_for_export(filters, |entry| {
+            if current.is_none() {
+                let path = numbered_path(output_path, written.len() + 1);
+                current = Some(self.file_writer(format, &path)?);
+                written.push(path);
+            }
+            let writer = current.as_mut().expect("a part is open");
+            writer.write_entry(&entry)?;
+            if writer.count() == split_size
+                && let Some(writer) = current.take()
+            {
+                writer.finish(footer.as_deref())?.close()?;
+            }
+            Ok(())
+        })?;
+        if let Some(writer) = current {
+            writer.finish(footer.as_deref())?.close()?;
+        }
+
+        Ok(written)
+    }
+
+    /// Export each journal into its own file in `output_dir`, named after the journal
+    /// (e.g. `Work.md`, `Personal.md`). Returns the paths of the written files.
+    pub fn export_by_journal(
+        &self,
+        format: &str,
+        output_dir: &str,
+        filters: Option<ExportFilters>,
+    ) -> Result<Vec<String>> {
+        let format = multi_file_format(format)?;
+        let footer = self.footer_text(&filters);
+
+        // One open file per journal, so entries are still streamed
+        let mut writers = std::collections::BTreeMap::new();
+        self.for_each_entry_for_export(filters, |entry| {
+            if !writers.contains_key(&entry.journal) {
+                let path = Path::new(output_dir)
+                    .join(format!(
+                        "{}.{}",
+                        journal_file_stem(&entry.journal),
+                        format.extension()
+                    ))
+                    .to_string_lossy()
+                    .to_string();
+                let writer = self.file_writer(format, &path)?;
+                writers.insert(entry.journal.clone(), (path, writer));
+            }
+            if let Some((_, writer)) = writers.get_mut(&entry.journal) {
+                writer.write_entry(&entry)?;
+            }
+            Ok(())
+        })?;
+
+        let mut written = Vec::new();
+        for (_, (path, writer)) in writers {
+            writer.finish(footer.as_deref())?.close()?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    /// Copy the attachments of `entries`, relative to `root`, into `assets_dir`
+    fn copy_assets_into(&self, root: &Path, entries: &[Entry], assets_dir: &Path) -> Result<()> {
+        for entry in entries {
+            for path in entry.attachment_paths() {
+                let source = root.join(path);
+                if !source.is_file() {
+                    eprintln!(
+                        "Warning: attachment {} of entry {} not found, not copied",
+                        path, entry.id
+                    );
+                    continue;
                 }
-                org_content.push_str(&convert_markdown_to_org(&entry.content, 1));
+                fs::create_dir_all(assets_dir).context("Failed to create assets directory")?;
+                fs::copy(&source, assets_dir.join(asset_name(path)))
+                    .with_context(|| format!("Failed to copy attachment {}", path))?;
             }
         }
 
-        self.write_output(
-            output_path,
-            org_content,
-            "Failed to write Org file".to_string(),
-        )
+        Ok(())
+    }
+
+    /// Build the provenance line of the export footer, if footers are enabled.
+    /// The entry count is added by the format-specific footer functions.
+    fn footer_text(&self, filters: &Option<ExportFilters>) -> Option<String> {
+        if !self.with_footer {
+            return None;
+        }
+
+        let filters = filters
+            .as_ref()
+            .map(ExportFilters::describe)
+            .unwrap_or_else(|| "none".to_string());
+
+        Some(format!(
+            "Generated by captains-log v{} on {}, {{count}} entries, filters: {}",
+            env!("CARGO_PKG_VERSION"),
+            self.to_local(&Utc::now()).format("%Y-%m-%d %H:%M"),
+            filters
+        ))
     }
 
     /// Get entries for export, applying filters if provided
     fn get_entries_for_export(&self, filters: Option<ExportFilters>) -> Result<Vec<Entry>> {
-        if let Some(filters) = filters {
-            // Parse date filters using .map().transpose() pattern
-            let date = filters
-                .date
-                .as_deref()
-                .map(parse_relative_date)
-                .transpose()
-                .map_err(|e| anyhow::anyhow!("Invalid date filter: {}", e))?;
-            let since = filters
-                .since
-                .as_deref()
-                .map(parse_relative_date)
-                .transpose()
-                .map_err(|e| anyhow::anyhow!("Invalid since filter: {}", e))?;
-            let until = filters
-                .until
-                .as_deref()
-                .map(parse_relative_date)
-                .transpose()
-                .map_err(|e| anyhow::anyhow!("Invalid until filter: {}", e))?;
-
-            self.journal.list_entries_filtered_with_order(
-                date.as_ref(),
-                since.as_ref(),
-                until.as_ref(),
-                filters.journal.as_deref(),
-                "timestamp",
-                "ASC",
-            )
-        } else {
-            self.journal.list_entries_with_order("timestamp", "ASC")
-        }
+        let mut entries = Vec::new();
+        self.for_each_entry_for_export(filters, |entry| {
+            entries.push(entry);
+            Ok(())
+        })?;
+
+        Ok(entries)
+    }
+
+    /// Stream entries for export (oldest first) to `f`, applying filters if provided
+    fn for_each_entry_for_export<F>(&self, filters: Option<ExportFilters>, f: F) -> Result<()>
+    where
+        F: FnMut(Entry) -> Result<()>,
+    {
+        let filters = filters.unwrap_or_default();
+
+        // Parse date filters using .map().transpose() pattern
+        let date = filters
+            .date
+            .as_deref()
+            .map(parse_relative_date)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid date filter: {}", e))?;
+        let since = filters
+            .since
+            .as_deref()
+            .map(parse_relative_date)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid since filter: {}", e))?;
+        let until = filters
+            .until
+            .as_deref()
+            .map(parse_relative_date)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid until filter: {}", e))?;
+
+        let entry_filter = EntryFilter {
+            date,
+            since,
+            until,
+            journal: filters.journal.as_deref(),
+            status: (!filters.include_drafts).then_some(STATUS_FINAL),
+            search: filters.search.as_deref(),
+            tag: filters.tag.as_deref(),
+            ..Default::default()
+        };
+
+        self.journal
+            .for_each_entry(&entry_filter, "timestamp", "ASC", f)
     }
 
     /// Group entries by date using NaiveDate for proper chronological ordering
     fn group_entries_by_date<'b>(
         &self,
-        entries: &'b [Entry],
+        entries: impl IntoIterator<Item = &'b Entry>,
     ) -> std::collections::BTreeMap<NaiveDate, Vec<&'b Entry>> {
         use std::collections::BTreeMap;
 
@@ -182,33 +350,151 @@ impl<'a> Exporter<'a> {
         }
         grouped_entries
     }
-
-    /// Write output to file or stdout
-    fn write_output(
-        &self,
-        output_path: Option<String>,
-        content: String,
-        error_msg: String,
-    ) -> Result<()> {
-        if let Some(path) = output_path {
-            // Create directory if it doesn't exist
-            if let Some(parent) = Path::new(&path).parent() {
-                fs::create_dir_all(parent).context("Failed to create output directory")?;
-            }
-            fs::write(&path, content).context(error_msg)?;
-        } else {
-            println!("{}", content);
-        }
-        Ok(())
-    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ExportFilters {
     pub date: Option<String>,
     pub since: Option<String>,
     pub until: Option<String>,
     pub journal: Option<String>,
+    pub search: Option<String>,
+    pub tag: Option<String>,
+    /// Drafts are left out of exports unless this is set
+    pub include_drafts: bool,
+}
+
+impl ExportFilters {
+    /// Human readable summary of the active filters (e.g. `journal=Work, since=2025-09-01`)
+    fn describe(&self) -> String {
+        let mut filters: Vec<String> = [
+            ("date", &self.date),
+            ("since", &self.since),
+            ("until", &self.until),
+            ("journal", &self.journal),
+            ("search", &self.search),
+            ("tag", &self.tag),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}={}", name, v)))
+        .collect();
+        if self.include_drafts {
+            filters.push("drafts included".to_string());
+        }
+
+        if filters.is_empty() {
+            "none".to_string()
+        } else {
+            filters.join(", ")
+        }
+    }
+}
+
+fn markdown_footer(footer: &str, count: usize) -> String {
+    format!(
+        "---\n\n*{}*\n",
+        footer.replace("{count}", &count.to_string())
+    )
+}
+
+fn org_footer(footer: &str, count: usize) -> String {
+    format!("-----\n{}\n", footer.replace("{count}", &count.to_string()))
+}
+
+fn text_footer(footer: &str, count: usize) -> String {
+    format!("--\n{}\n", footer.replace("{count}", &count.to_string()))
+}
+
+/// File name of an attachment once copied into the assets directory
+fn asset_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Markdown links to the copied attachments of `entry`: images inline, audio as a link.
+/// `root` is the relative path from the markdown file to the directory holding `assets/`.
+fn markdown_asset_links(entry: &Entry, root: &str) -> String {
+    let mut links = String::new();
+    for path in &entry.image_paths {
+        let name = asset_name(path);
+        links.push_str(&format!("![{}]({}{}/{})\n\n", name, root, ASSETS_DIR, name));
+    }
+    if let Some(path) = &entry.audio_path {
+        links.push_str(&format!(
+            "[Audio recording]({}{}/{})\n\n",
+            root,
+            ASSETS_DIR,
+            asset_name(path)
+        ));
+    }
+    links
+}
+
+/// Org links to the copied attachments of `entry`, in the same order as for markdown
+fn org_asset_links(entry: &Entry) -> String {
+    let mut links = String::new();
+    for path in &entry.image_paths {
+        links.push_str(&format!("[[file:{}/{}]]\n", ASSETS_DIR, asset_name(path)));
+    }
+    if let Some(path) = &entry.audio_path {
+        links.push_str(&format!(
+            "[[file:{}/{}][Audio recording]]\n",
+            ASSETS_DIR,
+            asset_name(path)
+        ));
+    }
+    links
+}
+
+/// Lowercase words of `text` joined by dashes, for file names (e.g. `Away Team` becomes `away-team`)
+fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The format named `format` if it can be written as several files, an error otherwise
+fn multi_file_format(format: &str) -> Result<Format> {
+    Format::parse(format).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unsupported export format '{}'. Currently supported formats: json, markdown, org, txt, jrnl",
+            format
+        )
+    })
+}
+
+/// File name (without extension) for a journal's file in a `--split-by journal` export;
+/// characters that aren't allowed in file names are replaced with `_`
+fn journal_file_stem(journal: &str) -> String {
+    journal
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Insert a zero-padded part number before the extension of `path`
+/// (e.g. `out/entries.md` with 2 becomes `out/entries-002.md`).
+fn numbered_path(path: &str, part: usize) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}-{:03}.{}", stem, part, ext.to_string_lossy()),
+        None => format!("{}-{:03}", stem, part),
+    };
+    path.with_file_name(file_name).to_string_lossy().to_string()
 }
 
 /// Convert a markdown string to an org-mode formatted string.
@@ -324,10 +610,148 @@ fn convert_markdown_to_org(markdown: &str, base_level: u32) -> String {
     result
 }
 
+/// Strip the markdown syntax from a string, keeping its text, list bullets and paragraphs.
+/// Link targets follow their text in parentheses.
+fn convert_markdown_to_text(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    let parser = pulldown_cmark::Parser::new_ext(markdown, options);
+    let mut result = String::new();
+
+    let mut list_depth: usize = 0;
+    let mut link_target: Option<String> = None;
+
+    for event in parser {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::List(_) => {
+                    // A nested list starts below its parent item's text
+                    if list_depth > 0 && !result.ends_with('\n') {
+                        result.push('\n');
+                    }
+                    list_depth += 1;
+                }
+                Tag::Item => {
+                    let indent = "  ".repeat(list_depth.saturating_sub(1));
+                    result.push_str(&format!("{}- ", indent));
+                }
+                Tag::Link { dest_url, .. } => {
+                    link_target = Some(dest_url.to_string());
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                // Paragraphs inside list items stay on the item's line
+                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock if list_depth == 0 => {
+                    result.push_str("\n\n");
+                }
+                TagEnd::List(_) => {
+                    list_depth = list_depth.saturating_sub(1);
+                    if list_depth == 0 {
+                        result.push('\n');
+                    }
+                }
+                TagEnd::Item if !result.ends_with('\n') => {
+                    result.push('\n');
+                }
+                TagEnd::Link => {
+                    if let Some(target) = link_target.take() {
+                        result.push_str(&format!(" ({})", target));
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(text) | Event::Code(text) => {
+                result.push_str(&text);
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                result.push('\n');
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_export_filters_describe() {
+        let filters = ExportFilters {
+            journal: Some("Work".to_string()),
+            since: Some("2025-09-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filters.describe(), "since=2025-09-01, journal=Work");
+        assert_eq!(ExportFilters::default().describe(), "none");
+
+        let filters = ExportFilters {
+            search: Some("apollo".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filters.describe(), "search=apollo");
+    }
+
+    #[test]
+    fn test_markdown_footer() {
+        let footer = markdown_footer("Generated by captains-log, {count} entries", 3);
+        assert_eq!(footer, "---\n\n*Generated by captains-log, 3 entries*\n");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Away Team / Logs"), "away-team-logs");
+        assert_eq!(slugify("!!"), "");
+    }
+
+    #[test]
+    fn test_journal_file_stem() {
+        assert_eq!(journal_file_stem("Work"), "Work");
+        assert_eq!(journal_file_stem("Away Team/Logs"), "Away Team_Logs");
+    }
+
+    #[test]
+    fn test_numbered_path() {
+        assert_eq!(numbered_path("out/entries.md", 2), "out/entries-002.md");
+        assert_eq!(numbered_path("entries", 12), "entries-012");
+    }
+
+    #[test]
+    fn test_asset_links() {
+        let journal = Journal::new(crate::database::Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Away mission", None).unwrap();
+        journal
+            .add_entry_images(
+                id,
+                &["attachments/20250101_120000_abcdef_bridge.png".to_string()],
+            )
+            .unwrap();
+        journal.set_entry_audio(id, "audio/log.wav").unwrap();
+        let entry = journal.get_entry(id).unwrap().unwrap();
+
+        assert_eq!(
+            markdown_asset_links(&entry, ""),
+            "![20250101_120000_abcdef_bridge.png](assets/20250101_120000_abcdef_bridge.png)\n\n\
+             [Audio recording](assets/log.wav)\n\n"
+        );
+        assert_eq!(
+            org_asset_links(&entry),
+            "[[file:assets/20250101_120000_abcdef_bridge.png]]\n\
+             [[file:assets/log.wav][Audio recording]]\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_markdown_to_text() {
+        let md = "# Log\n\n**Warp** core is `stable`, see [report](https://example.com).\n\n- One\n- Two\n";
+        assert_eq!(
+            convert_markdown_to_text(md),
+            "Log\n\nWarp core is stable, see report (https://example.com).\n\n- One\n- Two\n\n"
+        );
+    }
+
     #[test]
     fn test_convert_markdown_to_org() {
         let md = "**Bold**";
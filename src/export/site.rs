@@ -0,0 +1,314 @@
+//! Static site export: a browsable, read-only archive of plain HTML files.
+//!
+//! ```text
+//! index.html            months and journals
+//! tags.html             every tag with its entries
+//! entries/<id>.html     one page per entry, linked to the previous and next one
+//! months/<YYYY-MM>.html entries of a month, grouped by day
+//! journals/<name>.html  entries of a journal
+//! assets/               copied attachments
+//! ```
+use super::{ExportFilters, Exporter};
+use crate::journal::Entry;
+use crate::server::{escape_html, to_html};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const STYLE: &str = "body { max-width: 46rem; margin: 2rem auto; padding: 0 1rem; font-family: sans-serif; line-height: 1.5; color: #222; }
+nav { margin-bottom: 2rem; } nav a { margin-right: 1rem; }
+.meta { color: #777; } .meta a { color: inherit; }
+img { max-width: 100%; }
+ul.entries { list-style: none; padding: 0; }
+";
+
+impl Exporter<'_> {
+    /// Generate a static site in `output_dir`. Returns the number of exported entries.
+    pub fn export_to_site(
+        &self,
+        output_dir: &str,
+        filters: Option<ExportFilters>,
+    ) -> Result<usize> {
+        let dir = Path::new(output_dir);
+        let entries = self.get_entries_for_export(filters)?;
+        let index_path = dir.join("index.html");
+        let with_assets = match &self.attachments_root {
+            Some(root) => {
+                self.copy_assets_into(root, &entries, &dir.join(super::ASSETS_DIR))?;
+                true
+            }
+            None => false,
+        };
+
+        for sub_dir in ["entries", "months", "journals"] {
+            fs::create_dir_all(dir.join(sub_dir))
+                .with_context(|| format!("Failed to create {}", dir.join(sub_dir).display()))?;
+        }
+        write_page(&dir.join("style.css"), STYLE)?;
+
+        let mut months: BTreeMap<String, Vec<&Entry>> = BTreeMap::new();
+        let mut journals: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+        let mut tags: BTreeMap<String, Vec<&Entry>> = BTreeMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            let month = self.to_local(&entry.timestamp).format("%Y-%m").to_string();
+            months.entry(month).or_default().push(entry);
+            journals.entry(&entry.journal).or_default().push(entry);
+
+            let entry_tags = self.journal.get_entry_tags(entry.id)?;
+            for tag in &entry_tags {
+                tags.entry(tag.clone()).or_default().push(entry);
+            }
+
+            let previous = i.checked_sub(1).and_then(|i| entries.get(i));
+            let html = self.render_entry_page(
+                entry,
+                &entry_tags,
+                previous,
+                entries.get(i + 1),
+                with_assets,
+            );
+            write_page(
+                &dir.join("entries").join(format!("{}.html", entry.id)),
+                &html,
+            )?;
+        }
+
+        for (month, month_entries) in &months {
+            let mut body = format!("<h1>{}</h1>\n", month);
+            for (date, day_entries) in self.group_entries_by_date(month_entries.iter().copied()) {
+                body.push_str(&format!("<h2>{}</h2>\n", date.format("%A, %d %B %Y")));
+                body.push_str(&self.entry_list(&day_entries, "../", "%H:%M"));
+            }
+            write_page(
+                &dir.join("months").join(format!("{}.html", month)),
+                &page(month, "../", &body),
+            )?;
+        }
+
+        for (name, journal_entries) in &journals {
+            let body = format!(
+                "<h1>{}</h1>\n{}",
+                escape_html(name),
+                self.entry_list(journal_entries, "../", "%Y-%m-%d %H:%M")
+            );
+            write_page(
+                &dir.join("journals")
+                    .join(format!("{}.html", journal_slug(name))),
+                &page(name, "../", &body),
+            )?;
+        }
+
+        let mut body = "<h1>Tags</h1>\n".to_string();
+        for (tag, tag_entries) in &tags {
+            body.push_str(&format!(
+                "<h2 id=\"{tag}\">#{tag}</h2>\n{}",
+                self.entry_list(tag_entries, "", "%Y-%m-%d %H:%M"),
+                tag = escape_html(tag)
+            ));
+        }
+        write_page(&dir.join("tags.html"), &page("Tags", "", &body))?;
+
+        let mut body = format!(
+            "<h1>Captain's Log</h1>\n<p>{} entries</p>\n<h2>Months</h2>\n<ul>\n",
+            entries.len()
+        );
+        for (month, month_entries) in months.iter().rev() {
+            body.push_str(&format!(
+                "<li><a href=\"months/{month}.html\">{month}</a> ({})</li>\n",
+                month_entries.len()
+            ));
+        }
+        body.push_str("</ul>\n<h2>Journals</h2>\n<ul>\n");
+        for (name, journal_entries) in &journals {
+            body.push_str(&format!(
+                "<li><a href=\"journals/{}.html\">{}</a> ({})</li>\n",
+                journal_slug(name),
+                escape_html(name),
+                journal_entries.len()
+            ));
+        }
+        body.push_str("</ul>\n");
+        write_page(&index_path, &page("Captain's Log", "", &body))?;
+
+        Ok(entries.len())
+    }
+
+    fn render_entry_page(
+        &self,
+        entry: &Entry,
+        tags: &[String],
+        previous: Option<&Entry>,
+        next: Option<&Entry>,
+        with_assets: bool,
+    ) -> String {
+        let local = self.to_local(&entry.timestamp);
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&entry_label(entry)));
+
+        let tag_links: Vec<String> = tags
+            .iter()
+            .map(|tag| {
+                format!(
+                    "<a href=\"../tags.html#{tag}\">#{tag}</a>",
+                    tag = escape_html(tag)
+                )
+            })
+            .collect();
+        body.push_str(&format!(
+            "<p class=\"meta\"><a href=\"../months/{}.html\">{}</a> · <a href=\"../journals/{}.html\">{}</a>{}</p>\n",
+            local.format("%Y-%m"),
+            local.format("%A, %d %B %Y %H:%M"),
+            journal_slug(&entry.journal),
+            escape_html(&entry.journal),
+            if tag_links.is_empty() {
+                String::new()
+            } else {
+                format!(" · {}", tag_links.join(" "))
+            }
+        ));
+        body.push_str(&to_html(&entry.content));
+
+        if with_assets {
+            for path in &entry.image_paths {
+                body.push_str(&format!(
+                    "<p><img src=\"../assets/{}\" alt=\"\"></p>\n",
+                    escape_html(&super::asset_name(path))
+                ));
+            }
+            if let Some(path) = &entry.audio_path {
+                body.push_str(&format!(
+                    "<p><audio controls src=\"../assets/{}\"></audio></p>\n",
+                    escape_html(&super::asset_name(path))
+                ));
+            }
+        }
+
+        body.push_str("<p class=\"meta\">");
+        if let Some(previous) = previous {
+            body.push_str(&format!(
+                "<a href=\"{}.html\">← {}</a> ",
+                previous.id,
+                escape_html(&entry_label(previous))
+            ));
+        }
+        if let Some(next) = next {
+            body.push_str(&format!(
+                "<a href=\"{}.html\">{} →</a>",
+                next.id,
+                escape_html(&entry_label(next))
+            ));
+        }
+        body.push_str("</p>\n");
+
+        page(&entry_label(entry), "../", &body)
+    }
+
+    /// `<ul>` of links to entry pages, for a page whose relative path to the site root is `root`
+    fn entry_list(&self, entries: &[&Entry], root: &str, time_format: &str) -> String {
+        let mut html = "<ul class=\"entries\">\n".to_string();
+        for entry in entries {
+            html.push_str(&format!(
+                "<li><span class=\"meta\">{}</span> <a href=\"{}entries/{}.html\">{}</a></li>\n",
+                self.to_local(&entry.timestamp).format(time_format),
+                root,
+                entry.id,
+                escape_html(&entry_label(entry))
+            ));
+        }
+        html.push_str("</ul>\n");
+        html
+    }
+}
+
+/// Title of an entry, or the start of its first line for untitled ones
+fn entry_label(entry: &Entry) -> String {
+    match &entry.title {
+        Some(title) => title.clone(),
+        None => {
+            let first_line = entry.content.lines().next().unwrap_or("").trim();
+            if first_line.chars().count() > 60 {
+                format!("{}…", first_line.chars().take(60).collect::<String>())
+            } else if first_line.is_empty() {
+                format!("Entry {}", entry.id)
+            } else {
+                first_line.to_string()
+            }
+        }
+    }
+}
+
+/// File name for a journal page: lowercase letters and digits, dashes in between
+fn journal_slug(name: &str) -> String {
+    let slug = super::slugify(name);
+    if slug.is_empty() {
+        "journal".to_string()
+    } else {
+        slug
+    }
+}
+
+/// A complete HTML page; `root` is the relative path back to the site root
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"UTF-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n\
+         <title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n\
+         <nav><a href=\"{root}index.html\">Captain's Log</a><a href=\"{root}tags.html\">Tags</a></nav>\n\
+         {body}</body>\n</html>\n",
+        title = escape_html(title),
+    )
+}
+
+fn write_page(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_journal_slug() {
+        assert_eq!(journal_slug("Work"), "work");
+        assert_eq!(journal_slug("Away Team / Logs"), "away-team-logs");
+        assert_eq!(journal_slug("!!"), "journal");
+    }
+
+    #[test]
+    fn test_export_to_site() {
+        use crate::database::Database;
+        use crate::journal::Journal;
+
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let first = journal
+            .create_entry(
+                Some("Stardate"),
+                "Warp core *stable* #engineering",
+                Some("Ops"),
+            )
+            .unwrap();
+        let second = journal.create_entry(None, "Second log", None).unwrap();
+
+        let dir = TempDir::new("site");
+        let exporter = Exporter::new(&journal, Some("UTC".to_string()));
+        assert_eq!(
+            exporter
+                .export_to_site(dir.to_str().unwrap(), None)
+                .unwrap(),
+            2
+        );
+
+        let page = fs::read_to_string(dir.join(format!("entries/{}.html", first))).unwrap();
+        assert!(page.contains("<h1>Stardate</h1>"));
+        assert!(page.contains("Warp core <em>stable</em>"));
+        assert!(page.contains("../tags.html#engineering"));
+        assert!(page.contains(&format!("<a href=\"{}.html\">Second log →</a>", second)));
+        assert!(dir.join("journals/ops.html").exists());
+        assert!(
+            fs::read_to_string(dir.join("tags.html"))
+                .unwrap()
+                .contains("#engineering")
+        );
+    }
+}
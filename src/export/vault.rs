@@ -0,0 +1,118 @@
+//! Vault export: one markdown file per entry, laid out as
+//! `YYYY/MM/YYYY-MM-DD-HHMM-title.md` with the same YAML frontmatter as `cl edit`,
+//! for Obsidian, Logseq and similar tools. `cl import --format markdown --recursive`
+//! reads such a directory back.
+use super::{ASSETS_DIR, ExportFilters, Exporter, markdown_asset_links, slugify};
+use crate::cli::frontmatter::format_entry_with_frontmatter;
+use crate::journal::Entry;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+impl Exporter<'_> {
+    /// Write one file per entry under `output_dir`. Returns the number of files written.
+    pub fn export_to_vault(
+        &self,
+        output_dir: &str,
+        filters: Option<ExportFilters>,
+    ) -> Result<usize> {
+        let dir = Path::new(output_dir);
+        let assets_dir = dir.join(ASSETS_DIR);
+
+        let mut written = HashSet::new();
+        self.for_each_entry_for_export(filters, |entry| {
+            let path = self.unique_entry_path(dir, &entry, &written);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+
+            let mut body = match &entry.title {
+                Some(title) => format!("# {}\n\n{}\n", title, entry.content),
+                None => format!("{}\n", entry.content),
+            };
+            if let Some(root) = &self.attachments_root
+                && !entry.attachment_paths().is_empty()
+            {
+                self.copy_assets_into(root, std::slice::from_ref(&entry), &assets_dir)?;
+                // Entry files are two directories (year and month) below the vault root
+                body.push('\n');
+                body.push_str(&markdown_asset_links(&entry, "../../"));
+            }
+            let tags = self.journal.get_entry_tags(entry.id)?;
+            let content =
+                format_entry_with_frontmatter(&entry.journal, entry.timestamp, &tags, &body)?;
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+
+            written.insert(path);
+            Ok(())
+        })?;
+
+        Ok(written.len())
+    }
+
+    /// `YYYY/MM/YYYY-MM-DD-HHMM-title.md` under `dir`, with the entry ID added when
+    /// another entry of the same minute and title was already written
+    fn unique_entry_path(&self, dir: &Path, entry: &Entry, written: &HashSet<PathBuf>) -> PathBuf {
+        let local = self.to_local(&entry.timestamp);
+        let mut name = local.format("%Y-%m-%d-%H%M").to_string();
+        let slug = entry.title.as_deref().map(slugify).unwrap_or_default();
+        if !slug.is_empty() {
+            name.push('-');
+            name.push_str(&slug);
+        }
+
+        let month_dir = dir
+            .join(local.format("%Y").to_string())
+            .join(local.format("%m").to_string());
+        let path = month_dir.join(format!("{}.md", name));
+        if written.contains(&path) {
+            month_dir.join(format!("{}-{}.md", name, entry.id))
+        } else {
+            path
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::frontmatter::parse_frontmatter;
+    use crate::database::Database;
+    use crate::journal::Journal;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_export_to_vault() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal
+            .create_entry(Some("Away Team"), "Beamed down #risa", Some("Ops"))
+            .unwrap();
+        journal
+            .create_entry(Some("Away Team"), "Beamed up", Some("Ops"))
+            .unwrap();
+
+        let dir = TempDir::new("vault");
+        let exporter = Exporter::new(&journal, Some("UTC".to_string()));
+        assert_eq!(
+            exporter
+                .export_to_vault(dir.to_str().unwrap(), None)
+                .unwrap(),
+            2
+        );
+
+        let entry = journal.get_entry(id).unwrap().unwrap();
+        let path = dir
+            .join(entry.timestamp.format("%Y/%m").to_string())
+            .join(format!(
+                "{}-away-team.md",
+                entry.timestamp.format("%Y-%m-%d-%H%M")
+            ));
+        let (metadata, body) = parse_frontmatter(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(metadata.journal, "Ops");
+        assert_eq!(metadata.tags, vec!["risa"]);
+        assert!(body.starts_with("# Away Team\n\nBeamed down #risa"));
+    }
+}
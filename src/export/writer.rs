@@ -0,0 +1,300 @@
+//! Incremental writing of single-document exports (JSON, markdown, org, text, jrnl).
+//!
+//! Entries are written one at a time as they are read from the database, so memory
+//! stays flat however many entries are exported. They must come oldest first: date
+//! headings are written whenever the local date changes.
+use super::{
+    ExportData, Exporter, convert_markdown_to_org, convert_markdown_to_text, markdown_asset_links,
+    markdown_footer, org_asset_links, org_footer, text_footer,
+};
+use crate::journal::Entry;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Format {
+    Json,
+    Markdown,
+    Org,
+    Text,
+    Jrnl,
+}
+
+impl Format {
+    pub(super) fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "md" | "markdown" => Some(Format::Markdown),
+            "org" => Some(Format::Org),
+            "txt" | "text" => Some(Format::Text),
+            "jrnl" => Some(Format::Jrnl),
+            _ => None,
+        }
+    }
+
+    /// File extension for files of this format
+    pub(super) fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Markdown => "md",
+            Format::Org => "org",
+            Format::Text | Format::Jrnl => "txt",
+        }
+    }
+
+    pub(super) fn write_error(self) -> &'static str {
+        match self {
+            Format::Json => "Failed to write JSON file",
+            Format::Markdown => "Failed to write Markdown file",
+            Format::Org => "Failed to write Org file",
+            Format::Text => "Failed to write text file",
+            Format::Jrnl => "Failed to write jrnl file",
+        }
+    }
+}
+
+pub(super) struct DocumentWriter<'a, W: Write> {
+    exporter: &'a Exporter<'a>,
+    format: Format,
+    out: W,
+    /// Where attachments are copied to, for the formats that link them
+    assets_dir: Option<PathBuf>,
+    current_date: Option<NaiveDate>,
+    count: usize,
+}
+
+impl<'a, W: Write> DocumentWriter<'a, W> {
+    pub(super) fn new(
+        exporter: &'a Exporter<'a>,
+        format: Format,
+        mut out: W,
+        assets_dir: Option<PathBuf>,
+    ) -> Result<Self> {
+        if format == Format::Json {
+            let header = serde_json::to_string_pretty(&ExportData {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                exported_at: chrono::Utc::now(),
+                entries: Vec::new(),
+            })?;
+            // `entries` is the last field: entries are written between its brackets
+            let header = header
+                .strip_suffix("[]\n}")
+                .context("Unexpected JSON export header")?;
+            out.write_all(header.as_bytes())?;
+            out.write_all(b"[")?;
+        }
+
+        let assets_dir = assets_dir.filter(|_| {
+            exporter.attachments_root.is_some() && matches!(format, Format::Markdown | Format::Org)
+        });
+        Ok(Self {
+            exporter,
+            format,
+            out,
+            assets_dir,
+            current_date: None,
+            count: 0,
+        })
+    }
+
+    /// Number of entries written so far
+    pub(super) fn count(&self) -> usize {
+        self.count
+    }
+
+    pub(super) fn write_entry(&mut self, entry: &Entry) -> Result<()> {
+        let local = self.exporter.to_local(&entry.timestamp);
+        let date = local.date_naive();
+        let new_date = self.current_date != Some(date);
+        self.current_date = Some(date);
+
+        if let (Some(root), Some(assets_dir)) = (&self.exporter.attachments_root, &self.assets_dir)
+        {
+            self.exporter
+                .copy_assets_into(root, std::slice::from_ref(entry), assets_dir)?;
+        }
+        let with_assets = self.assets_dir.is_some();
+        let time = local.format("%H:%M");
+
+        let text = match self.format {
+            Format::Json => {
+                let json = serde_json::to_string_pretty(entry)
+                    .context("Failed to serialize entries to JSON")?;
+                format!(
+                    "{}\n    {}",
+                    if self.count > 0 { "," } else { "" },
+                    json.replace('\n', "\n    ")
+                )
+            }
+            Format::Markdown => {
+                let mut text = String::new();
+                if new_date {
+                    text.push_str(&format!("## {}\n\n", date.format("%A, %d %B %Y")));
+                }
+                match &entry.title {
+                    Some(title) => text.push_str(&format!("### {} - {}\n\n", time, title)),
+                    None => text.push_str(&format!("### {}\n\n", time)),
+                }
+                text.push_str(&format!("{}\n\n", entry.content));
+                if with_assets {
+                    text.push_str(&markdown_asset_links(entry, ""));
+                }
+                text
+            }
+            Format::Org => {
+                let mut text = String::new();
+                if new_date {
+                    text.push_str(&format!(
+                        "* {}\n:PROPERTIES:\n:CREATED:  {}\n:END:\n",
+                        date.format("%A, %d/%m/%Y"),
+                        date.format("%Y%m%d")
+                    ));
+                }
+                match &entry.title {
+                    Some(title) => text.push_str(&format!("** {} {}\n", time, title)),
+                    None => text.push_str(&format!("** {} \n", time)),
+                }
+                text.push_str(&convert_markdown_to_org(&entry.content, 1));
+                if with_assets {
+                    text.push_str(&org_asset_links(entry));
+                }
+                text
+            }
+            Format::Text => {
+                let mut text = String::new();
+                if new_date {
+                    let formatted_date = date.format("%A, %d %B %Y").to_string();
+                    text.push_str(&format!(
+                        "{}\n{}\n\n",
+                        formatted_date,
+                        "=".repeat(formatted_date.chars().count())
+                    ));
+                }
+                match &entry.title {
+                    Some(title) => text.push_str(&format!("{}  {}\n\n", time, title)),
+                    None => text.push_str(&format!("{}\n\n", time)),
+                }
+                text.push_str(convert_markdown_to_text(&entry.content).trim_end());
+                text.push_str("\n\n");
+                text
+            }
+            // jrnl takes the first line as the title, so untitled entries start with it
+            Format::Jrnl => {
+                let content = entry.content.trim();
+                let (title, body) = match &entry.title {
+                    Some(title) => (title.as_str(), content),
+                    None => content.split_once('\n').unwrap_or((content, "")),
+                };
+                let mut text = format!("[{}] {}\n", local.format("%Y-%m-%d %H:%M"), title.trim());
+                let body = body.trim();
+                if !body.is_empty() {
+                    text.push_str(body);
+                    text.push('\n');
+                }
+                text.push('\n');
+                text
+            }
+        };
+
+        self.out.write_all(text.as_bytes())?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Close the document, adding the footer (with the entry count) to the formats that have one
+    pub(super) fn finish(mut self, footer: Option<&str>) -> Result<W> {
+        let end = match (self.format, footer) {
+            (Format::Json, _) if self.count == 0 => "]\n}".to_string(),
+            (Format::Json, _) => "\n  ]\n}".to_string(),
+            (Format::Markdown, Some(footer)) => markdown_footer(footer, self.count),
+            (Format::Org, Some(footer)) => org_footer(footer, self.count),
+            (Format::Text, Some(footer)) => text_footer(footer, self.count),
+            _ => String::new(),
+        };
+        self.out.write_all(end.as_bytes())?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::journal::Journal;
+
+    fn journal() -> Journal {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal
+            .create_entry(
+                Some("Stardate 47634.4"),
+                "Warp core stable.\nAll hands.",
+                None,
+            )
+            .unwrap();
+        journal
+            .create_entry(None, "Untitled log\nWith a body", None)
+            .unwrap();
+        journal.create_entry(None, "One line", None).unwrap();
+        journal
+    }
+
+    fn render(exporter: &Exporter, format: Format) -> String {
+        let mut writer = DocumentWriter::new(exporter, format, Vec::new(), None).unwrap();
+        exporter
+            .for_each_entry_for_export(None, |entry| writer.write_entry(&entry))
+            .unwrap();
+        String::from_utf8(writer.finish(Some("{count} entries")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_jrnl_document() {
+        let journal = journal();
+        let exporter = Exporter::new(&journal, Some("UTC".to_string()));
+        let entries = exporter.get_entries_for_export(None).unwrap();
+        let date = entries[0].timestamp.format("%Y-%m-%d %H:%M").to_string();
+
+        let jrnl = render(&exporter, Format::Jrnl);
+        let expected_start = format!(
+            "[{}] Stardate 47634.4\nWarp core stable.\nAll hands.\n\n[",
+            date
+        );
+        assert!(jrnl.starts_with(&expected_start));
+        assert!(jrnl.contains("] Untitled log\nWith a body\n\n["));
+        assert!(jrnl.ends_with("] One line\n\n"));
+    }
+
+    #[test]
+    fn test_json_document_parses() {
+        let journal = journal();
+        let exporter = Exporter::new(&journal, Some("UTC".to_string()));
+
+        let data: ExportData = serde_json::from_str(&render(&exporter, Format::Json)).unwrap();
+        assert_eq!(data.entries.len(), 3);
+        assert_eq!(data.entries[0].title.as_deref(), Some("Stardate 47634.4"));
+
+        let empty = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let exporter = Exporter::new(&empty, None);
+        let data: ExportData = serde_json::from_str(&render(&exporter, Format::Json)).unwrap();
+        assert!(data.entries.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_document_groups_by_date() {
+        let journal = journal();
+        let exporter = Exporter::new(&journal, Some("UTC".to_string()));
+
+        let markdown = render(&exporter, Format::Markdown);
+        assert_eq!(
+            markdown
+                .lines()
+                .filter(|line| line.starts_with("## "))
+                .count(),
+            1
+        );
+        assert!(markdown.contains(" - Stardate 47634.4\n\nWarp core stable."));
+        assert!(markdown.ends_with("---\n\n*3 entries*\n"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Import format detection, for `cl import` without `--format`: the file (or the files of
+//! a directory) is sniffed for the structure of each supported format.
+use super::{collect_markdown_files, org_journal_files};
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The `--format` value to import `path` with.
+///
+/// Fails when nothing matches, or when several formats do (e.g. a directory with both
+/// markdown and org-journal files), asking for `--format`.
+pub fn detect_format(path: &Path) -> Result<&'static str> {
+    let candidates = if path.is_dir() {
+        detect_directory(path)?
+    } else {
+        detect_file(path)?
+    };
+
+    match candidates.as_slice() {
+        [format] => Ok(*format),
+        [] => Err(anyhow!(
+            "Could not detect the format of {}; pass --format (org, dayone, diaro, enex, jrnl, journey, json, markdown, text)",
+            path.display()
+        )),
+        _ => Err(anyhow!(
+            "{} looks like more than one format ({}); pass --format to choose",
+            path.display(),
+            candidates.join(", ")
+        )),
+    }
+}
+
+fn detect_directory(dir: &Path) -> Result<Vec<&'static str>> {
+    let mut candidates = Vec::new();
+    if !org_journal_files(dir)?.is_empty() {
+        candidates.push("org");
+    }
+
+    let mut markdown_files = Vec::new();
+    collect_markdown_files(dir, false, &mut markdown_files)?;
+    if !markdown_files.is_empty() {
+        candidates.push("markdown");
+    }
+
+    // Journey exports are directories of one JSON file per entry
+    let mut json_files: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .filter_map(|dir_entry| dir_entry.ok().map(|dir_entry| dir_entry.path()))
+        .filter(|file| {
+            file.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        })
+        .collect();
+    json_files.sort();
+    if let Some(file) = json_files.first()
+        && let Ok(content) = fs::read_to_string(file)
+        && sniff_content(&content) == ["journey"]
+    {
+        candidates.push("journey");
+    }
+
+    Ok(candidates)
+}
+
+fn detect_file(file: &Path) -> Result<Vec<&'static str>> {
+    let extension = file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("zip") => return Ok(vec!["dayone"]),
+        Some("enex") => return Ok(vec!["enex"]),
+        _ => {}
+    }
+
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    Ok(sniff_content(&content))
+}
+
+/// Formats whose structure `content` has
+fn sniff_content(content: &str) -> Vec<&'static str> {
+    let trimmed = content.trim_start();
+
+    if trimmed.starts_with('{') {
+        let Ok(serde_json::Value::Object(object)) = serde_json::from_str(trimmed) else {
+            return Vec::new();
+        };
+        return if object.contains_key("metadata") && object.contains_key("entries") {
+            vec!["dayone"]
+        } else if object.contains_key("exported_at") && object.contains_key("entries") {
+            vec!["json"]
+        } else if object.contains_key("date_journal") {
+            vec!["journey"]
+        } else {
+            Vec::new()
+        };
+    }
+
+    if trimmed.starts_with('<') {
+        return if trimmed.contains("<en-export") {
+            vec!["enex"]
+        } else if trimmed.contains(r#"<table name="diaro_entries">"#) {
+            vec!["diaro"]
+        } else {
+            Vec::new()
+        };
+    }
+
+    static JRNL_RE: OnceLock<Regex> = OnceLock::new();
+    let jrnl_re = JRNL_RE.get_or_init(|| {
+        Regex::new(r"(?m)^\[\d{4}-\d{2}-\d{2} \d{1,2}:\d{2}").expect("valid jrnl line regex")
+    });
+
+    let mut candidates = Vec::new();
+    let has_org_entries = content.lines().any(|line| line.starts_with("** "));
+    if has_org_entries && content.lines().any(|line| line.starts_with("* ")) {
+        candidates.push("org");
+    }
+    if jrnl_re.is_match(content) {
+        candidates.push("jrnl");
+    }
+    if trimmed.starts_with("---\n") || trimmed.starts_with("---\r\n") {
+        candidates.push("markdown");
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_content() {
+        assert_eq!(
+            sniff_content(r#"{"metadata": {"version": "1.0"}, "entries": []}"#),
+            vec!["dayone"]
+        );
+        assert_eq!(
+            sniff_content(
+                r#"{"version": "1.0", "exported_at": "2025-09-07T10:00:00Z", "entries": []}"#
+            ),
+            vec!["json"]
+        );
+        assert_eq!(
+            sniff_content(r#"{"text": "Shore leave", "date_journal": 1757239200000}"#),
+            vec!["journey"]
+        );
+        assert_eq!(
+            sniff_content("<?xml version=\"1.0\"?>\n<en-export><note></note></en-export>"),
+            vec!["enex"]
+        );
+        assert_eq!(
+            sniff_content("* Sunday, 07/09/2025\n** 09:30 Briefing\nAll hands.\n"),
+            vec!["org"]
+        );
+        assert_eq!(
+            sniff_content("[2025-09-07 09:30] Briefing\nAll hands.\n"),
+            vec!["jrnl"]
+        );
+        assert_eq!(
+            sniff_content("---\njournal: Ops\n---\n# Briefing\n"),
+            vec!["markdown"]
+        );
+        assert!(sniff_content("Dear diary,\n").is_empty());
+    }
+
+    #[test]
+    fn test_sniff_content_ambiguous() {
+        let content = "---\njournal: Ops\n---\n[2025-09-07 09:30] Briefing\n";
+        assert_eq!(sniff_content(content), vec!["jrnl", "markdown"]);
+    }
+}
@@ -1,46 +1,352 @@
-use crate::journal::Journal;
+use crate::export::ExportData;
+use crate::journal::{Entry, Journal, extract_tags};
 use anyhow::{Context, Result};
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use chrono_tz::Tz;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+mod detect;
+mod progress;
+mod watch;
+
+pub use detect::detect_format;
+use progress::Progress;
 
 pub struct Importer<'a> {
     journal: &'a Journal,
+    /// Database whose directory receives the attachments of imported entries
+    db_path: Option<PathBuf>,
+    /// Parse and count entries without writing anything
+    dry_run: bool,
+    progress: Progress,
 }
 
 impl<'a> Importer<'a> {
     pub fn new(journal: &'a Journal) -> Self {
-        Self { journal }
+        Self {
+            journal,
+            db_path: None,
+            dry_run: false,
+            progress: Progress::new(false),
+        }
+    }
+
+    /// Show a progress bar on stderr while importing many entries
+    pub fn with_progress(mut self, enabled: bool) -> Self {
+        self.progress = Progress::new(enabled);
+        self
+    }
+
+    /// Only report what would be imported: no entries are created and no files copied.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Copy the attachments of imported entries next to the database at `db_path`.
+    pub fn with_attachments(mut self, db_path: &Path) -> Self {
+        self.db_path = Some(db_path.to_path_buf());
+        self
+    }
+
+    /// Import entries from a Captain's Log JSON export (`cl export --format json`).
+    ///
+    /// Journals, titles, timestamps, drafts and stars are kept; `journal_category`
+    /// overrides the journal of every entry. Attachments are copied when found next to
+    /// the export file at their stored relative path (e.g. `attachments/...`), as in a
+    /// copy of the database directory. Entries already present are skipped, so importing
+    /// an export into the database it came from changes nothing.
+    pub fn import_from_json(
+        &self,
+        file_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+    ) -> Result<ImportStats> {
+        let content =
+            fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+        let data: ExportData =
+            serde_json::from_str(&content).context("Failed to parse Captain's Log JSON export")?;
+        let source_root = Path::new(file_path).parent().unwrap_or(Path::new(""));
+
+        let mut stats = ImportStats::default();
+
+        for entry in self.progress.track(data.entries.into_iter()) {
+            if !date_filter.matches(entry.timestamp.date_naive()) {
+                continue;
+            }
+            stats.total += 1;
+
+            match self.journal.entry_exists(entry.timestamp, &entry.content) {
+                Ok(true) => {
+                    stats.skipped += 1;
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    stats.skipped += 1;
+                    stats.errors.push(format!(
+                        "Failed to check for duplicates of entry at {}: {}",
+                        entry.timestamp, e
+                    ));
+                    continue;
+                }
+            }
+
+            match self.create_entry(
+                entry.title.as_deref(),
+                &entry.content,
+                Some(journal_category.unwrap_or(&entry.journal)),
+                entry.timestamp.naive_utc(),
+                &mut stats,
+            ) {
+                Ok(None) => {}
+                Ok(Some(id)) => {
+                    if let Err(e) = self.restore_entry_details(id, &entry, source_root, &mut stats)
+                    {
+                        stats
+                            .errors
+                            .push(format!("Failed to restore details of entry {}: {}", id, e));
+                    }
+                }
+                Err(e) => {
+                    stats.errors.push(format!(
+                        "Failed to import entry at {}: {}",
+                        entry.timestamp, e
+                    ));
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Status, star and attachments of an entry imported from a JSON export
+    fn restore_entry_details(
+        &self,
+        id: i64,
+        entry: &Entry,
+        source_root: &Path,
+        stats: &mut ImportStats,
+    ) -> Result<()> {
+        if entry.is_draft() {
+            self.journal.set_entry_status(id, &entry.status)?;
+        }
+        if entry.starred {
+            self.journal.set_entry_starred(id, true)?;
+        }
+
+        let Some(root) = self.db_path.as_deref().and_then(Path::parent) else {
+            return Ok(());
+        };
+        let mut copy = |path: &str| -> Result<bool> {
+            if !is_plain_relative(path) {
+                stats
+                    .errors
+                    .push(format!("Ignored attachment with unsafe path {}", path));
+                return Ok(false);
+            }
+            let destination = root.join(path);
+            if destination.exists() {
+                return Ok(true);
+            }
+            let source = source_root.join(path);
+            if !source.is_file() {
+                stats.errors.push(format!(
+                    "Attachment {} of entry {} not found next to the export",
+                    path, id
+                ));
+                return Ok(false);
+            }
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &destination)
+                .context(format!("Failed to copy attachment {}", path))?;
+            Ok(true)
+        };
+
+        if let Some(audio_path) = &entry.audio_path
+            && copy(audio_path)?
+        {
+            self.journal.set_entry_audio(id, audio_path)?;
+        }
+        let mut images = Vec::new();
+        for path in &entry.image_paths {
+            if copy(path)? {
+                images.push(path.clone());
+            }
+        }
+        if !images.is_empty() {
+            self.journal.add_entry_images(id, &images)?;
+        }
+
+        Ok(())
     }
 
-    /// Import entries from an org-journal file
+    /// Import entries from an org-journal file, or from an org-journal directory with one
+    /// file per day, week, month or year.
+    ///
+    /// In a directory, the files are the `.org` files and those named after a date, as
+    /// org-journal's default `%Y%m%d` file format does (weekly, monthly and yearly files
+    /// are named after the first day of their period). The file date applies to entries
+    /// that come before any date heading, as in daily files without one.
     pub fn import_from_org(
         &self,
         file_path: &str,
         journal_category: Option<&str>,
-        filter_date: Option<NaiveDate>,
+        date_filter: DateFilter,
+    ) -> Result<ImportStats> {
+        let path = Path::new(file_path);
+        if !path.is_dir() {
+            let content = fs::read_to_string(file_path)
+                .context(format!("Failed to read file: {}", file_path))?;
+            let entries = parse_org_journal(&content, org_journal_file_date(path), date_filter)?;
+            return Ok(self.import_entries(entries, journal_category));
+        }
+
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for file in org_journal_files(path)? {
+            let parsed = fs::read_to_string(&file)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| {
+                    parse_org_journal(&content, org_journal_file_date(&file), date_filter)
+                });
+            match parsed {
+                Ok(mut file_entries) => entries.append(&mut file_entries),
+                Err(e) => errors.push(format!("Failed to parse {}: {}", file.display(), e)),
+            }
+        }
+
+        let mut stats = self.import_entries(entries, journal_category);
+        stats.errors.append(&mut errors);
+        Ok(stats)
+    }
+
+    /// Import entries from a DayOne export: the export zip, or a JSON file of the unzipped export.
+    ///
+    /// DayOne exports one file per journal (`Travel.json`), so with `journal_from_file`
+    /// the file name becomes the journal of its entries (unless `journal_category` is given).
+    /// Photos are attached from the export's `photos` directory, next to the JSON file, and
+    /// their `dayone-moment://` links in the text point to the stored copies.
+    pub fn import_from_dayone(
+        &self,
+        file_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+        journal_from_file: bool,
+    ) -> Result<ImportStats> {
+        let path = Path::new(file_path);
+        if !path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+        {
+            return self.import_dayone_json(
+                file_path,
+                journal_category,
+                date_filter,
+                journal_from_file,
+            );
+        }
+
+        let dir = extract_zip(path)?;
+        let result = files_with_extension(&dir, "json").and_then(|files| {
+            if files.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "No DayOne journal (JSON file) in {}",
+                    file_path
+                ));
+            }
+            let mut stats = ImportStats::default();
+            for file in files {
+                let file_stats = self.import_dayone_json(
+                    &file.to_string_lossy(),
+                    journal_category,
+                    date_filter,
+                    journal_from_file,
+                )?;
+                stats.merge(file_stats);
+            }
+            Ok(stats)
+        });
+        let _ = fs::remove_dir_all(&dir);
+        result
+    }
+
+    fn import_dayone_json(
+        &self,
+        file_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+        journal_from_file: bool,
     ) -> Result<ImportStats> {
         let content =
             fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
 
-        let entries = parse_org_journal(&content, filter_date)?;
+        let entries = parse_dayone_json(&content, date_filter)?;
+        let journal_name = file_journal_name(Path::new(file_path)).filter(|_| journal_from_file);
+        let journal_category = journal_category.or(journal_name.as_deref());
+        let photo_dir = Path::new(file_path)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("photos");
 
         let mut stats = ImportStats {
             total: entries.len(),
-            imported: 0,
-            skipped: 0,
-            errors: Vec::new(),
+            ..Default::default()
         };
 
-        for entry in entries {
-            match self.journal.create_entry_with_timestamp(
+        for (mut entry, photos) in self.progress.track(entries.into_iter()) {
+            let mut stored = HashMap::new();
+            // A dry run copies nothing
+            for photo in photos.iter().filter(|_| !self.dry_run) {
+                match self.store_image(&photo_dir.join(photo.file_name())) {
+                    Ok(path) => {
+                        stored.insert(photo.identifier.clone(), path);
+                    }
+                    Err(e) => stats.errors.push(format!(
+                        "Photo {} of entry at {}: {}",
+                        photo.identifier, entry.timestamp, e
+                    )),
+                }
+            }
+            entry.content = rewrite_dayone_moments(&entry.content, &stored);
+
+            match self.create_entry(
                 entry.title.as_deref(),
                 &entry.content,
                 journal_category,
                 entry.timestamp,
+                &mut stats,
             ) {
-                Ok(_) => stats.imported += 1,
+                Ok(None) => {}
+                Ok(Some(id)) => {
+                    if entry.starred
+                        && let Err(e) = self.journal.set_entry_starred(id, true)
+                    {
+                        stats
+                            .errors
+                            .push(format!("Failed to star entry {}: {}", id, e));
+                    }
+                    // Attach in the order of the photos array
+                    let images: Vec<String> = photos
+                        .iter()
+                        .filter_map(|photo| stored.get(&photo.identifier).cloned())
+                        .collect();
+                    if !images.is_empty()
+                        && let Err(e) = self.journal.add_entry_images(id, &images)
+                    {
+                        stats
+                            .errors
+                            .push(format!("Failed to attach photos to entry {}: {}", id, e));
+                    }
+                }
                 Err(e) => {
                     stats.errors.push(format!(
                         "Failed to import entry at {}: {}",
@@ -54,33 +360,346 @@ impl<'a> Importer<'a> {
         Ok(stats)
     }
 
-    /// Import entries from a DayOne JSON export file
-    pub fn import_from_dayone(
+    /// Copy an image into the attachments directory next to the database
+    fn store_image(&self, path: &Path) -> Result<String> {
+        let db_path = self
+            .db_path
+            .as_deref()
+            .context("No attachments directory to copy it to")?;
+        crate::attachments::store_attachment(db_path, path)
+    }
+
+    /// Import entries from a jrnl plain-text file (`jrnl --export txt` or the journal file itself)
+    pub fn import_from_jrnl(
+        &self,
+        file_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+    ) -> Result<ImportStats> {
+        let content =
+            fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+
+        let entries = parse_jrnl(&content, date_filter);
+        Ok(self.import_entries(entries, journal_category))
+    }
+
+    /// Import entries from a Journey.cloud export: a directory of per-entry JSON files
+    /// (the unzipped export, photos included) or a single entry file.
+    ///
+    /// Entries already present in the journal are skipped; photos are attached when
+    /// found next to the JSON files.
+    pub fn import_from_journey(
+        &self,
+        path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+    ) -> Result<ImportStats> {
+        let path = Path::new(path);
+        let files = if path.is_dir() {
+            files_with_extension(path, "json")?
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut stats = ImportStats::default();
+
+        for file in self.progress.track(files.into_iter()) {
+            let parsed = fs::read_to_string(&file)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| parse_journey_entry(&content, date_filter));
+            let (entry, photos) = match parsed {
+                Ok(Some(parsed)) => parsed,
+                Ok(None) => continue,
+                Err(e) => {
+                    stats.total += 1;
+                    stats.skipped += 1;
+                    stats
+                        .errors
+                        .push(format!("Failed to parse {}: {}", file.display(), e));
+                    continue;
+                }
+            };
+            stats.total += 1;
+
+            let source_dir = file.parent().unwrap_or(Path::new(""));
+            let photos: Vec<PathBuf> = photos.iter().map(|photo| source_dir.join(photo)).collect();
+            self.import_entry_with_photos(
+                &entry,
+                journal_category,
+                &photos,
+                &file.display().to_string(),
+                &mut stats,
+            );
+        }
+
+        Ok(stats)
+    }
+
+    /// Import entries from a Diaro backup (`DiaroBackup.xml`, from the unzipped backup).
+    ///
+    /// Folders become journals unless `journal_category` is given, and photos are attached
+    /// when found in the backup's `media/photo` directory. Entries already present in the
+    /// journal are skipped.
+    pub fn import_from_diaro(
+        &self,
+        file_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+    ) -> Result<ImportStats> {
+        let content =
+            fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+        let photo_dir = Path::new(file_path)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("media")
+            .join("photo");
+
+        let (entries, mut errors) = parse_diaro_xml(&content, date_filter);
+        let mut stats = ImportStats {
+            total: entries.len() + errors.len(),
+            skipped: errors.len(),
+            ..Default::default()
+        };
+        stats.errors.append(&mut errors);
+
+        for diaro_entry in self.progress.track(entries.into_iter()) {
+            let photos: Vec<PathBuf> = diaro_entry
+                .photos
+                .iter()
+                .map(|photo| photo_dir.join(photo))
+                .collect();
+            self.import_entry_with_photos(
+                &diaro_entry.entry,
+                journal_category.or(diaro_entry.folder.as_deref()),
+                &photos,
+                &format!("entry at {}", diaro_entry.entry.timestamp),
+                &mut stats,
+            );
+        }
+
+        Ok(stats)
+    }
+
+    /// Import a plain-text diary, split into entries at the lines matching `date_pattern`.
+    ///
+    /// The date is read from the `date` named group of the pattern, its first group, or the
+    /// whole match, with `date_format` (a chrono format) or the usual `YYYY-MM-DD [HH:MM]`
+    /// forms. The rest of a header line becomes the entry title and the lines up to the
+    /// next header its content. Entries already present in the journal are skipped.
+    pub fn import_from_text(
         &self,
         file_path: &str,
         journal_category: Option<&str>,
-        filter_date: Option<NaiveDate>,
+        date_filter: DateFilter,
+        date_pattern: &str,
+        date_format: Option<&str>,
     ) -> Result<ImportStats> {
         let content =
             fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+        let pattern = Regex::new(date_pattern).context("Invalid --date-pattern")?;
+
+        let (entries, mut errors) = parse_dated_text(&content, &pattern, date_format, date_filter);
+        let mut stats = ImportStats {
+            total: entries.len(),
+            ..Default::default()
+        };
+        stats.errors.append(&mut errors);
+
+        for entry in self.progress.track(entries.into_iter()) {
+            let source = format!("entry at {}", entry.timestamp);
+            self.import_entry_with_photos(&entry, journal_category, &[], &source, &mut stats);
+        }
+
+        Ok(stats)
+    }
+
+    /// Import notes from an Evernote export: an `.enex` file or a directory of them.
+    ///
+    /// Evernote exports one notebook per file, so with `journal_from_file` each file name
+    /// becomes the journal of its notes (unless `journal_category` is given). Note bodies
+    /// are converted to markdown; attached resources are not imported.
+    pub fn import_from_enex(
+        &self,
+        path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+        journal_from_file: bool,
+    ) -> Result<ImportStats> {
+        let path = Path::new(path);
+        let files = if path.is_dir() {
+            files_with_extension(path, "enex")?
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut stats = ImportStats::default();
+
+        for file in files {
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read file: {}", file.display()))?;
+            let notebook = file_journal_name(&file).filter(|_| journal_from_file);
+            let journal = journal_category.or(notebook.as_deref());
+
+            for note in self
+                .progress
+                .track(xml_elements(&content, "note").into_iter())
+            {
+                let entry = match parse_enex_note(note) {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        stats.total += 1;
+                        stats.skipped += 1;
+                        stats.errors.push(format!(
+                            "Failed to parse a note of {}: {}",
+                            file.display(),
+                            e
+                        ));
+                        continue;
+                    }
+                };
+                if !date_filter.matches(entry.timestamp.date()) {
+                    continue;
+                }
+                stats.total += 1;
+
+                let source = match &entry.title {
+                    Some(title) => format!("note '{}'", title),
+                    None => format!("note at {}", entry.timestamp),
+                };
+                self.import_entry_with_photos(&entry, journal, &[], &source, &mut stats);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Import one entry unless it already exists, attaching the image files `photos`.
+    /// `source` names the entry in error messages.
+    fn import_entry_with_photos(
+        &self,
+        entry: &ParsedEntry,
+        journal_category: Option<&str>,
+        photos: &[PathBuf],
+        source: &str,
+        stats: &mut ImportStats,
+    ) {
+        let timestamp_utc = DateTime::<Utc>::from_naive_utc_and_offset(entry.timestamp, Utc);
+        match self.journal.entry_exists(timestamp_utc, &entry.content) {
+            Ok(true) => {
+                stats.skipped += 1;
+                return;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                stats.skipped += 1;
+                stats.errors.push(format!(
+                    "Failed to check for duplicates of {}: {}",
+                    source, e
+                ));
+                return;
+            }
+        }
+
+        let id = match self.create_entry(
+            entry.title.as_deref(),
+            &entry.content,
+            journal_category,
+            entry.timestamp,
+            stats,
+        ) {
+            Ok(Some(id)) => id,
+            Ok(None) => return,
+            Err(e) => {
+                stats
+                    .errors
+                    .push(format!("Failed to import {}: {}", source, e));
+                stats.skipped += 1;
+                return;
+            }
+        };
+
+        if entry.starred
+            && let Err(e) = self.journal.set_entry_starred(id, true)
+        {
+            stats
+                .errors
+                .push(format!("Failed to star entry {}: {}", id, e));
+        }
+
+        let mut images = Vec::new();
+        for photo in photos {
+            match self.store_image(photo) {
+                Ok(stored) => images.push(stored),
+                Err(e) => {
+                    stats
+                        .errors
+                        .push(format!("Photo {} of entry {}: {}", photo.display(), id, e))
+                }
+            }
+        }
+        if !images.is_empty()
+            && let Err(e) = self.journal.add_entry_images(id, &images)
+        {
+            stats
+                .errors
+                .push(format!("Failed to attach photos to entry {}: {}", id, e));
+        }
+    }
+
+    /// Create an imported entry, counting it in `stats`. In a dry run nothing is written:
+    /// the entry is only added to the preview in `stats` and `None` is returned.
+    fn create_entry(
+        &self,
+        title: Option<&str>,
+        content: &str,
+        journal_category: Option<&str>,
+        timestamp: NaiveDateTime,
+        stats: &mut ImportStats,
+    ) -> Result<Option<i64>> {
+        if self.dry_run {
+            stats.imported += 1;
+            stats.record_preview(timestamp, title, content);
+            return Ok(None);
+        }
 
-        let entries = parse_dayone_json(&content, filter_date)?;
+        let id = self.journal.create_entry_with_timestamp(
+            title,
+            content,
+            journal_category,
+            timestamp,
+        )?;
+        stats.imported += 1;
+        Ok(Some(id))
+    }
 
+    fn import_entries(
+        &self,
+        entries: Vec<ParsedEntry>,
+        journal_category: Option<&str>,
+    ) -> ImportStats {
         let mut stats = ImportStats {
             total: entries.len(),
-            imported: 0,
-            skipped: 0,
-            errors: Vec::new(),
+            ..Default::default()
         };
 
-        for entry in entries {
-            match self.journal.create_entry_with_timestamp(
+        for entry in self.progress.track(entries.into_iter()) {
+            match self.create_entry(
                 entry.title.as_deref(),
                 &entry.content,
                 journal_category,
                 entry.timestamp,
+                &mut stats,
             ) {
-                Ok(_) => stats.imported += 1,
+                Ok(None) => {}
+                Ok(Some(id)) => {
+                    if entry.starred
+                        && let Err(e) = self.journal.set_entry_starred(id, true)
+                    {
+                        stats
+                            .errors
+                            .push(format!("Failed to star entry {}: {}", id, e));
+                    }
+                }
                 Err(e) => {
                     stats.errors.push(format!(
                         "Failed to import entry at {}: {}",
@@ -91,16 +710,166 @@ impl<'a> Importer<'a> {
             }
         }
 
+        stats
+    }
+
+    /// Import entries from a directory of markdown files (one entry per file).
+    ///
+    /// The timestamp of each entry is taken from the YAML frontmatter (`timestamp`,
+    /// `date` or `created`), then from a `YYYY-MM-DD` filename, and finally from the
+    /// file modification time. Entries already present in the journal are skipped.
+    pub fn import_from_directory(
+        &self,
+        dir_path: &str,
+        journal_category: Option<&str>,
+        date_filter: DateFilter,
+        recursive: bool,
+    ) -> Result<ImportStats> {
+        let dir = Path::new(dir_path);
+        let mut files = Vec::new();
+        if dir.is_file() {
+            files.push(dir.to_path_buf());
+        } else if dir.is_dir() {
+            collect_markdown_files(dir, recursive, &mut files)?;
+            files.sort();
+        } else {
+            return Err(anyhow::anyhow!("Not a directory: {}", dir_path));
+        }
+
+        let mut stats = ImportStats::default();
+
+        for file in self.progress.track(files.into_iter()) {
+            let entry = match parse_markdown_file(&file) {
+                Ok(Some(entry)) => entry,
+                Ok(None) => continue,
+                Err(e) => {
+                    stats.total += 1;
+                    stats.skipped += 1;
+                    stats
+                        .errors
+                        .push(format!("Failed to parse {}: {}", file.display(), e));
+                    continue;
+                }
+            };
+
+            // Skip entries outside the date filter
+            if !date_filter.matches(entry.timestamp.date()) {
+                continue;
+            }
+
+            stats.total += 1;
+
+            let timestamp_utc = DateTime::<Utc>::from_naive_utc_and_offset(entry.timestamp, Utc);
+            match self.journal.entry_exists(timestamp_utc, &entry.content) {
+                Ok(true) => {
+                    stats.skipped += 1;
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    stats.skipped += 1;
+                    stats.errors.push(format!(
+                        "Failed to check for duplicates of {}: {}",
+                        file.display(),
+                        e
+                    ));
+                    continue;
+                }
+            }
+
+            if let Err(e) = self.create_entry(
+                entry.title.as_deref(),
+                &entry.content,
+                journal_category,
+                entry.timestamp,
+                &mut stats,
+            ) {
+                stats
+                    .errors
+                    .push(format!("Failed to import {}: {}", file.display(), e));
+                stats.skipped += 1;
+            }
+        }
+
         Ok(stats)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ImportStats {
     pub total: usize,
+    /// Entries created, or that would be created in a dry run
     pub imported: usize,
     pub skipped: usize,
+    /// `cl watch` only: entries rewritten with the new version of a changed file
+    pub updated: usize,
     pub errors: Vec<String>,
+    /// Dry runs only: oldest and newest timestamps of the entries that would be created
+    pub date_range: Option<(NaiveDateTime, NaiveDateTime)>,
+    /// Dry runs only: `date  title` lines for the first few entries that would be created
+    pub samples: Vec<String>,
+}
+
+/// Dates of the entries to import: one day, or a range (bounds included)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateFilter {
+    pub date: Option<NaiveDate>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+impl DateFilter {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        self.date.is_none_or(|filter| date == filter)
+            && self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+/// Number of sample entries shown by a dry run
+const DRY_RUN_SAMPLES: usize = 5;
+
+impl ImportStats {
+    /// Add the counts, errors and dry-run preview of another import to these
+    fn merge(&mut self, other: ImportStats) {
+        self.total += other.total;
+        self.imported += other.imported;
+        self.skipped += other.skipped;
+        self.updated += other.updated;
+        self.errors.extend(other.errors);
+        if let Some((first, last)) = other.date_range {
+            self.date_range = Some(match self.date_range {
+                Some((ours_first, ours_last)) => (ours_first.min(first), ours_last.max(last)),
+                None => (first, last),
+            });
+        }
+        let room = DRY_RUN_SAMPLES.saturating_sub(self.samples.len());
+        self.samples.extend(other.samples.into_iter().take(room));
+    }
+
+    fn record_preview(&mut self, timestamp: NaiveDateTime, title: Option<&str>, content: &str) {
+        self.date_range = Some(match self.date_range {
+            Some((first, last)) => (first.min(timestamp), last.max(timestamp)),
+            None => (timestamp, timestamp),
+        });
+
+        if self.samples.len() < DRY_RUN_SAMPLES {
+            let summary = title
+                .or_else(|| content.lines().find(|line| !line.trim().is_empty()))
+                .unwrap_or("")
+                .trim();
+            let summary = if summary.chars().count() > 60 {
+                format!("{}…", summary.chars().take(60).collect::<String>())
+            } else {
+                summary.to_string()
+            };
+            self.samples.push(format!(
+                "{}  {}",
+                timestamp.format("%Y-%m-%d %H:%M"),
+                summary
+            ));
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -108,13 +877,45 @@ struct ParsedEntry {
     timestamp: NaiveDateTime,
     title: Option<String>,
     content: String,
+    starred: bool,
 }
 
-/// Parse an org-journal file and extract entries
-fn parse_org_journal(content: &str, filter_date: Option<NaiveDate>) -> Result<Vec<ParsedEntry>> {
-    let lines: Vec<&str> = content.lines().collect();
-    let mut entries = Vec::new();
-    let mut current_date: Option<NaiveDate> = None;
+/// Files of an org-journal directory: `.org` files and files named after a date, sorted
+fn org_journal_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for dir_entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let file = dir_entry?.path();
+        let is_org = file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("org"));
+        let is_dated = file.extension().is_none() && org_journal_file_date(&file).is_some();
+        if file.is_file() && (is_org || is_dated) {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Date in the name of an org-journal file (`20250907`, `2025-09-07.org`, ...)
+fn org_journal_file_date(file: &Path) -> Option<NaiveDate> {
+    let stem = file.file_stem()?.to_str()?;
+    ["%Y%m%d", "%Y-%m-%d", "%Y_%m_%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(stem, format).ok())
+}
+
+/// Parse an org-journal file and extract entries
+fn parse_org_journal(
+    content: &str,
+    file_date: Option<NaiveDate>,
+    date_filter: DateFilter,
+) -> Result<Vec<ParsedEntry>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries = Vec::new();
+    let mut current_date: Option<NaiveDate> = file_date;
     let mut i = 0;
 
     while i < lines.len() {
@@ -125,15 +926,17 @@ fn parse_org_journal(content: &str, filter_date: Option<NaiveDate>) -> Result<Ve
             let date_str = line.strip_prefix("* ").unwrap().trim();
             current_date = parse_org_date_header(date_str);
 
-            // Skip to after the :PROPERTIES: block
+            // Skip to after the :PROPERTIES: block, if the header has one
             i += 1;
-            while i < lines.len() {
-                let prop_line = lines[i].trim();
-                if prop_line == ":END:" {
+            if lines.get(i).is_some_and(|l| l.trim() == ":PROPERTIES:") {
+                while i < lines.len() {
+                    let prop_line = lines[i].trim();
+                    if prop_line == ":END:" {
+                        i += 1;
+                        break;
+                    }
                     i += 1;
-                    break;
                 }
-                i += 1;
             }
             continue;
         }
@@ -141,12 +944,10 @@ fn parse_org_journal(content: &str, filter_date: Option<NaiveDate>) -> Result<Ve
         // Parse entry header (e.g., "** 14:30 My Title")
         if line.starts_with("** ") {
             if let Some(date) = current_date {
-                // Skip if filter_date is set and doesn't match
-                if let Some(filter) = filter_date {
-                    if date != filter {
-                        i += 1;
-                        continue;
-                    }
+                // Skip entries outside the date filter
+                if !date_filter.matches(date) {
+                    i += 1;
+                    continue;
                 }
 
                 let entry_header = line.strip_prefix("** ").unwrap().trim();
@@ -176,207 +977,1102 @@ fn parse_org_journal(content: &str, filter_date: Option<NaiveDate>) -> Result<Ve
                         timestamp,
                         title,
                         content: markdown_content,
+                        starred: false,
                     });
                     continue;
                 }
             }
         }
-
-        i += 1;
+
+        i += 1;
+    }
+
+    Ok(entries)
+}
+
+/// Parse org-journal date header.
+///
+/// Recognizes the org-journal default (`Saturday, 07/09/2025`), org active and inactive
+/// timestamps (`<2025-09-07 Sun>`, `[2025-09-07 Sun]`) and plain ISO dates (`2025-09-07`).
+/// The first word that parses as a date wins.
+fn parse_org_date_header(date_str: &str) -> Option<NaiveDate> {
+    date_str
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| word.trim_matches(|c| matches!(c, '<' | '>' | '[' | ']')))
+        .find_map(parse_org_date_word)
+}
+
+/// Parse a single date word: `YYYY-MM-DD`, `YYYY/MM/DD` or `DD/MM/YYYY`
+fn parse_org_date_word(word: &str) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let parts: Vec<&str> = word.split('/').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let (year, month, day) = if parts[0].len() == 4 {
+        (parts[0], parts[1], parts[2])
+    } else {
+        (parts[2], parts[1], parts[0])
+    };
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// Parse entry header to extract time and title
+fn parse_entry_header(header: &str) -> (Option<&str>, Option<String>) {
+    // Format: "14:30 My Title" or just "14:30"
+    let parts: Vec<&str> = header.splitn(2, ' ').collect();
+    if parts.is_empty() {
+        return (None, None);
+    }
+
+    let time_str = parts[0];
+    let title = if parts.len() > 1 {
+        let title_text = parts[1].trim();
+        if title_text.is_empty() {
+            None
+        } else {
+            Some(title_text.to_string())
+        }
+    } else {
+        None
+    };
+
+    (Some(time_str), title)
+}
+
+/// Parse timestamp from date and time string
+fn parse_timestamp(date: NaiveDate, time_str: Option<&str>) -> Option<NaiveDateTime> {
+    if let Some(time) = time_str {
+        let parts: Vec<&str> = time.split(':').collect();
+        if parts.len() >= 2 {
+            if let (Ok(hour), Ok(minute)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
+                return date.and_hms_opt(hour, minute, 0);
+            }
+        }
+    }
+    None
+}
+
+/// Convert org-mode format to markdown
+fn convert_org_to_markdown(org: &str) -> String {
+    let mut result = String::new();
+    let lines: Vec<&str> = org.lines().collect();
+    let mut in_src_block = false;
+    let mut in_quote = false;
+
+    for line in lines {
+        let trimmed = line.trim();
+
+        // Handle code blocks
+        if trimmed.starts_with("#+BEGIN_SRC") {
+            in_src_block = true;
+            let lang = trimmed.strip_prefix("#+BEGIN_SRC").unwrap_or("").trim();
+            result.push_str(&format!("```{}\n", lang));
+            continue;
+        }
+        if trimmed == "#+END_SRC" {
+            in_src_block = false;
+            result.push_str("```\n");
+            continue;
+        }
+
+        // Handle quotes
+        if trimmed.starts_with("#+BEGIN_QUOTE") {
+            in_quote = true;
+            result.push_str("> ");
+            continue;
+        }
+        if trimmed == "#+END_QUOTE" {
+            in_quote = false;
+            result.push('\n');
+            continue;
+        }
+
+        // Pass through content in code blocks as-is
+        if in_src_block {
+            result.push_str(line);
+            result.push('\n');
+            continue;
+        }
+
+        // Convert org formatting to markdown
+        let mut converted = line.to_string();
+
+        // Headings: *** -> ###
+        // Org-mode headings must have a space after the asterisks
+        let is_heading = trimmed.starts_with('*') && {
+            let after_asterisks = trimmed.trim_start_matches('*');
+            after_asterisks.starts_with(' ')
+        };
+
+        if is_heading {
+            let level = trimmed.chars().take_while(|&c| c == '*').count();
+            let rest = trimmed.trim_start_matches('*').trim();
+            converted = format!("{} {}", "#".repeat(level), rest);
+        } else {
+            // Bold: *text* -> **text**
+            converted = converted.replace("*", "**");
+
+            // Italic: /text/ -> *text*
+            converted = convert_delimiter(&converted, '/', '*');
+
+            // Strikethrough: +text+ -> ~~text~~
+            converted = convert_delimiter(&converted, '+', '~');
+
+            // Inline code: ~code~ -> `code`
+            converted = convert_delimiter(&converted, '~', '`');
+
+            // Links: [[url][text]] -> [text](url)
+            converted = convert_org_links(&converted);
+        }
+
+        if in_quote && !converted.trim().is_empty() {
+            result.push_str("> ");
+        }
+        result.push_str(&converted);
+        result.push('\n');
+    }
+
+    result.trim().to_string()
+}
+
+/// Convert delimiter-based formatting (helper for org to markdown conversion)
+fn convert_delimiter(text: &str, from_delim: char, to_delim: char) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    let mut in_delimiter = false;
+
+    while let Some(c) = chars.next() {
+        if c == from_delim {
+            if in_delimiter {
+                result.push(to_delim);
+                in_delimiter = false;
+            } else {
+                result.push(to_delim);
+                in_delimiter = true;
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Convert org-mode links [[url][text]] to markdown [text](url)
+fn convert_org_links(text: &str) -> String {
+    let mut result = text.to_string();
+
+    // Simple regex-like replacement for [[url][text]] pattern
+    while let Some(start) = result.find("[[") {
+        if let Some(middle) = result[start..].find("][") {
+            if let Some(end) = result[start + middle..].find("]]") {
+                let url_start = start + 2;
+                let url_end = start + middle;
+                let text_start = url_end + 2;
+                let text_end = start + middle + end;
+
+                let url = &result[url_start..url_end];
+                let link_text = &result[text_start..text_end];
+
+                let markdown_link = format!("[{}]({})", link_text, url);
+                result.replace_range(start..text_end + 2, &markdown_link);
+            } else {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+
+    result
+}
+
+// jrnl import functions
+
+/// Timestamp formats of jrnl entry headers (jrnl's default `%F %r` and the 24-hour ones)
+const JRNL_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %I:%M %p",
+    "%Y-%m-%d %I:%M:%S %p",
+];
+
+/// Parse a jrnl plain-text journal: entries start with a `[date time] title` line and
+/// run until the next one.
+///
+/// A trailing ` *` on the title line marks a starred entry, and jrnl's `@tags` become
+/// `#tags`. Single-line entries are imported untitled, with the line as their content;
+/// tags that only appear in the title are appended to the content so they are kept.
+fn parse_jrnl(content: &str, date_filter: DateFilter) -> Vec<ParsedEntry> {
+    static HEADER_RE: OnceLock<Regex> = OnceLock::new();
+    let header_re = HEADER_RE.get_or_init(|| {
+        Regex::new(r"^\[(\d{4}-\d{2}-\d{2} [^\]]+)\] ?(.*)$").expect("valid jrnl header regex")
+    });
+
+    let mut headers: Vec<(NaiveDateTime, &str)> = Vec::new();
+    let mut bodies: Vec<Vec<&str>> = Vec::new();
+    for line in content.lines() {
+        let header = header_re.captures(line).and_then(|captures| {
+            let date = captures.get(1)?.as_str().trim();
+            let timestamp = JRNL_TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())?;
+            Some((
+                timestamp,
+                captures.get(2).map_or("", |title| title.as_str()),
+            ))
+        });
+        match header {
+            Some(header) => {
+                headers.push(header);
+                bodies.push(Vec::new());
+            }
+            // Text before the first entry is not part of any entry
+            None => {
+                if let Some(body) = bodies.last_mut() {
+                    body.push(line);
+                }
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    for ((timestamp, title), body) in headers.into_iter().zip(bodies) {
+        if !date_filter.matches(timestamp.date()) {
+            continue;
+        }
+
+        let title = title.trim_end();
+        let (title, starred) = match title.strip_suffix(" *") {
+            Some(title) => (title.trim_end(), true),
+            None => (title, false),
+        };
+        let title = jrnl_tags_to_hashtags(title);
+        let mut body = jrnl_tags_to_hashtags(body.join("\n").trim());
+
+        let (title, content) = if body.is_empty() {
+            (None, title)
+        } else {
+            let body_tags = extract_tags(&body);
+            let missing_tags: Vec<String> = extract_tags(&title)
+                .into_iter()
+                .filter(|tag| !body_tags.contains(tag))
+                .map(|tag| format!("#{}", tag))
+                .collect();
+            if !missing_tags.is_empty() {
+                body.push_str("\n\n");
+                body.push_str(&missing_tags.join(" "));
+            }
+            (Some(title), body)
+        };
+        if content.is_empty() {
+            continue;
+        }
+
+        entries.push(ParsedEntry {
+            timestamp,
+            title: title.filter(|title| !title.is_empty()),
+            content,
+            starred,
+        });
+    }
+
+    entries
+}
+
+/// Turn jrnl `@tags` into `#tags`, leaving email addresses alone
+fn jrnl_tags_to_hashtags(text: &str) -> String {
+    static TAG_RE: OnceLock<Regex> = OnceLock::new();
+    let re = TAG_RE.get_or_init(|| {
+        Regex::new(r"(^|[^\w@.])@(\p{L}[\p{L}\p{N}_-]*)").expect("valid jrnl tag regex")
+    });
+    re.replace_all(text, "$1#$2").into_owned()
+}
+
+// Journey import functions
+
+#[derive(Debug, Deserialize)]
+struct JourneyEntry {
+    #[serde(default)]
+    text: String,
+    /// Milliseconds since the epoch
+    date_journal: i64,
+    #[serde(default)]
+    timezone: Option<String>,
+    /// `html` for entries written with Journey's rich text editor
+    #[serde(default, rename = "type")]
+    text_type: Option<String>,
+    #[serde(default)]
+    favourite: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// File names of photos, next to the entry file in the export
+    #[serde(default)]
+    photos: Vec<String>,
+}
+
+/// Parse a Journey entry file into an entry and the file names of its photos, or `None`
+/// when the entry's local date (in its recorded timezone) is outside `date_filter`.
+///
+/// Journey tags are appended to the content as `#tags`.
+fn parse_journey_entry(
+    content: &str,
+    date_filter: DateFilter,
+) -> Result<Option<(ParsedEntry, Vec<String>)>> {
+    let journey: JourneyEntry =
+        serde_json::from_str(content).context("Failed to parse Journey JSON entry")?;
+
+    let utc_time = DateTime::<Utc>::from_timestamp_millis(journey.date_journal)
+        .context(format!("Invalid date_journal: {}", journey.date_journal))?;
+    let local_date = match journey.timezone.as_deref().map(str::parse::<Tz>) {
+        Some(Ok(tz)) => utc_time.with_timezone(&tz).date_naive(),
+        _ => utc_time.date_naive(),
+    };
+    if !date_filter.matches(local_date) {
+        return Ok(None);
+    }
+
+    let mut text = if journey.text_type.as_deref() == Some("html") {
+        html_to_markdown(&journey.text)
+    } else {
+        journey.text.trim().to_string()
+    };
+    append_hashtags(&mut text, &journey.tags);
+    if text.is_empty() && journey.photos.is_empty() {
+        return Err(anyhow::anyhow!("Empty entry"));
+    }
+
+    Ok(Some((
+        ParsedEntry {
+            timestamp: utc_time.naive_utc(),
+            title: None,
+            content: text,
+            starred: journey.favourite,
+        },
+        journey.photos,
+    )))
+}
+
+/// Files directly in `dir` with the extension `extension`, sorted
+fn files_with_extension(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for dir_entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let file = dir_entry?.path();
+        if file.is_file()
+            && file
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+        {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Extract a zip archive into a new temporary directory, with `unzip` or `tar`
+/// (bsdtar, the `tar` of macOS and Windows, reads zip files)
+fn extract_zip(archive: &Path) -> Result<PathBuf> {
+    use rand::distr::SampleString;
+
+    let dir = std::env::temp_dir().join(format!(
+        "cl_import_{}",
+        rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 8)
+    ));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create temporary directory {}", dir.display()))?;
+
+    let mut command = if which::which("unzip").is_ok() {
+        let mut command = std::process::Command::new("unzip");
+        command.arg("-q").arg(archive).arg("-d").arg(&dir);
+        command
+    } else {
+        let mut command = std::process::Command::new("tar");
+        command.arg("-xf").arg(archive).arg("-C").arg(&dir);
+        command
+    };
+    let status = command
+        .status()
+        .context("Failed to run unzip or tar; install one of them to import zip archives")?;
+    if !status.success() {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(anyhow::anyhow!(
+            "Failed to extract {} ({})",
+            archive.display(),
+            status
+        ));
+    }
+    Ok(dir)
+}
+
+/// Journal name taken from an imported file's name (an Evernote notebook, a DayOne journal)
+fn file_journal_name(file: &Path) -> Option<String> {
+    file.file_stem()
+        .map(|stem| stem.to_string_lossy().trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+// Plain-text import functions
+
+/// Split `content` into entries at the lines matching `pattern` (see `import_from_text`).
+/// Header lines whose date can't be parsed are reported and kept as content.
+fn parse_dated_text(
+    content: &str,
+    pattern: &Regex,
+    date_format: Option<&str>,
+    date_filter: DateFilter,
+) -> (Vec<ParsedEntry>, Vec<String>) {
+    let mut headers: Vec<(NaiveDateTime, String)> = Vec::new();
+    let mut bodies: Vec<Vec<&str>> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let header = pattern.captures(line).and_then(|captures| {
+            let whole = captures.get(0).expect("whole match");
+            let date = captures
+                .name("date")
+                .or(captures.get(1))
+                .unwrap_or(whole)
+                .as_str()
+                .trim();
+            let timestamp = match date_format {
+                Some(format) => NaiveDateTime::parse_from_str(date, format)
+                    .ok()
+                    .or_else(|| {
+                        NaiveDate::parse_from_str(date, format)
+                            .ok()
+                            .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    }),
+                None => parse_note_timestamp(date),
+            };
+            if timestamp.is_none() {
+                errors.push(format!(
+                    "Line {}: could not parse date '{}'",
+                    number + 1,
+                    date
+                ));
+            }
+            let title = format!("{}{}", &line[..whole.start()], &line[whole.end()..]);
+            let title = title
+                .trim()
+                .trim_matches(|c: char| matches!(c, '-' | ':' | '#'));
+            timestamp.map(|timestamp| (timestamp, title.trim().to_string()))
+        });
+
+        match header {
+            Some(header) => {
+                headers.push(header);
+                bodies.push(Vec::new());
+            }
+            None => {
+                if let Some(body) = bodies.last_mut() {
+                    body.push(line);
+                }
+            }
+        }
+    }
+
+    let entries = headers
+        .into_iter()
+        .zip(bodies)
+        .filter(|((timestamp, _), _)| date_filter.matches(timestamp.date()))
+        .filter_map(|((timestamp, title), body)| {
+            let content = body.join("\n").trim().to_string();
+            if content.is_empty() && title.is_empty() {
+                return None;
+            }
+            let (title, content) = if content.is_empty() {
+                (None, title)
+            } else {
+                (Some(title).filter(|title| !title.is_empty()), content)
+            };
+            Some(ParsedEntry {
+                timestamp,
+                title,
+                content,
+                starred: false,
+            })
+        })
+        .collect();
+
+    (entries, errors)
+}
+
+// Evernote import functions
+
+/// Parse a `<note>` of an ENEX file: title, creation date (UTC), tags and ENML content
+fn parse_enex_note(note: &str) -> Result<ParsedEntry> {
+    let content_xml = xml_elements(note, "content").first().copied();
+    let enml = content_xml.map(xml_text).unwrap_or_default();
+    // The content may hold any markup: metadata is read from the rest of the note
+    let metadata = match content_xml {
+        Some(content_xml) => note.replacen(content_xml, "", 1),
+        None => note.to_string(),
+    };
+    let field = |tag: &str| {
+        xml_elements(&metadata, tag)
+            .first()
+            .map(|inner| xml_text(inner).trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    let created = field("created").context("Note without a creation date")?;
+    let timestamp = NaiveDateTime::parse_from_str(&created, "%Y%m%dT%H%M%SZ")
+        .context(format!("Invalid creation date: {}", created))?;
+
+    let mut content = html_to_markdown(&enml);
+    let tags: Vec<String> = xml_elements(&metadata, "tag")
+        .into_iter()
+        .map(xml_text)
+        .collect();
+    append_hashtags(&mut content, &tags);
+
+    Ok(ParsedEntry {
+        timestamp,
+        title: field("title"),
+        content,
+        starred: false,
+    })
+}
+
+// Diaro import functions
+
+struct DiaroEntry {
+    entry: ParsedEntry,
+    /// Title of the entry's folder
+    folder: Option<String>,
+    /// Photo file names, in the backup's `media/photo` directory
+    photos: Vec<String>,
+}
+
+/// Parse a Diaro XML backup: `<table name="...">` elements holding `<r>` rows of
+/// simple fields. Entries that can't be read are returned as error messages.
+fn parse_diaro_xml(content: &str, date_filter: DateFilter) -> (Vec<DiaroEntry>, Vec<String>) {
+    static TABLE_RE: OnceLock<Regex> = OnceLock::new();
+    let table_re = TABLE_RE.get_or_init(|| {
+        Regex::new(r#"(?s)<table name="([^"]+)">(.*?)</table>"#).expect("valid table regex")
+    });
+    let tables: HashMap<&str, Vec<HashMap<String, String>>> = table_re
+        .captures_iter(content)
+        .map(|captures| {
+            let name = captures.get(1).map_or("", |name| name.as_str());
+            let rows = xml_elements(captures.get(2).map_or("", |rows| rows.as_str()), "r")
+                .into_iter()
+                .map(xml_fields)
+                .collect();
+            (name, rows)
+        })
+        .collect();
+    let table = |name: &str| tables.get(name).map(Vec::as_slice).unwrap_or_default();
+    let titles = |name: &str| -> HashMap<String, String> {
+        table(name)
+            .iter()
+            .filter_map(|row| Some((row.get("uid")?.clone(), row.get("title")?.clone())))
+            .collect()
+    };
+    let folders = titles("diaro_folders");
+    let tags = titles("diaro_tags");
+
+    let mut photos: HashMap<&str, Vec<String>> = HashMap::new();
+    for row in table("diaro_attachments") {
+        if let (Some(entry_uid), Some(filename)) = (row.get("entry_uid"), row.get("filename"))
+            && row.get("type").is_none_or(|kind| kind == "photo")
+        {
+            photos
+                .entry(entry_uid.as_str())
+                .or_default()
+                .push(filename.clone());
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for row in table("diaro_entries") {
+        let uid = row.get("uid").map_or("", String::as_str);
+        let Some(utc_time) = row
+            .get("date")
+            .and_then(|date| date.trim().parse().ok())
+            .and_then(DateTime::<Utc>::from_timestamp_millis)
+        else {
+            errors.push(format!("Entry {} has no valid date", uid));
+            continue;
+        };
+        let local_date = match row
+            .get("tz_offset")
+            .and_then(|offset| offset.trim().parse::<chrono::FixedOffset>().ok())
+        {
+            Some(offset) => utc_time.with_timezone(&offset).date_naive(),
+            None => utc_time.date_naive(),
+        };
+        if !date_filter.matches(local_date) {
+            continue;
+        }
+
+        let mut text = row
+            .get("text")
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default();
+        let entry_tags: Vec<String> = row
+            .get("tags")
+            .map(|uids| {
+                uids.split(',')
+                    .filter_map(|uid| tags.get(uid.trim()).cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        append_hashtags(&mut text, &entry_tags);
+        let title = row
+            .get("title")
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        let entry_photos = photos.remove(uid).unwrap_or_default();
+        if text.is_empty() && title.is_none() && entry_photos.is_empty() {
+            continue;
+        }
+
+        entries.push(DiaroEntry {
+            entry: ParsedEntry {
+                timestamp: utc_time.naive_utc(),
+                title,
+                content: text,
+                starred: false,
+            },
+            folder: row
+                .get("folder_uid")
+                .and_then(|uid| folders.get(uid.trim()))
+                .map(|folder| folder.trim().to_string())
+                .filter(|folder| !folder.is_empty()),
+            photos: entry_photos,
+        });
+    }
+
+    (entries, errors)
+}
+
+/// Inner XML of each `<tag>` element in `xml`; elements with that name must not nest
+fn xml_elements<'x>(xml: &'x str, tag: &str) -> Vec<&'x str> {
+    let re = Regex::new(&format!(
+        r"(?s)<{tag}(?:\s[^>]*)?>(.*?)</{tag}>|<{tag}(?:\s[^>]*)?/>",
+        tag = regex::escape(tag)
+    ))
+    .expect("valid element regex");
+    re.captures_iter(xml)
+        .map(|captures| captures.get(1).map_or("", |inner| inner.as_str()))
+        .collect()
+}
+
+/// Child elements of a flat XML row (`<name>text</name>...`), by name
+fn xml_fields(row: &str) -> HashMap<String, String> {
+    static FIELD_RE: OnceLock<Regex> = OnceLock::new();
+    let re = FIELD_RE.get_or_init(|| {
+        Regex::new(r"(?s)<([\w:-]+)>(.*?)</([\w:-]+)>").expect("valid field regex")
+    });
+    re.captures_iter(row)
+        .filter(|captures| captures[1] == captures[3])
+        .map(|captures| (captures[1].to_string(), xml_text(&captures[2])))
+        .collect()
+}
+
+/// Text content of an XML element: CDATA unwrapped, entities decoded
+fn xml_text(inner: &str) -> String {
+    match inner
+        .trim()
+        .strip_prefix("<![CDATA[")
+        .and_then(|cdata| cdata.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => decode_html_entities(inner),
+    }
+}
+
+/// Append `tags` missing from `text` as a final line of `#tags`, since entry tags
+/// come from the content
+fn append_hashtags(text: &mut String, tags: &[String]) {
+    let existing_tags = extract_tags(text);
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().trim_start_matches('#').replace(' ', "-"))
+        .filter(|tag| !tag.is_empty() && !existing_tags.contains(&tag.to_lowercase()))
+        .map(|tag| format!("#{}", tag))
+        .collect();
+    if !tags.is_empty() {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&tags.join(" "));
+    }
+}
+
+/// Convert HTML (Journey's rich text, Evernote's ENML) to markdown. Paragraphs, headings,
+/// emphasis, links, lists, checkboxes and code are kept; other tags are dropped, keeping
+/// their text.
+fn html_to_markdown(html: &str) -> String {
+    static TAG_RE: OnceLock<Regex> = OnceLock::new();
+    static HREF_RE: OnceLock<Regex> = OnceLock::new();
+    let tag_re = TAG_RE.get_or_init(|| {
+        Regex::new(r"(?s)<!--.*?-->|<![^>]*>|<\?.*?\?>|<(/?)([a-zA-Z][a-zA-Z0-9:-]*)([^>]*)>")
+            .expect("valid tag regex")
+    });
+    let href_re = HREF_RE.get_or_init(|| {
+        Regex::new(r#"(?i)href\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid href regex")
+    });
+
+    let mut markdown = String::new();
+    // `None` for a bullet list, the next number for an ordered one
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let mut links: Vec<Option<String>> = Vec::new();
+    let mut in_pre = false;
+    let mut last = 0;
+
+    for captures in tag_re.captures_iter(html) {
+        let tag = captures.get(0).expect("whole match");
+        push_html_text(&mut markdown, &html[last..tag.start()], in_pre);
+        last = tag.end();
+        let Some(name) = captures.get(2) else {
+            continue;
+        };
+        let closing = !captures[1].is_empty();
+        let name = name.as_str().to_lowercase();
+
+        match (name.as_str(), closing) {
+            ("br", _) => markdown.push('\n'),
+            ("p" | "div" | "blockquote" | "table" | "tr", _) => end_block(&mut markdown),
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                end_block(&mut markdown);
+                let level = name[1..].parse().unwrap_or(1);
+                markdown.push_str(&"#".repeat(level));
+                markdown.push(' ');
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => end_block(&mut markdown),
+            ("strong" | "b", _) => markdown.push_str("**"),
+            ("em" | "i", _) => markdown.push('*'),
+            ("s" | "del" | "strike", _) => markdown.push_str("~~"),
+            ("code", _) if !in_pre => markdown.push('`'),
+            ("pre", false) => {
+                end_block(&mut markdown);
+                markdown.push_str("```\n");
+                in_pre = true;
+            }
+            ("pre", true) => {
+                if !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str("```");
+                end_block(&mut markdown);
+                in_pre = false;
+            }
+            ("hr", _) => {
+                end_block(&mut markdown);
+                markdown.push_str("---");
+                end_block(&mut markdown);
+            }
+            ("a", false) => {
+                let href = href_re.captures(&captures[3]).and_then(|href| {
+                    href.get(1)
+                        .or(href.get(2))
+                        .map(|url| decode_html_entities(url.as_str()))
+                });
+                if href.is_some() {
+                    markdown.push('[');
+                }
+                links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = links.pop() {
+                    markdown.push_str(&format!("]({})", href));
+                }
+            }
+            ("ul", false) => {
+                end_line(&mut markdown);
+                lists.push(None);
+            }
+            ("ol", false) => {
+                end_line(&mut markdown);
+                lists.push(Some(1));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                if lists.is_empty() {
+                    end_block(&mut markdown);
+                }
+            }
+            ("li", false) => {
+                end_line(&mut markdown);
+                let depth = lists.len().max(1) - 1;
+                markdown.push_str(&"  ".repeat(depth));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        markdown.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => markdown.push_str("- "),
+                }
+            }
+            ("li", true) => end_line(&mut markdown),
+            ("en-todo", false) => {
+                if captures[3].contains("checked=\"true\"") {
+                    markdown.push_str("[x] ");
+                } else {
+                    markdown.push_str("[ ] ");
+                }
+            }
+            _ => {}
+        }
     }
-
-    Ok(entries)
+    push_html_text(&mut markdown, &html[last..], in_pre);
+
+    static BLANK_LINES_RE: OnceLock<Regex> = OnceLock::new();
+    let blank_lines_re =
+        BLANK_LINES_RE.get_or_init(|| Regex::new(r"\n{3,}").expect("valid blank lines regex"));
+    let markdown: Vec<&str> = markdown.lines().map(str::trim_end).collect();
+    blank_lines_re
+        .replace_all(&markdown.join("\n"), "\n\n")
+        .trim()
+        .to_string()
 }
 
-/// Parse org-journal date header (e.g., "Saturday, 07/09/2025")
-fn parse_org_date_header(date_str: &str) -> Option<NaiveDate> {
-    // Extract date part after the comma
-    if let Some(date_part) = date_str.split(',').nth(1) {
-        let date_part = date_part.trim();
-        // Parse "07/09/2025" format (DD/MM/YYYY)
-        let parts: Vec<&str> = date_part.split('/').collect();
-        if parts.len() == 3 {
-            if let (Ok(day), Ok(month), Ok(year)) = (
-                parts[0].parse::<u32>(),
-                parts[1].parse::<u32>(),
-                parts[2].parse::<i32>(),
-            ) {
-                return NaiveDate::from_ymd_opt(year, month, day);
-            }
+/// Append HTML text, collapsing whitespace outside `<pre>` blocks
+fn push_html_text(markdown: &mut String, text: &str, in_pre: bool) {
+    let text = decode_html_entities(text);
+    if in_pre {
+        markdown.push_str(&text);
+        return;
+    }
+    for (i, word) in text.split_whitespace().enumerate() {
+        let at_line_start = markdown.is_empty() || markdown.ends_with(['\n', ' ']);
+        if (i > 0 || text.starts_with(char::is_whitespace)) && !at_line_start {
+            markdown.push(' ');
         }
+        markdown.push_str(word);
+    }
+    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+        markdown.push(' ');
     }
-    None
 }
 
-/// Parse entry header to extract time and title
-fn parse_entry_header(header: &str) -> (Option<&str>, Option<String>) {
-    // Format: "14:30 My Title" or just "14:30"
-    let parts: Vec<&str> = header.splitn(2, ' ').collect();
-    if parts.is_empty() {
-        return (None, None);
+fn end_line(markdown: &mut String) {
+    let trimmed = markdown.trim_end_matches(' ').len();
+    markdown.truncate(trimmed);
+    if !markdown.is_empty() && !markdown.ends_with('\n') {
+        markdown.push('\n');
     }
+}
 
-    let time_str = parts[0];
-    let title = if parts.len() > 1 {
-        let title_text = parts[1].trim();
-        if title_text.is_empty() {
-            None
-        } else {
-            Some(title_text.to_string())
-        }
-    } else {
-        None
-    };
+fn end_block(markdown: &mut String) {
+    end_line(markdown);
+    if !markdown.is_empty() && !markdown.ends_with("\n\n") {
+        markdown.push('\n');
+    }
+}
 
-    (Some(time_str), title)
+fn decode_html_entities(text: &str) -> String {
+    static ENTITY_RE: OnceLock<Regex> = OnceLock::new();
+    let re = ENTITY_RE.get_or_init(|| {
+        Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("valid entity regex")
+    });
+    re.replace_all(text, |captures: &regex::Captures| {
+        let entity = &captures[1];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or(entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        decoded.map_or_else(|| captures[0].to_string(), String::from)
+    })
+    .into_owned()
 }
 
-/// Parse timestamp from date and time string
-fn parse_timestamp(date: NaiveDate, time_str: Option<&str>) -> Option<NaiveDateTime> {
-    if let Some(time) = time_str {
-        let parts: Vec<&str> = time.split(':').collect();
-        if parts.len() >= 2 {
-            if let (Ok(hour), Ok(minute)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                return date.and_hms_opt(hour, minute, 0);
-            }
-        }
-    }
-    None
+/// Whether `path` is relative and stays below the directory it is joined to
+fn is_plain_relative(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
 }
 
-/// Convert org-mode format to markdown
-fn convert_org_to_markdown(org: &str) -> String {
-    let mut result = String::new();
-    let lines: Vec<&str> = org.lines().collect();
-    let mut in_src_block = false;
-    let mut in_quote = false;
+// Markdown directory import functions
 
-    for line in lines {
-        let trimmed = line.trim();
+/// Collect all markdown files in a directory, descending into subdirectories if `recursive`
+fn collect_markdown_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<()> {
+    let read_dir =
+        fs::read_dir(dir).context(format!("Failed to read directory: {}", dir.display()))?;
 
-        // Handle code blocks
-        if trimmed.starts_with("#+BEGIN_SRC") {
-            in_src_block = true;
-            let lang = trimmed.strip_prefix("#+BEGIN_SRC").unwrap_or("").trim();
-            result.push_str(&format!("```{}\n", lang));
-            continue;
-        }
-        if trimmed == "#+END_SRC" {
-            in_src_block = false;
-            result.push_str("```\n");
-            continue;
-        }
+    for dir_entry in read_dir {
+        let path = dir_entry?.path();
 
-        // Handle quotes
-        if trimmed.starts_with("#+BEGIN_QUOTE") {
-            in_quote = true;
-            result.push_str("> ");
-            continue;
-        }
-        if trimmed == "#+END_QUOTE" {
-            in_quote = false;
-            result.push('\n');
+        // Skip hidden files and folders (e.g. .obsidian, .git)
+        let is_hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        if is_hidden {
             continue;
         }
 
-        // Pass through content in code blocks as-is
-        if in_src_block {
-            result.push_str(line);
-            result.push('\n');
-            continue;
+        if path.is_dir() {
+            if recursive {
+                collect_markdown_files(&path, recursive, files)?;
+            }
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            })
+        {
+            files.push(path);
         }
+    }
 
-        // Convert org formatting to markdown
-        let mut converted = line.to_string();
-
-        // Headings: *** -> ###
-        // Org-mode headings must have a space after the asterisks
-        let is_heading = trimmed.starts_with('*') && {
-            let after_asterisks = trimmed.trim_start_matches('*');
-            after_asterisks.starts_with(' ')
-        };
-
-        if is_heading {
-            let level = trimmed.chars().take_while(|&c| c == '*').count();
-            let rest = trimmed.trim_start_matches('*').trim();
-            converted = format!("{} {}", "#".repeat(level), rest);
-        } else {
-            // Bold: *text* -> **text**
-            converted = converted.replace("*", "**");
-
-            // Italic: /text/ -> *text*
-            converted = convert_delimiter(&converted, '/', '*');
-
-            // Strikethrough: +text+ -> ~~text~~
-            converted = convert_delimiter(&converted, '+', '~');
+    Ok(())
+}
 
-            // Inline code: ~code~ -> `code`
-            converted = convert_delimiter(&converted, '~', '`');
+/// Parse a single markdown file into an entry, falling back to the file
+/// modification time when no date can be found in the frontmatter or filename.
+/// Returns `None` for empty notes.
+fn parse_markdown_file(path: &Path) -> Result<Option<ParsedEntry>> {
+    let content =
+        fs::read_to_string(path).context(format!("Failed to read file: {}", path.display()))?;
+    let file_stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+
+    let (timestamp, title, content) = parse_markdown_note(&content, file_stem)?;
+
+    if content.is_empty() && title.is_none() {
+        return Ok(None);
+    }
 
-            // Links: [[url][text]] -> [text](url)
-            converted = convert_org_links(&converted);
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => {
+            let modified = fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .context(format!(
+                    "Failed to read modification time: {}",
+                    path.display()
+                ))?;
+            DateTime::<Utc>::from(modified).naive_utc()
         }
+    };
 
-        if in_quote && !converted.trim().is_empty() {
-            result.push_str("> ");
-        }
-        result.push_str(&converted);
-        result.push('\n');
+    Ok(Some(ParsedEntry {
+        timestamp,
+        title,
+        content,
+        starred: false,
+    }))
+}
+
+/// Parse the text of a markdown note into (timestamp, title, content).
+///
+/// The title comes from the `title` frontmatter key, then from a leading `# ` heading,
+/// then from the filename (unless the filename is just a date, as in daily notes).
+fn parse_markdown_note(
+    text: &str,
+    file_stem: &str,
+) -> Result<(Option<NaiveDateTime>, Option<String>, String)> {
+    let mut timestamp = None;
+    let mut title = None;
+    let mut body = text;
+
+    // Extract optional YAML frontmatter
+    if let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+        && let Some(end) = rest.find("\n---")
+    {
+        let metadata: serde_yaml::Value =
+            serde_yaml::from_str(&rest[..end]).context("Failed to parse frontmatter YAML")?;
+
+        timestamp = ["timestamp", "date", "created"]
+            .iter()
+            .filter_map(|key| metadata.get(*key).and_then(|v| v.as_str()))
+            .find_map(parse_note_timestamp);
+        title = metadata
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        // Skip the closing delimiter line
+        body = rest[end + 4..]
+            .split_once('\n')
+            .map(|(_, after)| after)
+            .unwrap_or("");
     }
 
-    result.trim().to_string()
-}
+    let date_from_filename = file_stem
+        .get(..10)
+        .and_then(|prefix| NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok());
 
-/// Convert delimiter-based formatting (helper for org to markdown conversion)
-fn convert_delimiter(text: &str, from_delim: char, to_delim: char) -> String {
-    let mut result = String::new();
-    let mut chars = text.chars().peekable();
-    let mut in_delimiter = false;
+    if timestamp.is_none() {
+        timestamp = date_from_filename.and_then(|date| date.and_hms_opt(0, 0, 0));
+    }
 
-    while let Some(c) = chars.next() {
-        if c == from_delim {
-            if in_delimiter {
-                result.push(to_delim);
-                in_delimiter = false;
-            } else {
-                result.push(to_delim);
-                in_delimiter = true;
-            }
-        } else {
-            result.push(c);
+    let body = body.trim();
+    let heading = body
+        .lines()
+        .next()
+        .and_then(|line| line.trim().strip_prefix("# "));
+    let content = match heading {
+        Some(heading) if title.is_none() => {
+            title = Some(heading.trim().to_string());
+            body.lines()
+                .skip(1)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
         }
+        _ => body.to_string(),
+    };
+
+    if title.is_none() && date_from_filename.is_none() && !content.is_empty() {
+        title = Some(file_stem.to_string());
     }
 
-    result
+    Ok((timestamp, title, content))
 }
 
-/// Convert org-mode links [[url][text]] to markdown [text](url)
-fn convert_org_links(text: &str) -> String {
-    let mut result = text.to_string();
-
-    // Simple regex-like replacement for [[url][text]] pattern
-    while let Some(start) = result.find("[[") {
-        if let Some(middle) = result[start..].find("][") {
-            if let Some(end) = result[start + middle..].find("]]") {
-                let url_start = start + 2;
-                let url_end = start + middle;
-                let text_start = url_end + 2;
-                let text_end = start + middle + end;
+/// Parse a date or datetime string as found in markdown note frontmatter
+fn parse_note_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
 
-                let url = &result[url_start..url_end];
-                let link_text = &result[text_start..text_end];
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.naive_utc());
+    }
 
-                let markdown_link = format!("[{}]({})", link_text, url);
-                result.replace_range(start..text_end + 2, &markdown_link);
-            } else {
-                break;
-            }
-        } else {
-            break;
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt);
         }
     }
 
-    result
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
 }
 
 // DayOne JSON import structures and functions
@@ -392,6 +2088,23 @@ struct DayOneMetadata {
     version: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOnePhoto {
+    /// Referenced from the text as `dayone-moment://<identifier>`
+    identifier: String,
+    /// The file is `photos/<md5>.<type>` in the export
+    md5: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+impl DayOnePhoto {
+    fn file_name(&self) -> String {
+        format!("{}.{}", self.md5, self.kind.as_deref().unwrap_or("jpeg"))
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DayOneEntry {
@@ -404,6 +2117,10 @@ struct DayOneEntry {
     starred: bool,
     #[serde(default)]
     is_pinned: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    photos: Vec<DayOnePhoto>,
     time_zone: Option<String>,
     location: Option<DayOneLocation>,
 }
@@ -436,7 +2153,10 @@ struct LineAttributes {
 }
 
 /// Parse a DayOne JSON export file and extract entries
-fn parse_dayone_json(content: &str, filter_date: Option<NaiveDate>) -> Result<Vec<ParsedEntry>> {
+fn parse_dayone_json(
+    content: &str,
+    date_filter: DateFilter,
+) -> Result<Vec<(ParsedEntry, Vec<DayOnePhoto>)>> {
     let export: DayOneExport =
         serde_json::from_str(content).context("Failed to parse DayOne JSON file")?;
 
@@ -459,27 +2179,16 @@ fn parse_dayone_json(content: &str, filter_date: Option<NaiveDate>) -> Result<Ve
                 .and_then(|loc| loc.time_zone_name.as_ref())
         });
 
-        // Convert to local timezone if available, otherwise use UTC
-        let timestamp = if let Some(tz_string) = tz_str {
-            // Parse timezone (e.g., "Europe/Rome")
-            if let Ok(tz) = tz_string.parse::<Tz>() {
-                // Convert UTC time to the local timezone and get naive local time
-                let local_time = utc_time.with_timezone(&tz);
-                local_time.naive_local()
-            } else {
-                // If timezone parsing fails, fall back to UTC naive time
-                utc_time.naive_utc()
-            }
-        } else {
-            // If no timezone specified, use UTC naive time
-            utc_time.naive_utc()
+        // The day of the entry is its date in its own timezone (e.g., "Europe/Rome"),
+        // as in DayOne; without a valid timezone it's the UTC date
+        let local_date = match tz_str.map(|tz_string| tz_string.parse::<Tz>()) {
+            Some(Ok(tz)) => utc_time.with_timezone(&tz).date_naive(),
+            _ => utc_time.date_naive(),
         };
 
-        // Skip if filter_date is set and doesn't match
-        if let Some(filter) = filter_date {
-            if timestamp.date() != filter {
-                continue;
-            }
+        // Skip entries outside the date filter
+        if !date_filter.matches(local_date) {
+            continue;
         }
 
         // Try to extract title from richText if available
@@ -490,23 +2199,46 @@ fn parse_dayone_json(content: &str, filter_date: Option<NaiveDate>) -> Result<Ve
         };
 
         // Use the text field as content (it's already plain text)
-        let content = dayone_entry.text.trim().to_string();
+        let mut content = dayone_entry.text.trim().to_string();
+        append_hashtags(&mut content, &dayone_entry.tags);
 
         // Skip empty entries
         if content.is_empty() && title.is_none() {
             continue;
         }
 
-        entries.push(ParsedEntry {
-            timestamp,
-            title,
-            content,
-        });
+        entries.push((
+            ParsedEntry {
+                timestamp: utc_time.naive_utc(),
+                title,
+                content,
+                starred: dayone_entry.starred,
+            },
+            dayone_entry.photos,
+        ));
     }
 
     Ok(entries)
 }
 
+/// Replace the `dayone-moment://<identifier>` image links of `content` with the stored
+/// attachment paths in `stored`; links to photos that weren't imported are removed.
+fn rewrite_dayone_moments(content: &str, stored: &HashMap<String, String>) -> String {
+    static MOMENT_RE: OnceLock<Regex> = OnceLock::new();
+    let re = MOMENT_RE.get_or_init(|| {
+        Regex::new(r"!\[([^\]]*)\]\(dayone-moment:/+([A-Za-z0-9-]+)\)")
+            .expect("valid dayone-moment regex")
+    });
+    re.replace_all(content, |captures: &regex::Captures| {
+        match stored.get(&captures[2]) {
+            Some(path) => format!("![{}]({})", &captures[1], path),
+            None => String::new(),
+        }
+    })
+    .trim()
+    .to_string()
+}
+
 /// Extract title from DayOne richText JSON
 /// The first block with a header attribute is considered the title
 fn extract_title_from_rich_text(rich_text_str: &str) -> Option<String> {
@@ -531,6 +2263,7 @@ fn extract_title_from_rich_text(rich_text_str: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
 
     #[test]
     fn test_parse_org_date_header() {
@@ -538,6 +2271,40 @@ mod tests {
         assert_eq!(date, NaiveDate::from_ymd_opt(2025, 9, 7));
     }
 
+    #[test]
+    fn test_parse_org_date_header_org_timestamps() {
+        let expected = NaiveDate::from_ymd_opt(2025, 9, 7);
+        assert_eq!(parse_org_date_header("<2025-09-07 Sun>"), expected);
+        assert_eq!(parse_org_date_header("[2025-09-07 Sun]"), expected);
+        assert_eq!(parse_org_date_header("<2025-09-07 Sun 14:30>"), expected);
+    }
+
+    #[test]
+    fn test_parse_org_date_header_iso() {
+        let expected = NaiveDate::from_ymd_opt(2025, 9, 7);
+        assert_eq!(parse_org_date_header("2025-09-07"), expected);
+        assert_eq!(parse_org_date_header("Sunday, 2025-09-07"), expected);
+        assert_eq!(parse_org_date_header("2025/09/07"), expected);
+    }
+
+    #[test]
+    fn test_parse_org_journal_headers_without_properties() {
+        let org = "* <2025-09-07 Sun>\n** 09:00 Morning\nCoffee.\n* [2025-09-08 Mon]\n** 10:15\nMeeting.\n";
+        let entries = parse_org_journal(org, None, DateFilter::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title.as_deref(), Some("Morning"));
+        assert_eq!(
+            entries[1].timestamp.date(),
+            NaiveDate::from_ymd_opt(2025, 9, 8).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_org_date_header_unrecognized() {
+        assert_eq!(parse_org_date_header("Meeting notes"), None);
+        assert_eq!(parse_org_date_header("Saturday, 31/02/2025"), None);
+    }
+
     #[test]
     fn test_parse_entry_header() {
         let (time, title) = parse_entry_header("14:30 My Title");
@@ -549,6 +2316,451 @@ mod tests {
         assert_eq!(title, None);
     }
 
+    #[test]
+    fn test_parse_markdown_note_with_frontmatter() {
+        let note =
+            "---\ntitle: Trip\ndate: 2025-09-07 14:30\ntags: [travel]\n---\n\nWe left early.";
+        let (timestamp, title, content) = parse_markdown_note(note, "trip-notes").unwrap();
+        assert_eq!(
+            timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7).and_then(|d| d.and_hms_opt(14, 30, 0))
+        );
+        assert_eq!(title, Some("Trip".to_string()));
+        assert_eq!(content, "We left early.");
+    }
+
+    #[test]
+    fn test_parse_markdown_note_daily_filename() {
+        let note = "# Good day\n\nWrote some code.";
+        let (timestamp, title, content) = parse_markdown_note(note, "2025-09-07").unwrap();
+        assert_eq!(
+            timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7).and_then(|d| d.and_hms_opt(0, 0, 0))
+        );
+        assert_eq!(title, Some("Good day".to_string()));
+        assert_eq!(content, "Wrote some code.");
+
+        let (timestamp, title, _) = parse_markdown_note("Just text", "Some Note").unwrap();
+        assert_eq!(timestamp, None);
+        assert_eq!(title, Some("Some Note".to_string()));
+    }
+
+    #[test]
+    fn test_import_from_json_round_trip() {
+        use crate::database::Database;
+        use crate::export::{ExportFilters, Exporter};
+
+        let dir = TempDir::new("json_import");
+        fs::create_dir_all(dir.join("source/attachments")).unwrap();
+        fs::write(dir.join("source/attachments/log.wav"), b"RIFF").unwrap();
+
+        let source = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = source
+            .create_entry(Some("Stardate 47634.4"), "Warp core stable.", Some("Ops"))
+            .unwrap();
+        source.set_entry_starred(id, true).unwrap();
+        source.set_entry_audio(id, "attachments/log.wav").unwrap();
+        let draft = source.create_entry(None, "Unfinished", None).unwrap();
+        source
+            .set_entry_status(draft, crate::journal::STATUS_DRAFT)
+            .unwrap();
+
+        let export_path = dir.join("source/export.json");
+        Exporter::new(&source, None)
+            .export_to_json(
+                Some(export_path.to_str().unwrap().to_string()),
+                Some(ExportFilters {
+                    include_drafts: true,
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+
+        let target = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let importer = Importer::new(&target).with_attachments(&dir.join("target/journal.db"));
+        let stats = importer
+            .import_from_json(export_path.to_str().unwrap(), None, DateFilter::default())
+            .unwrap();
+        assert_eq!((stats.total, stats.imported, stats.skipped), (2, 2, 0));
+        assert!(stats.errors.is_empty());
+
+        let original = source.get_entry(id).unwrap().unwrap();
+        let entries = target.list_entries().unwrap();
+        let imported = entries
+            .iter()
+            .find(|entry| entry.title.as_deref() == Some("Stardate 47634.4"))
+            .unwrap();
+        assert_eq!(imported.timestamp, original.timestamp);
+        assert_eq!(imported.journal, "Ops");
+        assert!(imported.starred);
+        assert_eq!(imported.audio_path.as_deref(), Some("attachments/log.wav"));
+        assert!(dir.join("target/attachments/log.wav").exists());
+        assert!(entries.iter().any(|entry| entry.is_draft()));
+
+        // Importing the same export again adds nothing
+        let stats = importer
+            .import_from_json(export_path.to_str().unwrap(), None, DateFilter::default())
+            .unwrap();
+        assert_eq!((stats.imported, stats.skipped), (0, 2));
+    }
+
+    #[test]
+    fn test_parse_jrnl() {
+        let jrnl = "[2025-09-07 09:30] Away mission @risa *\nBeamed down with Riker.\n\n\
+                    [2025-09-07 18:00] Dinner with @crew, invites to picard@enterprise.example\n\n\
+                    [2025-09-08 07:15:00 AM] Briefing\nFirst line\n\nSecond @paragraph\n";
+        let entries = parse_jrnl(jrnl, DateFilter::default());
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].title, Some("Away mission #risa".to_string()));
+        assert_eq!(entries[0].content, "Beamed down with Riker.\n\n#risa");
+        assert!(entries[0].starred);
+
+        assert_eq!(entries[1].title, None);
+        assert_eq!(
+            entries[1].content,
+            "Dinner with #crew, invites to picard@enterprise.example"
+        );
+        assert!(!entries[1].starred);
+
+        assert_eq!(
+            entries[2].timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 8)
+                .and_then(|d| d.and_hms_opt(7, 15, 0))
+                .unwrap()
+        );
+        assert_eq!(entries[2].content, "First line\n\nSecond #paragraph");
+
+        let filtered = parse_jrnl(
+            jrnl,
+            DateFilter {
+                since: NaiveDate::from_ymd_opt(2025, 9, 8),
+                ..Default::default()
+            },
+        );
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_journey_entry() {
+        let json = r#"{
+            "text": "<p>Shore leave on <b>Risa</b></p>",
+            "type": "html",
+            "date_journal": 1757235000000,
+            "timezone": "Europe/Rome",
+            "favourite": true,
+            "tags": ["vacation"],
+            "photos": ["1757235000000-beach.jpg"]
+        }"#;
+        let (entry, photos) = parse_journey_entry(json, DateFilter::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            entry.timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+                .and_then(|d| d.and_hms_opt(8, 50, 0))
+                .unwrap()
+        );
+        assert_eq!(entry.content, "Shore leave on **Risa**\n\n#vacation");
+        assert!(entry.starred);
+        assert_eq!(photos, vec!["1757235000000-beach.jpg"]);
+
+        assert!(
+            parse_journey_entry(r#"{"text": "", "date_journal": 0}"#, DateFilter::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_html_to_markdown() {
+        let html = "<p>Hello <b>world</b> &amp; <a href=\"https://x.example\">link</a></p>\
+                    <ul><li>One</li><li>Two</li></ul><h2>Title</h2><p>End</p>";
+        assert_eq!(
+            html_to_markdown(html),
+            "Hello **world** & [link](https://x.example)\n\n- One\n- Two\n\n## Title\n\nEnd"
+        );
+    }
+
+    #[test]
+    fn test_parse_diaro_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<data version="2">
+<table name="diaro_folders"><r><uid>f1</uid><title>Away Missions</title><color>#F44336</color></r></table>
+<table name="diaro_tags"><r><uid>t1</uid><title>risa</title></r></table>
+<table name="diaro_entries">
+<r><uid>e1</uid><date>1757235000000</date><tz_offset>+02:00</tz_offset><title>Shore leave</title><text>Sun &amp; sand</text><folder_uid>f1</folder_uid><tags>,t1,</tags></r>
+<r><uid>e2</uid><date>not a date</date><text>Broken</text></r>
+</table>
+<table name="diaro_attachments"><r><uid>a1</uid><entry_uid>e1</entry_uid><type>photo</type><filename>photo_1.jpg</filename></r></table>
+</data>"#;
+        let (entries, errors) = parse_diaro_xml(xml, DateFilter::default());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(errors.len(), 1);
+
+        let entry = &entries[0];
+        assert_eq!(
+            entry.entry.timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+                .and_then(|d| d.and_hms_opt(8, 50, 0))
+                .unwrap()
+        );
+        assert_eq!(entry.entry.title.as_deref(), Some("Shore leave"));
+        assert_eq!(entry.entry.content, "Sun & sand\n\n#risa");
+        assert_eq!(entry.folder.as_deref(), Some("Away Missions"));
+        assert_eq!(entry.photos, vec!["photo_1.jpg"]);
+    }
+
+    #[test]
+    fn test_parse_enex_note() {
+        let note = r#"<title>Captain&apos;s orders</title>
+<content><![CDATA[<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+<en-note><div>Make it <b>so</b></div><div><en-todo checked="true"/>Engage</div><div><en-todo/>Tea</div></en-note>]]></content>
+<created>20250907T085000Z</created><updated>20250908T090000Z</updated>
+<tag>Bridge</tag><tag>orders</tag>"#;
+        let entry = parse_enex_note(note).unwrap();
+        assert_eq!(entry.title.as_deref(), Some("Captain's orders"));
+        assert_eq!(
+            entry.timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+                .and_then(|d| d.and_hms_opt(8, 50, 0))
+                .unwrap()
+        );
+        assert_eq!(
+            entry.content,
+            "Make it **so**\n\n[x] Engage\n\n[ ] Tea\n\n#Bridge #orders"
+        );
+
+        assert!(parse_enex_note("<title>No date</title>").is_err());
+    }
+
+    #[test]
+    fn test_parse_dated_text() {
+        let text = "Notes from the Enterprise\n\
+                    === 07.09.2025 - Away mission\n\
+                    Beamed down.\n\n\
+                    Met the locals.\n\
+                    === 08.09.2025\n\
+                    Back on board.\n\
+                    === 99.99.2025\n";
+        let pattern = Regex::new(r"^=== (\d{2}\.\d{2}\.\d{4})").unwrap();
+        let (entries, errors) =
+            parse_dated_text(text, &pattern, Some("%d.%m.%Y"), DateFilter::default());
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(entries[0].title.as_deref(), Some("Away mission"));
+        assert_eq!(entries[0].content, "Beamed down.\n\nMet the locals.");
+        assert_eq!(
+            entries[1].timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 8)
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .unwrap()
+        );
+        assert_eq!(entries[1].title, None);
+        assert!(entries[1].content.ends_with("=== 99.99.2025"));
+
+        let pattern = Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2})$").unwrap();
+        let (entries, _) = parse_dated_text(
+            "2025-09-07\nFirst\n2025-09-08\nSecond",
+            &pattern,
+            None,
+            DateFilter::default(),
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].content, "Second");
+    }
+
+    #[test]
+    fn test_parse_dayone_json_tags() {
+        let json = r#"{
+            "metadata": {"version": "1.0"},
+            "entries": [{
+                "uuid": "A1",
+                "creationDate": "2025-09-07T08:50:00Z",
+                "text": "Shore leave #risa",
+                "tags": ["risa", "Shore Leave"]
+            }]
+        }"#;
+        let entries = parse_dayone_json(json, DateFilter::default()).unwrap();
+        assert_eq!(entries[0].0.content, "Shore leave #risa\n\n#Shore-Leave");
+        assert_eq!(
+            file_journal_name(Path::new("export/Travel.json")),
+            Some("Travel".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_dayone_json_timezone() {
+        // 23:30 UTC is already the next day in Rome
+        let json = r#"{
+            "metadata": {"version": "1.0"},
+            "entries": [{
+                "uuid": "A1",
+                "creationDate": "2025-09-07T23:30:00Z",
+                "timeZone": "Europe/Rome",
+                "text": "Late log"
+            }]
+        }"#;
+        let next_day = DateFilter {
+            date: NaiveDate::from_ymd_opt(2025, 9, 8),
+            ..Default::default()
+        };
+        let entries = parse_dayone_json(json, next_day).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].0.timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+                .and_then(|d| d.and_hms_opt(23, 30, 0))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rewrite_dayone_moments() {
+        let stored = HashMap::from([(
+            "A1B2".to_string(),
+            "attachments/20250907_beach.jpeg".to_string(),
+        )]);
+        assert_eq!(
+            rewrite_dayone_moments(
+                "![](dayone-moment://A1B2)\nOn the beach ![](dayone-moment://MISSING)",
+                &stored
+            ),
+            "![](attachments/20250907_beach.jpeg)\nOn the beach"
+        );
+    }
+
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        use crate::database::Database;
+
+        let dir = TempDir::new("dry_run");
+        let path = dir.join("import.txt");
+        fs::write(
+            &path,
+            "[2025-09-08 09:00] Second\nBody\n\n[2025-09-07 09:00] First\n",
+        )
+        .unwrap();
+
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let stats = Importer::new(&journal)
+            .with_dry_run(true)
+            .import_from_jrnl(path.to_str().unwrap(), None, DateFilter::default())
+            .unwrap();
+
+        assert_eq!((stats.total, stats.imported), (2, 2));
+        assert_eq!(
+            stats.samples,
+            vec!["2025-09-08 09:00  Second", "2025-09-07 09:00  First"]
+        );
+        let (first, last) = stats.date_range.unwrap();
+        assert_eq!(first.format("%Y-%m-%d").to_string(), "2025-09-07");
+        assert_eq!(last.format("%Y-%m-%d").to_string(), "2025-09-08");
+        assert!(journal.list_entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_of_dayone_zip() {
+        use crate::database::Database;
+
+        if which::which("zip").is_err() {
+            return;
+        }
+        let dir = TempDir::new("dayone_zip");
+        let journal_json = |date: &str, text: &str| {
+            format!(
+                r#"{{"metadata": {{"version": "1.0"}}, "entries": [{{"uuid": "{}", "creationDate": "{}T08:00:00Z", "text": "{}"}}]}}"#,
+                text, date, text
+            )
+        };
+        fs::write(dir.join("Travel.json"), journal_json("2025-09-01", "Risa")).unwrap();
+        fs::write(
+            dir.join("Work.json"),
+            journal_json("2025-09-07", "Briefing"),
+        )
+        .unwrap();
+        let status = std::process::Command::new("zip")
+            .current_dir(&dir)
+            .args(["-q", "export.zip", "Travel.json", "Work.json"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let stats = Importer::new(&journal)
+            .with_dry_run(true)
+            .import_from_dayone(
+                dir.join("export.zip").to_str().unwrap(),
+                None,
+                DateFilter::default(),
+                true,
+            )
+            .unwrap();
+
+        assert_eq!((stats.total, stats.imported), (2, 2));
+        assert_eq!(stats.samples.len(), 2);
+        let (first, last) = stats.date_range.unwrap();
+        assert_eq!(first.format("%Y-%m-%d").to_string(), "2025-09-01");
+        assert_eq!(last.format("%Y-%m-%d").to_string(), "2025-09-07");
+        assert!(journal.list_entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_date_filter() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 9, d).unwrap();
+        assert!(DateFilter::default().matches(day(1)));
+
+        let range = DateFilter {
+            since: Some(day(7)),
+            until: Some(day(8)),
+            ..Default::default()
+        };
+        assert!(!range.matches(day(6)));
+        assert!(range.matches(day(7)));
+        assert!(range.matches(day(8)));
+        assert!(!range.matches(day(9)));
+
+        let single = DateFilter {
+            date: Some(day(7)),
+            ..Default::default()
+        };
+        assert!(single.matches(day(7)) && !single.matches(day(8)));
+    }
+
+    #[test]
+    fn test_parse_org_journal_daily_file() {
+        let date = org_journal_file_date(Path::new("journal/20250907"));
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 9, 7));
+        assert_eq!(
+            org_journal_file_date(Path::new("2025-09-07.org")),
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+        );
+        assert_eq!(org_journal_file_date(Path::new("notes.org")), None);
+
+        // Daily files may have no date heading: the file date applies
+        let entries = parse_org_journal(
+            "** 09:30 Briefing\nAll hands.\n",
+            date,
+            DateFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].timestamp,
+            NaiveDate::from_ymd_opt(2025, 9, 7)
+                .and_then(|d| d.and_hms_opt(9, 30, 0))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_plain_relative() {
+        assert!(is_plain_relative("attachments/log.wav"));
+        assert!(!is_plain_relative("../secrets"));
+        assert!(!is_plain_relative("/etc/passwd"));
+    }
+
     #[test]
     fn test_convert_org_to_markdown() {
         let org = "*Bold* /italic/ +strikethrough+ ~code~";
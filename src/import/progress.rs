@@ -0,0 +1,107 @@
+//! Progress bar for long imports, drawn on stderr while entries are processed.
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Imports with fewer entries finish too quickly to need a progress bar
+const MIN_TOTAL: usize = 50;
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 30;
+
+#[derive(Default)]
+pub(super) struct Progress {
+    enabled: bool,
+    total: Cell<usize>,
+    done: Cell<usize>,
+    started: Cell<Option<Instant>>,
+    last_draw: Cell<Option<Instant>>,
+}
+
+impl Progress {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Iterate over `items`, advancing the bar as each one is taken. The bar is cleared
+    /// when the returned iterator is dropped.
+    pub(super) fn track<I: ExactSizeIterator>(&self, items: I) -> Tracked<'_, I> {
+        self.total.set(self.total.get() + items.len());
+        if self.started.get().is_none() {
+            self.started.set(Some(Instant::now()));
+        }
+        Tracked {
+            progress: self,
+            items,
+        }
+    }
+
+    fn advance(&self) {
+        self.done.set(self.done.get() + 1);
+        let now = Instant::now();
+        let due = self
+            .last_draw
+            .get()
+            .is_none_or(|last| now.duration_since(last) >= REDRAW_INTERVAL);
+        if due || self.done.get() == self.total.get() {
+            self.last_draw.set(Some(now));
+            self.draw(now);
+        }
+    }
+
+    fn draw(&self, now: Instant) {
+        let (done, total) = (self.done.get(), self.total.get());
+        if !self.enabled || total < MIN_TOTAL {
+            return;
+        }
+
+        let filled = BAR_WIDTH * done / total;
+        let mut line = format!(
+            "\r  Importing [{}{}] {}/{} {:>3}%",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            done,
+            total,
+            100 * done / total
+        );
+        if let Some(started) = self.started.get()
+            && done > 0
+            && done < total
+        {
+            let remaining =
+                now.duration_since(started).as_secs_f64() / done as f64 * (total - done) as f64;
+            let remaining = remaining.round() as u64;
+            line.push_str(&format!("  ETA {}:{:02}", remaining / 60, remaining % 60));
+        }
+        eprint!("{:<80}", line);
+    }
+
+    fn clear(&self) {
+        if self.enabled && self.last_draw.get().is_some() && self.total.get() >= MIN_TOTAL {
+            eprint!("\r{:80}\r", "");
+        }
+        self.last_draw.set(None);
+    }
+}
+
+pub(super) struct Tracked<'p, I> {
+    progress: &'p Progress,
+    items: I,
+}
+
+impl<I: Iterator> Iterator for Tracked<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.items.next()?;
+        self.progress.advance();
+        Some(item)
+    }
+}
+
+impl<I> Drop for Tracked<'_, I> {
+    fn drop(&mut self) {
+        self.progress.clear();
+    }
+}
@@ -0,0 +1,295 @@
+//! Watch-folder import (`cl watch`): a directory is scanned every few seconds and new or
+//! changed files are imported once they have stopped changing, so a synced notes folder
+//! feeds the journal continuously. Scanning rather than filesystem events (e.g. the
+//! `notify` crate) also works on network mounts and with sync tools that replace files
+//! wholesale, and needs no extra dependency.
+//!
+//! A changed file updates the entries it was imported into rather than adding new ones,
+//! so editing a synced daily note doesn't fill the journal with near-duplicates.
+use super::{
+    DateFilter, ImportStats, Importer, ParsedEntry, collect_markdown_files, files_with_extension,
+    org_journal_file_date, org_journal_files, parse_jrnl, parse_markdown_file, parse_org_journal,
+};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Formats `cl watch` imports, one file at a time
+const WATCH_FORMATS: &[&str] = &["markdown", "org", "jrnl"];
+
+/// What a scan last saw of a watched file
+#[derive(Debug, Default)]
+struct WatchedFile {
+    size: u64,
+    modified: Option<SystemTime>,
+    /// Unchanged since the previous scan, and handled
+    settled: bool,
+    /// Hash of the content last imported, to skip files touched but not changed
+    imported: Option<u64>,
+    /// Entries created from (or found for) the file, updated when it changes
+    entries: Vec<i64>,
+}
+
+impl Importer<'_> {
+    /// Scan `dir` every `interval` until `stop` is set, importing each new or changed file
+    /// once it is unchanged for a whole interval (files being synced or written are left
+    /// for the next scan). Files present when watching starts are imported too; entries
+    /// already in the journal are skipped as in `cl import`. A file changed since its
+    /// import updates its entries (see [`Importer::sync_file`]).
+    ///
+    /// `on_import` receives the result of each file's import.
+    #[allow(clippy::too_many_arguments)]
+    pub fn watch(
+        &self,
+        dir: &Path,
+        format: &str,
+        journal_category: Option<&str>,
+        recursive: bool,
+        interval: Duration,
+        stop: &AtomicBool,
+        mut on_import: impl FnMut(&Path, Result<ImportStats>),
+    ) -> Result<()> {
+        let format = format.to_lowercase();
+        if !dir.is_dir() {
+            return Err(anyhow!("Not a directory: {}", dir.display()));
+        }
+        if recursive && format != "markdown" {
+            return Err(anyhow!("--recursive only applies to markdown files"));
+        }
+
+        let mut watched: HashMap<PathBuf, WatchedFile> = HashMap::new();
+        while !stop.load(Ordering::Relaxed) {
+            for file in watched_files(dir, &format, recursive)? {
+                let Ok(metadata) = fs::metadata(&file) else {
+                    continue;
+                };
+                let state = watched.entry(file.clone()).or_default();
+                let modified = metadata.modified().ok();
+                if state.size != metadata.len() || state.modified != modified {
+                    state.size = metadata.len();
+                    state.modified = modified;
+                    state.settled = false;
+                    continue;
+                }
+                if state.settled {
+                    continue;
+                }
+                state.settled = true;
+
+                let Ok(content) = fs::read(&file) else {
+                    continue;
+                };
+                let mut hasher = DefaultHasher::new();
+                content.hash(&mut hasher);
+                let hash = hasher.finish();
+                if state.imported == Some(hash) {
+                    continue;
+                }
+                state.imported = Some(hash);
+
+                let result = self.sync_file(&file, &format, journal_category, &mut state.entries);
+                on_import(&file, result);
+            }
+
+            // Sleep in short steps so Ctrl-C stops the watch promptly
+            let started = Instant::now();
+            while started.elapsed() < interval && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(100).min(interval));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bring the journal in line with `file`, whose entries so far are `entries`.
+    ///
+    /// Entries already in the journal are skipped. A new or edited entry replaces the
+    /// earlier version it came from: the tracked entry with the same timestamp, or the
+    /// only one when the file holds a single entry (a daily note). Anything else is
+    /// created. `entries` is left with the entries of the current version of the file.
+    fn sync_file(
+        &self,
+        file: &Path,
+        format: &str,
+        journal_category: Option<&str>,
+        entries: &mut Vec<i64>,
+    ) -> Result<ImportStats> {
+        let parsed = parse_watched_file(file, format)?;
+        let mut earlier = Vec::new();
+        for id in entries.iter() {
+            if let Some(entry) = self.journal.get_entry(*id)? {
+                earlier.push(entry);
+            }
+        }
+        let single = parsed.len() == 1 && earlier.len() == 1;
+
+        let mut stats = ImportStats {
+            total: parsed.len(),
+            ..Default::default()
+        };
+        let mut current = Vec::new();
+        for entry in parsed {
+            let timestamp = DateTime::<Utc>::from_naive_utc_and_offset(entry.timestamp, Utc);
+            if let Some(id) = self.journal.find_entry(timestamp, &entry.content)? {
+                earlier.retain(|earlier| earlier.id != id);
+                current.push(id);
+                stats.skipped += 1;
+                continue;
+            }
+
+            let previous = earlier
+                .iter()
+                .position(|earlier| earlier.timestamp == timestamp)
+                .or(if single { Some(0) } else { None });
+            if let Some(index) = previous {
+                let previous = earlier.remove(index);
+                self.journal.update_entry_with_metadata(
+                    previous.id,
+                    entry.title.as_deref(),
+                    &entry.content,
+                    &previous.journal,
+                    timestamp,
+                )?;
+                current.push(previous.id);
+                stats.updated += 1;
+                continue;
+            }
+
+            let id = self.journal.create_entry_with_timestamp(
+                entry.title.as_deref(),
+                &entry.content,
+                journal_category,
+                entry.timestamp,
+            )?;
+            if entry.starred {
+                self.journal.set_entry_starred(id, true)?;
+            }
+            current.push(id);
+            stats.imported += 1;
+        }
+
+        *entries = current;
+        Ok(stats)
+    }
+}
+
+/// The entries of a watched `file` in `format`
+fn parse_watched_file(file: &Path, format: &str) -> Result<Vec<ParsedEntry>> {
+    if format == "markdown" {
+        return Ok(parse_markdown_file(file)?.into_iter().collect());
+    }
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    match format {
+        "org" => parse_org_journal(&content, org_journal_file_date(file), DateFilter::default()),
+        "jrnl" => Ok(parse_jrnl(&content, DateFilter::default())),
+        _ => Err(unsupported_format(format)),
+    }
+}
+
+/// The files of `dir` to watch for `format`
+fn watched_files(dir: &Path, format: &str, recursive: bool) -> Result<Vec<PathBuf>> {
+    match format {
+        "markdown" => {
+            let mut files = Vec::new();
+            collect_markdown_files(dir, recursive, &mut files)?;
+            Ok(files)
+        }
+        "org" => org_journal_files(dir),
+        "jrnl" => files_with_extension(dir, "txt"),
+        _ => Err(unsupported_format(format)),
+    }
+}
+
+fn unsupported_format(format: &str) -> anyhow::Error {
+    anyhow!(
+        "Unsupported watch format: {} (supported: {})",
+        format,
+        WATCH_FORMATS.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::journal::Journal;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_watch_imports_settled_files() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let dir = TempDir::new("watch");
+        fs::write(
+            dir.join("2025-09-07.md"),
+            "---\njournal: Ops\n---\n# Briefing\n\nAll hands.\n",
+        )
+        .unwrap();
+
+        // Stop after the scan that imports the settled file
+        let stop = AtomicBool::new(false);
+        let mut imported = Vec::new();
+        Importer::new(&journal)
+            .watch(
+                &dir,
+                "markdown",
+                None,
+                false,
+                Duration::from_millis(10),
+                &stop,
+                |file, stats| {
+                    imported.push((file.to_path_buf(), stats.unwrap().imported));
+                    stop.store(true, Ordering::Relaxed);
+                },
+            )
+            .unwrap();
+
+        assert_eq!(imported, vec![(dir.join("2025-09-07.md"), 1)]);
+        assert_eq!(journal.list_entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_watch_updates_entries_of_edited_files() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let dir = TempDir::new("watch_edit");
+        let note = dir.join("2025-09-07.md");
+        fs::write(&note, "# Briefing\n\nAll hands.\n").unwrap();
+
+        // Each scan that imports a file edits the note, until the third import
+        let stop = AtomicBool::new(false);
+        let mut results = Vec::new();
+        Importer::new(&journal)
+            .watch(
+                &dir,
+                "markdown",
+                None,
+                false,
+                Duration::from_millis(10),
+                &stop,
+                |_, stats| {
+                    let stats = stats.unwrap();
+                    results.push((stats.imported, stats.updated));
+                    let edit = format!("# Briefing\n\nAll hands, take {}.\n", results.len());
+                    fs::write(&note, edit).unwrap();
+                    stop.store(results.len() == 3, Ordering::Relaxed);
+                },
+            )
+            .unwrap();
+
+        assert_eq!(results, vec![(1, 0), (0, 1), (0, 1)]);
+        let entries = journal.list_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "All hands, take 2.");
+        assert_eq!(
+            journal.list_entry_revisions(entries[0].id).unwrap().len(),
+            2
+        );
+    }
+}
@@ -1,10 +1,27 @@
 use std::fmt;
 
+use crate::crypto::is_encrypted_text;
 use crate::database::Database;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
-use rusqlite::{Row, params};
+use regex::Regex;
+use rusqlite::{OptionalExtension, Row, params};
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+mod search;
+mod undo;
+
+pub use search::SearchQuery;
+pub use undo::UndoAction;
+
+/// Journal of entries created without one
+pub const DEFAULT_JOURNAL: &str = "Personal";
+
+/// Status of an entry still being worked on
+pub const STATUS_DRAFT: &str = "draft";
+/// Status of a finished entry (the default)
+pub const STATUS_FINAL: &str = "final";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Entry {
@@ -15,8 +32,12 @@ pub struct Entry {
     pub audio_path: Option<String>,
     pub image_paths: Vec<String>,
     pub journal: String,
+    pub status: String,
+    pub starred: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the entry was moved to the trash, if it was
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl Entry {
@@ -36,14 +57,37 @@ impl Entry {
             image_paths,
             journal: row
                 .get("journal")
-                .unwrap_or_else(|_| "Personal".to_string()),
+                .unwrap_or_else(|_| DEFAULT_JOURNAL.to_string()),
+            status: row.get("status")?,
+            starred: row.get("starred")?,
             created_at: row.get("created_at")?,
             updated_at: row.get("updated_at")?,
+            deleted_at: row.get("deleted_at")?,
         })
     }
 
+    pub fn is_draft(&self) -> bool {
+        self.status == STATUS_DRAFT
+    }
+
+    /// Whether the content is encrypted (`cl encrypt-entry`, `cl new --encrypted`)
+    pub fn is_encrypted(&self) -> bool {
+        is_encrypted_text(&self.content)
+    }
+
+    /// Paths of the attached images followed by the audio recording, if any
+    pub fn attachment_paths(&self) -> Vec<&str> {
+        self.image_paths
+            .iter()
+            .map(String::as_str)
+            .chain(self.audio_path.as_deref())
+            .collect()
+    }
+
     pub fn get_summary(&self, summary_size: usize) -> String {
-        let content_preview = if self.content.len() > summary_size {
+        let content_preview = if self.is_encrypted() {
+            "🔒 encrypted".to_string()
+        } else if self.content.len() > summary_size {
             format!("{}...", &self.content[..summary_size])
         } else {
             self.content.to_string()
@@ -84,6 +128,148 @@ impl fmt::Display for Entry {
     }
 }
 
+/// An earlier version of an entry, as listed by `cl history`
+#[derive(Debug)]
+pub struct EntryRevision {
+    /// Position among the entry's revisions, starting at 1 for the oldest
+    pub number: usize,
+    pub title: Option<String>,
+    pub content: String,
+    /// When this version was replaced by an edit
+    pub revised_at: DateTime<Utc>,
+}
+
+/// Usage of a tag across entries, as reported by `cl tags`
+#[derive(Debug)]
+pub struct TagUsage {
+    pub name: String,
+    pub count: usize,
+    /// Timestamp of the most recent entry with the tag
+    pub last_used: DateTime<Utc>,
+}
+
+/// A journal category and its entries, as reported by `cl journals`
+#[derive(Debug)]
+pub struct JournalUsage {
+    pub name: String,
+    pub count: usize,
+    pub first_entry: DateTime<Utc>,
+    pub last_entry: DateTime<Utc>,
+}
+
+/// Optional settings of a journal, from the `journals` table
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct JournalInfo {
+    pub name: String,
+    pub description: Option<String>,
+    /// Color name used to render the journal name (e.g. "blue", "bright magenta")
+    pub color: Option<String>,
+    /// Template opened by `cl new` for this journal
+    pub default_template: Option<String>,
+    /// Hidden from default list, search and calendar output
+    pub archived: bool,
+}
+
+/// Writing statistics, as reported by `cl stats`
+#[derive(Debug, Default)]
+pub struct JournalStats {
+    pub total_entries: usize,
+    /// Journal name and entry count, most used first
+    pub per_journal: Vec<(String, usize)>,
+    /// Month (YYYY-MM) and entry count, oldest first
+    pub per_month: Vec<(String, usize)>,
+    pub first_entry: Option<DateTime<Utc>>,
+    pub last_entry: Option<DateTime<Utc>>,
+    pub total_words: usize,
+    /// ID and word count of the longest entry
+    pub longest_entry: Option<(i64, usize)>,
+}
+
+impl JournalStats {
+    pub fn average_words(&self) -> f64 {
+        if self.total_entries == 0 {
+            0.0
+        } else {
+            self.total_words as f64 / self.total_entries as f64
+        }
+    }
+}
+
+/// Consecutive-day writing streaks, as reported by `cl streak`
+#[derive(Debug, Default, PartialEq)]
+pub struct Streak {
+    /// Days in the run ending today, or yesterday (today may still get an entry)
+    pub current: usize,
+    pub longest: usize,
+    /// Days since the most recent entry date, `None` without entries
+    pub days_since_last: Option<i64>,
+}
+
+impl Streak {
+    /// Compute streaks from distinct entry dates sorted in ascending order
+    pub fn from_dates(dates: &[NaiveDate], today: NaiveDate) -> Self {
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for &date in dates {
+            run = match previous {
+                Some(prev) if (date - prev).num_days() == 1 => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            previous = Some(date);
+        }
+
+        let days_since_last = previous.map(|last| (today - last).num_days());
+        let current = match days_since_last {
+            Some(0 | 1) => run,
+            _ => 0,
+        };
+
+        Streak {
+            current,
+            longest,
+            days_since_last,
+        }
+    }
+}
+
+/// Conditions for listing entries. Unset fields don't filter anything.
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter<'a> {
+    /// Entries dated exactly on this day
+    pub date: Option<NaiveDate>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    /// Entries edited on or after this day, whatever their entry date
+    pub updated_since: Option<NaiveDate>,
+    pub journal: Option<&'a str>,
+    /// `draft` or `final`
+    pub status: Option<&'a str>,
+    /// Substring of the title or content
+    pub search: Option<&'a str>,
+    pub tag: Option<&'a str>,
+    /// Skip the entries of archived journals
+    pub exclude_archived: bool,
+    /// Only starred entries
+    pub starred: bool,
+}
+
+impl EntryFilter<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.date.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.updated_since.is_none()
+            && self.journal.is_none()
+            && self.status.is_none()
+            && self.search.is_none()
+            && self.tag.is_none()
+            && !self.exclude_archived
+            && !self.starred
+    }
+}
+
 pub struct Journal {
     db: Database,
 }
@@ -101,7 +287,7 @@ impl Journal {
     ) -> Result<i64> {
         let conn = self.db.connection();
         let now = Utc::now();
-        let journal_name = journal.unwrap_or("Personal");
+        let journal_name = journal.unwrap_or(DEFAULT_JOURNAL);
 
         conn.execute(
             "INSERT INTO entries (timestamp, title, content, journal, created_at, updated_at)
@@ -109,7 +295,10 @@ impl Journal {
             params![now, title, content, journal_name, now, now],
         )?;
 
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        self.set_entry_tags(id, &extract_tags(content))?;
+
+        Ok(id)
     }
 
     pub fn create_entry_with_timestamp(
@@ -121,7 +310,7 @@ impl Journal {
     ) -> Result<i64> {
         let conn = self.db.connection();
         let now = Utc::now();
-        let journal_name = journal.unwrap_or("Personal");
+        let journal_name = journal.unwrap_or(DEFAULT_JOURNAL);
 
         // Convert NaiveDateTime to DateTime<Utc>
         let timestamp_utc = DateTime::<Utc>::from_naive_utc_and_offset(timestamp, Utc);
@@ -132,7 +321,10 @@ impl Journal {
             params![timestamp_utc, title, content, journal_name, now, now],
         )?;
 
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        self.set_entry_tags(id, &extract_tags(content))?;
+
+        Ok(id)
     }
 
     pub fn create_entry_with_audio(
@@ -144,7 +336,7 @@ impl Journal {
     ) -> Result<i64> {
         let conn = self.db.connection();
         let now = Utc::now();
-        let journal_name = journal.unwrap_or("Personal");
+        let journal_name = journal.unwrap_or(DEFAULT_JOURNAL);
 
         conn.execute(
             "INSERT INTO entries (timestamp, title, content, journal, audio_path, created_at, updated_at)
@@ -152,7 +344,10 @@ impl Journal {
             params![now, title, content, journal_name, audio_path, now, now],
         )?;
 
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        self.set_entry_tags(id, &extract_tags(content))?;
+
+        Ok(id)
     }
 
     pub fn get_entry(&self, id: i64) -> Result<Option<Entry>> {
@@ -160,8 +355,8 @@ impl Journal {
 
         let mut stmt = conn.prepare(
             "SELECT id, timestamp, title, content, audio_path, image_paths,
-                    journal, created_at, updated_at
-             FROM entries WHERE id = ?1",
+                    journal, status, starred, created_at, updated_at, deleted_at
+             FROM entries WHERE id = ?1 AND deleted_at IS NULL",
         )?;
 
         let mut entry_iter = stmt.query_map([id], Entry::from_row)?;
@@ -173,6 +368,27 @@ impl Journal {
         Ok(None)
     }
 
+    /// Get the most recent entry, optionally restricted to a journal.
+    /// Ties on timestamp are broken by the highest ID.
+    pub fn get_last_entry(&self, journal: Option<&str>) -> Result<Option<Entry>> {
+        let conn = self.db.connection();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, title, content, audio_path, image_paths,
+                    journal, status, starred, created_at, updated_at, deleted_at
+             FROM entries WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL
+             ORDER BY timestamp DESC, id DESC LIMIT 1",
+        )?;
+
+        let mut entry_iter = stmt.query_map([journal], Entry::from_row)?;
+
+        if let Some(entry) = entry_iter.next() {
+            return Ok(Some(entry?));
+        }
+
+        Ok(None)
+    }
+
     pub fn list_entries(&self) -> Result<Vec<Entry>> {
         self.list_entries_with_order("timestamp", "DESC")
     }
@@ -186,8 +402,8 @@ impl Journal {
 
         let query = format!(
             "SELECT id, timestamp, title, content, audio_path, image_paths,
-                    journal, created_at, updated_at
-             FROM entries ORDER BY {} {}",
+                    journal, status, starred, created_at, updated_at, deleted_at
+             FROM entries WHERE deleted_at IS NULL ORDER BY {} {}",
             order_field, order_direction
         );
 
@@ -202,36 +418,151 @@ impl Journal {
         Ok(entries)
     }
 
-    pub fn search_entries(&self, query: &str) -> Result<Vec<Entry>> {
+    /// Search entries by substring among those matching `filter`, newest first
+    /// according to `order_field` (`timestamp` or `created_at`).
+    pub fn search_entries_filtered(
+        &self,
+        query: &str,
+        filter: EntryFilter,
+        order_field: &str,
+    ) -> Result<Vec<Entry>> {
+        let filter = EntryFilter {
+            search: Some(query),
+            ..filter
+        };
+        self.list_entries_filtered_with_order(&filter, order_field, "DESC")
+    }
+
+    /// Search the entries matching `filter` by fuzzy word similarity rather than exact
+    /// substring matching. Returns at most `limit` entries scoring at least `threshold`
+    /// (0.0 to 1.0), best matches first.
+    pub fn fuzzy_search_entries(
+        &self,
+        query: &str,
+        filter: &EntryFilter,
+        threshold: f64,
+        limit: usize,
+    ) -> Result<Vec<(Entry, f64)>> {
+        let query_words = tokenize(query);
+        if query_words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut matches: Vec<(Entry, f64)> = Vec::new();
+        self.for_each_entry(filter, "timestamp", "DESC", |entry| {
+            let score = fuzzy_score(&query_words, &entry);
+            if score >= threshold {
+                matches.push((entry, score));
+            }
+            Ok(())
+        })?;
+
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches.truncate(limit);
+
+        Ok(matches)
+    }
+
+    /// Check whether an entry with the same timestamp and content already exists.
+    /// Used by importers to avoid creating duplicates when re-importing the same source.
+    pub fn entry_exists(&self, timestamp: DateTime<Utc>, content: &str) -> Result<bool> {
         let conn = self.db.connection();
-        let search_pattern = format!("%{}%", query);
 
-        let mut stmt = conn.prepare(
-            "SELECT id, timestamp, title, content, audio_path, image_paths,
-                    journal, created_at, updated_at
-             FROM entries
-             WHERE content LIKE ?1 OR title LIKE ?1
-             ORDER BY created_at DESC",
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM entries WHERE timestamp = ?1 AND content = ?2)",
+            params![timestamp, content],
+            |row| row.get(0),
         )?;
 
-        let entry_iter = stmt.query_map([&search_pattern], Entry::from_row)?;
+        Ok(exists)
+    }
 
-        let mut entries = Vec::new();
-        for entry in entry_iter {
-            entries.push(entry?);
+    /// ID of the entry (not in the trash) with this timestamp and content, if any
+    pub fn find_entry(&self, timestamp: DateTime<Utc>, content: &str) -> Result<Option<i64>> {
+        let conn = self.db.connection();
+        let id = conn
+            .query_row(
+                "SELECT id FROM entries
+                 WHERE timestamp = ?1 AND content = ?2 AND deleted_at IS NULL
+                 ORDER BY id LIMIT 1",
+                params![timestamp, content],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
+
+    /// Move an entry to the trash. It disappears from every listing but can be
+    /// restored with `restore_entry` until the trash is emptied.
+    pub fn delete_entry(&self, id: i64) -> Result<bool> {
+        let conn = self.db.connection();
+
+        let rows_affected = conn.execute(
+            "UPDATE entries SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![Utc::now(), id],
+        )?;
+        if rows_affected > 0 {
+            self.record_undo(&UndoAction::Delete { ids: vec![id] })?;
         }
 
-        Ok(entries)
+        Ok(rows_affected > 0)
     }
 
-    pub fn delete_entry(&self, id: i64) -> Result<bool> {
+    /// Take an entry out of the trash. Returns false if it is not in the trash.
+    pub fn restore_entry(&self, id: i64) -> Result<bool> {
         let conn = self.db.connection();
 
-        let rows_affected = conn.execute("DELETE FROM entries WHERE id = ?1", [id])?;
+        let rows_affected = conn.execute(
+            "UPDATE entries SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )?;
 
         Ok(rows_affected > 0)
     }
 
+    /// Entries in the trash, most recently deleted first
+    pub fn list_trashed_entries(&self) -> Result<Vec<Entry>> {
+        let conn = self.db.connection();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, title, content, audio_path, image_paths,
+                    journal, status, starred, created_at, updated_at, deleted_at
+             FROM entries WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC, id DESC",
+        )?;
+        let entries = stmt
+            .query_map([], Entry::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Permanently delete the entries in the trash, only those deleted before
+    /// `deleted_before` if given. Returns how many were deleted.
+    pub fn empty_trash(&self, deleted_before: Option<DateTime<Utc>>) -> Result<usize> {
+        let conn = self.db.connection();
+        let tx = conn.unchecked_transaction()?;
+
+        for table in ["entry_tags", "entry_revisions"] {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {} WHERE entry_id IN (
+                         SELECT id FROM entries
+                         WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1))",
+                    table
+                ),
+                [deleted_before],
+            )?;
+        }
+        let deleted = tx.execute(
+            "DELETE FROM entries WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1)",
+            [deleted_before],
+        )?;
+
+        tx.commit()?;
+        Ok(deleted)
+    }
+
     /// Update entry's title and content. Returns true if the entry was found and updated.
     ///
     /// Note: This is currenltly replaced by `update_entry_with_metadata` which also updates journal and timestamp.
@@ -247,14 +578,58 @@ impl Journal {
         let conn = self.db.connection();
         let now = Utc::now();
 
+        if let Some(entry) = self.get_entry(id)? {
+            self.record_edit(&entry, title, content, &entry.journal, entry.timestamp)?;
+        }
+        self.save_revision(id, title, content)?;
         let rows_affected = conn.execute(
-            "UPDATE entries SET title = ?1, content = ?2, updated_at = ?3 WHERE id = ?4",
+            "UPDATE entries SET title = ?1, content = ?2, updated_at = ?3
+             WHERE id = ?4 AND deleted_at IS NULL",
             params![title, content, now, id],
         )?;
+        if rows_affected > 0 {
+            self.set_entry_tags(id, &extract_tags(content))?;
+        }
 
         Ok(rows_affected > 0)
     }
 
+    /// Replace an entry's content with its encrypted version, removing everything that
+    /// would reveal the plaintext: earlier revisions, tags and the undo record.
+    /// Returns true if the entry was found and updated.
+    pub fn encrypt_entry(&self, id: i64, encrypted_content: &str) -> Result<bool> {
+        let conn = self.db.connection();
+        // All or nothing: a partial update would leave plaintext next to the ciphertext
+        let tx = conn.unchecked_transaction()?;
+        let rows_affected = tx.execute(
+            "UPDATE entries SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            params![encrypted_content, Utc::now(), id],
+        )?;
+        if rows_affected > 0 {
+            tx.execute("DELETE FROM entry_revisions WHERE entry_id = ?1", [id])?;
+            tx.execute("DELETE FROM undo_log", [])?;
+            tx.execute("DELETE FROM entry_tags WHERE entry_id = ?1", [id])?;
+        }
+        tx.commit()?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Append `text` as a new paragraph at the end of an entry's content.
+    /// Returns true if the entry was found and updated.
+    pub fn append_to_entry(&self, id: i64, text: &str) -> Result<bool> {
+        let Some(entry) = self.get_entry(id)? else {
+            return Ok(false);
+        };
+
+        let content = if entry.content.trim().is_empty() {
+            text.to_string()
+        } else {
+            format!("{}\n\n{}", entry.content.trim_end(), text)
+        };
+        self.update_entry(id, entry.title.as_deref(), &content)
+    }
+
     /// Update entry's title, content, journal, and timestamp. Returns true if the entry was found and updated.
     ///
     /// # Arguments
@@ -277,85 +652,497 @@ impl Journal {
         let conn = self.db.connection();
         let now = Utc::now();
 
+        if let Some(entry) = self.get_entry(id)? {
+            self.record_edit(&entry, title, content, journal, timestamp)?;
+        }
+        self.save_revision(id, title, content)?;
         let rows_affected = conn.execute(
-            "UPDATE entries SET title = ?1, content = ?2, journal = ?3, timestamp = ?4, updated_at = ?5 WHERE id = ?6",
+            "UPDATE entries SET title = ?1, content = ?2, journal = ?3, timestamp = ?4, updated_at = ?5
+             WHERE id = ?6 AND deleted_at IS NULL",
             params![title, content, journal, timestamp, now, id],
         )?;
+        if rows_affected > 0 {
+            self.set_entry_tags(id, &extract_tags(content))?;
+        }
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Keep the current title and content of an entry as a revision, unless the
+    /// update about to be made leaves them unchanged
+    fn save_revision(&self, id: i64, title: Option<&str>, content: &str) -> Result<()> {
+        let conn = self.db.connection();
+        conn.execute(
+            "INSERT INTO entry_revisions (entry_id, title, content, revised_at)
+             SELECT id, title, content, ?4 FROM entries
+             WHERE id = ?1 AND deleted_at IS NULL AND (title IS NOT ?2 OR content IS NOT ?3)",
+            params![id, title, content, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    /// Earlier versions of an entry, oldest first
+    pub fn list_entry_revisions(&self, id: i64) -> Result<Vec<EntryRevision>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT title, content, revised_at FROM entry_revisions
+             WHERE entry_id = ?1 ORDER BY id ASC",
+        )?;
+        let revisions = stmt
+            .query_map([id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (title, content, revised_at))| EntryRevision {
+                number: i + 1,
+                title,
+                content,
+                revised_at,
+            })
+            .collect();
+        Ok(revisions)
+    }
+
+    /// Set an entry's status (`draft` or `final`). Returns true if the entry was found.
+    pub fn set_entry_status(&self, id: i64, status: &str) -> Result<bool> {
+        let conn = self.db.connection();
+        let now = Utc::now();
+
+        let rows_affected = conn.execute(
+            "UPDATE entries SET status = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL",
+            params![status, now, id],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Set the audio recording of an entry. Returns true if the entry was found.
+    pub fn set_entry_audio(&self, id: i64, audio_path: &str) -> Result<bool> {
+        let conn = self.db.connection();
+        let rows_affected = conn.execute(
+            "UPDATE entries SET audio_path = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL",
+            params![audio_path, Utc::now(), id],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Add attached image paths to an entry. Returns true if the entry was found.
+    pub fn add_entry_images(&self, id: i64, paths: &[String]) -> Result<bool> {
+        let Some(entry) = self.get_entry(id)? else {
+            return Ok(false);
+        };
+        let mut image_paths = entry.image_paths;
+        image_paths.extend(paths.iter().cloned());
+
+        let conn = self.db.connection();
+        conn.execute(
+            "UPDATE entries SET image_paths = ?1, updated_at = ?2 WHERE id = ?3",
+            params![serde_json::to_string(&image_paths)?, Utc::now(), id],
+        )?;
+
+        Ok(true)
+    }
+
+    /// Every attachment path referenced by an entry (images and audio), paired with
+    /// the entry ID. Entries in the trash count, as they can still be restored.
+    pub fn list_attachment_references(&self) -> Result<Vec<(i64, String)>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT id, audio_path, image_paths FROM entries
+             WHERE audio_path IS NOT NULL OR image_paths IS NOT NULL",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut references = Vec::new();
+        for (id, audio_path, image_paths) in rows {
+            references.extend(audio_path.map(|path| (id, path)));
+            let images: Vec<String> = image_paths
+                .map(|json| serde_json::from_str(&json).unwrap_or_default())
+                .unwrap_or_default();
+            references.extend(images.into_iter().map(|path| (id, path)));
+        }
+
+        Ok(references)
+    }
+
+    /// Star or unstar an entry. Starring is not an edit, so `updated_at` is left alone.
+    pub fn set_entry_starred(&self, id: i64, starred: bool) -> Result<bool> {
+        let conn = self.db.connection();
+        let rows_affected = conn.execute(
+            "UPDATE entries SET starred = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![starred, id],
+        )?;
 
         Ok(rows_affected > 0)
     }
 
+    /// Replace the tags of an entry. Tags are normalized (lowercase, no leading `#`).
+    pub fn set_entry_tags(&self, id: i64, tags: &[String]) -> Result<()> {
+        let conn = self.db.connection();
+
+        conn.execute("DELETE FROM entry_tags WHERE entry_id = ?1", [id])?;
+        for tag in tags {
+            let tag = normalize_tag(tag);
+            if tag.is_empty() {
+                continue;
+            }
+            conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [&tag])?;
+            conn.execute(
+                "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+                params![id, tag],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Tags of an entry, sorted alphabetically
+    pub fn get_entry_tags(&self, id: i64) -> Result<Vec<String>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT tags.name FROM tags
+             JOIN entry_tags ON entry_tags.tag_id = tags.id
+             WHERE entry_tags.entry_id = ?1
+             ORDER BY tags.name ASC",
+        )?;
+        let tags = stmt
+            .query_map([id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Every tag with its number of entries and last use, most used first,
+    /// optionally counting only the entries of a journal
+    pub fn list_tag_usage(&self, journal: Option<&str>) -> Result<Vec<TagUsage>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT tags.name, COUNT(*), MAX(entries.timestamp) FROM tags
+             JOIN entry_tags ON entry_tags.tag_id = tags.id
+             JOIN entries ON entries.id = entry_tags.entry_id
+             WHERE (?1 IS NULL OR entries.journal = ?1) AND entries.deleted_at IS NULL
+             GROUP BY tags.name
+             ORDER BY COUNT(*) DESC, tags.name ASC",
+        )?;
+        let usage = stmt
+            .query_map([journal], |row| {
+                Ok(TagUsage {
+                    name: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as usize,
+                    last_used: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(usage)
+    }
+
+    /// Writing statistics, optionally restricted to a journal. Counts and dates are
+    /// aggregated in SQL; words are counted while streaming only the content column.
+    pub fn stats(&self, journal: Option<&str>) -> Result<JournalStats> {
+        let conn = self.db.connection();
+        let mut stats = JournalStats::default();
+
+        let (total, first, last): (i64, Option<DateTime<Utc>>, Option<DateTime<Utc>>) = conn
+            .query_row(
+                "SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM entries
+                 WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL",
+                [journal],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+        stats.total_entries = total as usize;
+        stats.first_entry = first;
+        stats.last_entry = last;
+
+        let mut stmt = conn.prepare(
+            "SELECT journal, COUNT(*) FROM entries
+             WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL
+             GROUP BY journal ORDER BY COUNT(*) DESC, journal ASC",
+        )?;
+        stats.per_journal = stmt
+            .query_map([journal], |row| {
+                Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT strftime('%Y-%m', timestamp) AS month, COUNT(*) FROM entries
+             WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL
+             GROUP BY month ORDER BY month ASC",
+        )?;
+        stats.per_month = stmt
+            .query_map([journal], |row| {
+                Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, content FROM entries
+             WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL",
+        )?;
+        let mut rows = stmt.query([journal])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let words = row.get_ref(1)?.as_str()?.split_whitespace().count();
+            stats.total_words += words;
+            if stats
+                .longest_entry
+                .is_none_or(|(_, longest)| words > longest)
+            {
+                stats.longest_entry = Some((id, words));
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Timestamps of all entries, oldest first. Callers bucket them into days of the
+    /// display timezone, which SQLite's `DATE()` (always UTC) cannot do.
+    pub fn list_entry_timestamps(
+        &self,
+        journal: Option<&str>,
+        exclude_archived: bool,
+    ) -> Result<Vec<DateTime<Utc>>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp FROM entries
+             WHERE (?1 IS NULL OR journal = ?1) AND deleted_at IS NULL
+               AND NOT (?2 AND journal IN (SELECT name FROM journals WHERE archived = 1))
+             ORDER BY timestamp ASC",
+        )?;
+        let timestamps = stmt
+            .query_map(params![journal, exclude_archived], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(timestamps)
+    }
+
     pub fn list_journals(&self) -> Result<Vec<String>> {
         let conn = self.db.connection();
-        let mut stmt = conn.prepare("SELECT DISTINCT journal FROM entries ORDER BY journal ASC")?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT journal FROM entries WHERE deleted_at IS NULL ORDER BY journal ASC",
+        )?;
         let journals = stmt
             .query_map([], |row| row.get(0))?
             .collect::<Result<Vec<String>, _>>()?;
         Ok(journals)
     }
 
+    /// Every journal with its entry count and first/last entry timestamps, by name
+    pub fn list_journal_usage(&self) -> Result<Vec<JournalUsage>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT journal, COUNT(*), MIN(timestamp), MAX(timestamp) FROM entries
+             WHERE deleted_at IS NULL GROUP BY journal ORDER BY journal ASC",
+        )?;
+        let usage = stmt
+            .query_map([], |row| {
+                Ok(JournalUsage {
+                    name: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as usize,
+                    first_entry: row.get(2)?,
+                    last_entry: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(usage)
+    }
+
+    /// Settings of the named journal, if any were set
+    pub fn get_journal_info(&self, name: &str) -> Result<Option<JournalInfo>> {
+        Ok(self
+            .list_journal_info()?
+            .into_iter()
+            .find(|info| info.name == name))
+    }
+
+    /// Settings of every journal that has some, by name
+    pub fn list_journal_info(&self) -> Result<Vec<JournalInfo>> {
+        let conn = self.db.connection();
+        let mut stmt = conn.prepare(
+            "SELECT name, description, color, default_template, archived FROM journals
+             ORDER BY name ASC",
+        )?;
+        let info = stmt
+            .query_map([], |row| {
+                Ok(JournalInfo {
+                    name: row.get(0)?,
+                    description: row.get(1)?,
+                    color: row.get(2)?,
+                    default_template: row.get(3)?,
+                    archived: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(info)
+    }
+
+    /// Store the settings of `info.name`, replacing any previous ones
+    pub fn set_journal_info(&self, info: &JournalInfo) -> Result<()> {
+        let conn = self.db.connection();
+        conn.execute(
+            "INSERT OR REPLACE INTO journals (name, description, color, default_template, archived)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                info.name,
+                info.description,
+                info.color,
+                info.default_template,
+                info.archived
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Entries of journal `name`: those listed, and those in the trash
+    pub fn count_journal_entries(&self, name: &str) -> Result<(usize, usize)> {
+        let conn = self.db.connection();
+        let (live, trashed): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*) FILTER (WHERE deleted_at IS NULL),
+                    COUNT(*) FILTER (WHERE deleted_at IS NOT NULL)
+             FROM entries WHERE journal = ?1",
+            [name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok((live as usize, trashed as usize))
+    }
+
+    /// Remove a journal by moving its entries to `move_to`, or deleting them (with their
+    /// tags) when `None`, in a single transaction. Returns the number of entries affected.
+    pub fn delete_journal(&self, name: &str, move_to: Option<&str>) -> Result<usize> {
+        let conn = self.db.connection();
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute("DELETE FROM journals WHERE name = ?1", [name])?;
+        let affected = match move_to {
+            Some(target) => tx.execute(
+                "UPDATE entries SET journal = ?1, updated_at = ?2 WHERE journal = ?3",
+                params![target, Utc::now(), name],
+            )?,
+            None => {
+                for table in ["entry_tags", "entry_revisions"] {
+                    tx.execute(
+                        &format!(
+                            "DELETE FROM {} WHERE entry_id IN (SELECT id FROM entries WHERE journal = ?1)",
+                            table
+                        ),
+                        [name],
+                    )?;
+                }
+                tx.execute("DELETE FROM entries WHERE journal = ?1", [name])?
+            }
+        };
+
+        tx.commit()?;
+        Ok(affected)
+    }
+
     pub fn move_entry(&self, id: i64, new_journal: &str) -> Result<bool> {
         let conn = self.db.connection();
         let now = Utc::now();
 
+        if let Some(entry) = self.get_entry(id)? {
+            self.record_undo(&UndoAction::Move {
+                moves: vec![(id, entry.journal)],
+            })?;
+        }
+
         let rows_affected = conn.execute(
-            "UPDATE entries SET journal = ?1, updated_at = ?2 WHERE id = ?3",
+            "UPDATE entries SET journal = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL",
             params![new_journal, now, id],
         )?;
 
         Ok(rows_affected > 0)
     }
 
-    pub fn list_entries_filtered(
-        &self,
-        date: Option<&NaiveDate>,
-        since: Option<&NaiveDate>,
-        until: Option<&NaiveDate>,
-        journal: Option<&str>,
-    ) -> Result<Vec<Entry>> {
-        self.list_entries_filtered_with_order(date, since, until, journal, "timestamp", "DESC")
+    /// Move the entries matching `filter` to journal `to` in one transaction,
+    /// returning how many moved. Entries already in `to` are left alone.
+    pub fn move_entries(&self, filter: &EntryFilter, to: &str) -> Result<usize> {
+        let conn = self.db.connection();
+        let (where_clause, filter_params) = filter_where_clause(filter)?;
+        let mut param_refs: Vec<&dyn rusqlite::ToSql> =
+            filter_params.iter().map(|p| p.as_ref()).collect();
+        param_refs.push(&to);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, journal FROM entries{} AND journal IS NOT ?",
+            where_clause
+        ))?;
+        let moves = stmt
+            .query_map(param_refs.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, String)>, _>>()?;
+        if moves.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now();
+        for (id, _) in &moves {
+            tx.execute(
+                "UPDATE entries SET journal = ?1, updated_at = ?2 WHERE id = ?3",
+                params![to, now, id],
+            )?;
+        }
+        self.record_undo(&UndoAction::Move {
+            moves: moves.clone(),
+        })?;
+        tx.commit()?;
+
+        Ok(moves.len())
     }
 
     pub fn list_entries_filtered_with_order(
         &self,
-        date: Option<&NaiveDate>,
-        since: Option<&NaiveDate>,
-        until: Option<&NaiveDate>,
-        journal: Option<&str>,
+        filter: &EntryFilter,
         order_field: &str,
         order_direction: &str,
     ) -> Result<Vec<Entry>> {
-        let conn = self.db.connection();
-        let mut query = "SELECT id, timestamp, title, content, audio_path, image_paths, journal, created_at, updated_at FROM entries".to_string();
-        let mut conditions = Vec::new();
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(date) = date {
-            conditions.push("DATE(timestamp) = ?");
-            params.push(Box::new(date.to_string()));
-        }
+        let mut entries = Vec::new();
+        self.for_each_entry(filter, order_field, order_direction, |entry| {
+            entries.push(entry);
+            Ok(())
+        })?;
 
-        if let Some(since_date) = since {
-            conditions.push("DATE(timestamp) >= ?");
-            params.push(Box::new(since_date.to_string()));
-        }
+        Ok(entries)
+    }
 
-        if let Some(until_date) = until {
-            conditions.push("DATE(timestamp) <= ?");
-            params.push(Box::new(until_date.to_string()));
-        }
+    /// Number of entries matching `filter`
+    pub fn count_entries(&self, filter: &EntryFilter) -> Result<usize> {
+        let conn = self.db.connection();
+        let (where_clause, params) = filter_where_clause(filter)?;
+        let query = format!("SELECT COUNT(*) FROM entries{}", where_clause);
 
-        if let Some(journal_str) = journal {
-            conditions.push("journal = ?");
-            params.push(Box::new(journal_str.to_string()));
-        }
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let count: i64 = conn.query_row(&query, param_refs.as_slice(), |row| row.get(0))?;
 
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
-        }
+        Ok(count as usize)
+    }
 
-        query.push_str(&format!(" ORDER BY {} {}", order_field, order_direction));
+    /// One page of the entries matching `filter`: at most `limit` entries (all when `None`)
+    /// after skipping the first `offset`, with the paging done by SQLite.
+    pub fn list_entries_page(
+        &self,
+        filter: &EntryFilter,
+        order_field: &str,
+        order_direction: &str,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<Entry>> {
+        let conn = self.db.connection();
+        let (where_clause, mut params) = filter_where_clause(filter)?;
+        // LIMIT -1 means no limit in SQLite, and OFFSET requires a LIMIT
+        let query = format!(
+            "SELECT id, timestamp, title, content, audio_path, image_paths, journal, status, starred, created_at, updated_at, deleted_at FROM entries{} ORDER BY {} {} LIMIT ? OFFSET ?",
+            where_clause, order_field, order_direction
+        );
+        params.push(Box::new(limit.map_or(-1, |limit| limit as i64)));
+        params.push(Box::new(offset as i64));
 
         let mut stmt = conn.prepare(&query)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
@@ -369,8 +1156,41 @@ impl Journal {
         Ok(entries)
     }
 
+    /// Stream the entries matching `filter` to `f` one at a time, in the given order,
+    /// without collecting them into memory. Iteration stops at the first error returned by `f`.
+    ///
+    /// This is a callback rather than an `Iterator` because the rusqlite statement
+    /// borrows the connection for as long as rows are being read.
+    pub fn for_each_entry<F>(
+        &self,
+        filter: &EntryFilter,
+        order_field: &str,
+        order_direction: &str,
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Entry) -> Result<()>,
+    {
+        let conn = self.db.connection();
+        let (where_clause, params) = filter_where_clause(filter)?;
+        let query = format!(
+            "SELECT id, timestamp, title, content, audio_path, image_paths, journal, status, starred, created_at, updated_at, deleted_at FROM entries{} ORDER BY {} {}",
+            where_clause, order_field, order_direction
+        );
+
+        let mut stmt = conn.prepare(&query)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut rows = stmt.query(param_refs.as_slice())?;
+
+        while let Some(row) = rows.next()? {
+            f(Entry::from_row(row)?)?;
+        }
+
+        Ok(())
+    }
+
     pub fn list_entries_for_month(&self, year: i32, month: u32) -> Result<Vec<Entry>> {
-        self.list_entries_for_month_filtered(year, month, None)
+        self.list_entries_for_month_filtered(year, month, None, false)
     }
 
     pub fn list_entries_for_month_filtered(
@@ -378,10 +1198,11 @@ impl Journal {
         year: i32,
         month: u32,
         journal: Option<&str>,
+        exclude_archived: bool,
     ) -> Result<Vec<Entry>> {
         let conn = self.db.connection();
 
-        let mut query = "SELECT id, timestamp, title, content, audio_path, image_paths, journal, created_at, updated_at FROM entries WHERE strftime('%Y', timestamp) = ?1 AND strftime('%m', timestamp) = ?2".to_string();
+        let mut query = "SELECT id, timestamp, title, content, audio_path, image_paths, journal, status, starred, created_at, updated_at, deleted_at FROM entries WHERE strftime('%Y', timestamp) = ?1 AND strftime('%m', timestamp) = ?2 AND deleted_at IS NULL".to_string();
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![
             Box::new(year.to_string()),
             Box::new(format!("{:02}", month)),
@@ -392,6 +1213,10 @@ impl Journal {
             params.push(Box::new(journal_str.to_string()));
         }
 
+        if exclude_archived {
+            query.push_str(" AND journal NOT IN (SELECT name FROM journals WHERE archived = 1)");
+        }
+
         query.push_str(" ORDER BY timestamp ASC");
 
         let mut stmt = conn.prepare(&query)?;
@@ -406,3 +1231,871 @@ impl Journal {
         Ok(entries)
     }
 }
+
+/// SQL `WHERE` clause (empty when nothing is filtered) and its parameters for `filter`
+fn filter_where_clause(filter: &EntryFilter) -> Result<(String, Vec<Box<dyn rusqlite::ToSql>>)> {
+    // Compiled up front so the condition can borrow it alongside the static ones
+    let mut search_params = Vec::new();
+    let search_sql = match filter.search {
+        Some(search) => Some(SearchQuery::parse(search)?.to_sql(&mut search_params)),
+        None => None,
+    };
+    // Entries in the trash are never listed
+    let mut conditions = vec!["deleted_at IS NULL"];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(date) = filter.date {
+        conditions.push("DATE(timestamp) = ?");
+        params.push(Box::new(date.to_string()));
+    }
+
+    if let Some(since_date) = filter.since {
+        conditions.push("DATE(timestamp) >= ?");
+        params.push(Box::new(since_date.to_string()));
+    }
+
+    if let Some(until_date) = filter.until {
+        conditions.push("DATE(timestamp) <= ?");
+        params.push(Box::new(until_date.to_string()));
+    }
+
+    // Unlike the filters above, this is about editing activity rather than the entry date
+    if let Some(updated_since) = filter.updated_since {
+        conditions.push("DATE(updated_at) >= ?");
+        params.push(Box::new(updated_since.to_string()));
+    }
+
+    if let Some(journal_str) = filter.journal {
+        conditions.push("journal = ?");
+        params.push(Box::new(journal_str.to_string()));
+    }
+
+    if let Some(status) = filter.status {
+        conditions.push("status = ?");
+        params.push(Box::new(status.to_string()));
+    }
+
+    if let Some(search_sql) = &search_sql {
+        conditions.push(search_sql.as_str());
+        for pattern in search_params {
+            params.push(Box::new(pattern));
+        }
+    }
+
+    if filter.starred {
+        conditions.push("starred = 1");
+    }
+
+    if filter.exclude_archived {
+        conditions.push("journal NOT IN (SELECT name FROM journals WHERE archived = 1)");
+    }
+
+    if let Some(tag) = filter.tag {
+        conditions.push(
+            "id IN (SELECT entry_tags.entry_id FROM entry_tags
+                    JOIN tags ON tags.id = entry_tags.tag_id WHERE tags.name = ?)",
+        );
+        params.push(Box::new(normalize_tag(tag)));
+    }
+
+    Ok((format!(" WHERE {}", conditions.join(" AND ")), params))
+}
+
+/// Split text into lowercase alphanumeric words
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Score an entry against the query words. Each query word is matched against its
+/// most similar word in the title and content (Jaro-Winkler), and the scores are averaged.
+fn fuzzy_score(query_words: &[String], entry: &Entry) -> f64 {
+    let mut words = tokenize(&entry.content);
+    if let Some(title) = &entry.title {
+        words.extend(tokenize(title));
+    }
+    if words.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = query_words
+        .iter()
+        .map(|query_word| {
+            words
+                .iter()
+                .map(|word| strsim::jaro_winkler(query_word, word))
+                .fold(0.0, f64::max)
+        })
+        .sum();
+
+    total / query_words.len() as f64
+}
+
+/// Extract `#tag` tokens from entry content, normalized and without duplicates.
+///
+/// A tag starts with a letter, so Markdown headings (`# Title`), issue numbers (`#12`)
+/// and URL fragments (`page#section`) are not tags.
+pub fn extract_tags(content: &str) -> Vec<String> {
+    static TAG_RE: OnceLock<Regex> = OnceLock::new();
+    let re = TAG_RE.get_or_init(|| {
+        Regex::new(r"(?:^|[^\w&#/])#(\p{L}[\p{L}\p{N}_-]*)").expect("valid tag regex")
+    });
+
+    let mut tags: Vec<String> = Vec::new();
+    for capture in re.captures_iter(content) {
+        let tag = normalize_tag(&capture[1]);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Lowercase a tag and strip its leading `#`
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+
+    fn entry_with(title: Option<&str>, content: &str) -> Entry {
+        Entry {
+            id: 1,
+            timestamp: Utc::now(),
+            title: title.map(str::to_string),
+            content: content.to_string(),
+            audio_path: None,
+            image_paths: Vec::new(),
+            journal: "Personal".to_string(),
+            status: STATUS_FINAL.to_string(),
+            starred: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn count_tagged(journal: &Journal, tag: &str) -> usize {
+        let filter = EntryFilter {
+            tag: Some(tag),
+            ..Default::default()
+        };
+        journal.count_entries(&filter).unwrap()
+    }
+
+    #[test]
+    fn test_fuzzy_score_tolerates_typos() {
+        let entry = entry_with(Some("Shore leave"), "Visited the botanical garden on Risa.");
+        let query = tokenize("botanicle");
+        assert!(fuzzy_score(&query, &entry) > 0.9);
+
+        let unrelated = tokenize("warp core");
+        assert!(fuzzy_score(&unrelated, &entry) < fuzzy_score(&query, &entry));
+    }
+
+    #[test]
+    fn test_fuzzy_score_empty_entry() {
+        let entry = entry_with(None, "");
+        assert_eq!(fuzzy_score(&tokenize("anything"), &entry), 0.0);
+    }
+
+    #[test]
+    fn test_fuzzy_search_applies_filter_before_limit() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        for _ in 0..3 {
+            journal
+                .create_entry(None, "Warp core inspection", Some("Work"))
+                .unwrap();
+        }
+        let personal = journal
+            .create_entry(None, "Warp core dream", Some("Personal"))
+            .unwrap();
+
+        let filter = EntryFilter {
+            journal: Some("Personal"),
+            ..Default::default()
+        };
+        let matches = journal
+            .fuzzy_search_entries("warp core", &filter, 0.5, 1)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.id, personal);
+        assert_eq!(
+            journal
+                .fuzzy_search_entries("warp core", &EntryFilter::default(), 0.5, 2)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_for_each_entry_search_combines_with_journal() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal
+            .create_entry(Some("Project Apollo"), "Kickoff", Some("Work"))
+            .unwrap();
+        journal
+            .create_entry(None, "Apollo notes at home", Some("Personal"))
+            .unwrap();
+        journal
+            .create_entry(None, "Unrelated", Some("Work"))
+            .unwrap();
+
+        let mut titles = Vec::new();
+        let filter = EntryFilter {
+            journal: Some("Work"),
+            search: Some("apollo"),
+            ..Default::default()
+        };
+        journal
+            .for_each_entry(&filter, "timestamp", "ASC", |entry| {
+                titles.push(entry.title);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(titles, vec![Some("Project Apollo".to_string())]);
+    }
+
+    #[test]
+    fn test_for_each_entry_boolean_search() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal
+            .create_entry(Some("Project X"), "Deadline moved to Friday", None)
+            .unwrap();
+        journal
+            .create_entry(None, "Project X deadline cancelled", None)
+            .unwrap();
+        journal
+            .create_entry(None, "Urgent: warp core breach", None)
+            .unwrap();
+
+        let contents = |query| {
+            let filter = EntryFilter {
+                search: Some(query),
+                ..Default::default()
+            };
+            journal
+                .list_entries_filtered_with_order(&filter, "id", "ASC")
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.content)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            contents(r#""project x" AND deadline NOT cancelled"#),
+            vec!["Deadline moved to Friday"]
+        );
+        assert_eq!(
+            contents("cancelled OR urgent"),
+            vec!["Project X deadline cancelled", "Urgent: warp core breach"]
+        );
+        assert!(
+            journal
+                .list_entries_filtered_with_order(
+                    &EntryFilter {
+                        search: Some("deadline AND"),
+                        ..Default::default()
+                    },
+                    "id",
+                    "ASC"
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_list_entries_page() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        for i in 1..=5 {
+            let journal_name = if i % 2 == 0 { "Even" } else { "Odd" };
+            journal
+                .create_entry(None, &format!("Entry {}", i), Some(journal_name))
+                .unwrap();
+        }
+
+        let all = EntryFilter::default();
+        let page = |limit, offset| {
+            journal
+                .list_entries_page(&all, "id", "ASC", limit, offset)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.content)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(journal.count_entries(&all).unwrap(), 5);
+        assert_eq!(page(Some(2), 2), vec!["Entry 3", "Entry 4"]);
+        assert_eq!(page(Some(2), 4), vec!["Entry 5"]);
+        assert_eq!(page(None, 3), vec!["Entry 4", "Entry 5"]);
+
+        let odd = EntryFilter {
+            journal: Some("Odd"),
+            ..Default::default()
+        };
+        assert_eq!(journal.count_entries(&odd).unwrap(), 3);
+        assert_eq!(
+            journal
+                .list_entries_page(&odd, "id", "ASC", Some(1), 1)
+                .unwrap()[0]
+                .content,
+            "Entry 3"
+        );
+    }
+
+    #[test]
+    fn test_append_to_entry() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal
+            .create_entry(Some("Shift log"), "Started diagnostics\n", None)
+            .unwrap();
+
+        assert!(journal.append_to_entry(id, "Found the #leak").unwrap());
+        let entry = journal.get_entry(id).unwrap().unwrap();
+        assert_eq!(entry.title.as_deref(), Some("Shift log"));
+        assert_eq!(entry.content, "Started diagnostics\n\nFound the #leak");
+        assert_eq!(journal.get_entry_tags(id).unwrap(), vec!["leak"]);
+
+        assert!(!journal.append_to_entry(id + 1, "nothing").unwrap());
+    }
+
+    #[test]
+    fn test_stats() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        assert_eq!(journal.stats(None).unwrap().total_entries, 0);
+
+        let at = |m, d| {
+            NaiveDate::from_ymd_opt(2025, m, d)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+        };
+        journal
+            .create_entry_with_timestamp(None, "one two", Some("Work"), at(1, 5))
+            .unwrap();
+        let longest = journal
+            .create_entry_with_timestamp(None, "one two three four", Some("Work"), at(1, 20))
+            .unwrap();
+        journal
+            .create_entry_with_timestamp(None, "one two three", Some("Personal"), at(3, 1))
+            .unwrap();
+
+        let stats = journal.stats(None).unwrap();
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!(
+            stats.per_journal,
+            vec![("Work".to_string(), 2), ("Personal".to_string(), 1)]
+        );
+        assert_eq!(
+            stats.per_month,
+            vec![("2025-01".to_string(), 2), ("2025-03".to_string(), 1)]
+        );
+        assert_eq!(stats.first_entry.unwrap().date_naive(), at(1, 5).date());
+        assert_eq!(stats.last_entry.unwrap().date_naive(), at(3, 1).date());
+        assert_eq!(stats.average_words(), 3.0);
+        assert_eq!(stats.longest_entry, Some((longest, 4)));
+
+        assert_eq!(journal.stats(Some("Personal")).unwrap().total_entries, 1);
+    }
+
+    #[test]
+    fn test_streak_from_dates() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 9, d).unwrap();
+        let dates = [day(1), day(2), day(3), day(6), day(8), day(9)];
+
+        assert_eq!(
+            Streak::from_dates(&dates, day(10)),
+            Streak {
+                current: 2,
+                longest: 3,
+                days_since_last: Some(1),
+            }
+        );
+        assert_eq!(Streak::from_dates(&dates, day(9)).current, 2);
+        assert_eq!(Streak::from_dates(&dates, day(12)).current, 0);
+        assert_eq!(Streak::from_dates(&[], day(12)), Streak::default());
+    }
+
+    #[test]
+    fn test_list_entry_timestamps() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let at = |d, h| {
+            NaiveDate::from_ymd_opt(2025, 9, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+        };
+        for (timestamp, name) in [
+            (at(2, 9), "Work"),
+            (at(1, 9), "Work"),
+            (at(2, 18), "Personal"),
+        ] {
+            journal
+                .create_entry_with_timestamp(None, "Log", Some(name), timestamp)
+                .unwrap();
+        }
+
+        let utc = |timestamp: chrono::NaiveDateTime| timestamp.and_utc();
+        assert_eq!(
+            journal.list_entry_timestamps(None, false).unwrap(),
+            vec![utc(at(1, 9)), utc(at(2, 9)), utc(at(2, 18))]
+        );
+        assert_eq!(
+            journal
+                .list_entry_timestamps(Some("Personal"), false)
+                .unwrap(),
+            vec![utc(at(2, 18))]
+        );
+    }
+
+    #[test]
+    fn test_list_journal_usage() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let at = |d| {
+            NaiveDate::from_ymd_opt(2025, 9, d)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+        };
+        for (day, name) in [(3, "Work"), (1, "Work"), (2, "Personal"), (5, "Work")] {
+            journal
+                .create_entry_with_timestamp(None, "Log", Some(name), at(day))
+                .unwrap();
+        }
+
+        let usage = journal.list_journal_usage().unwrap();
+        let summary: Vec<_> = usage
+            .iter()
+            .map(|u| {
+                (
+                    u.name.as_str(),
+                    u.count,
+                    u.first_entry.date_naive().day(),
+                    u.last_entry.date_naive().day(),
+                )
+            })
+            .collect();
+        assert_eq!(summary, vec![("Personal", 1, 2, 2), ("Work", 3, 1, 5)]);
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Oops #ops", None).unwrap();
+        journal.create_entry(None, "Keep", None).unwrap();
+
+        assert!(journal.delete_entry(id).unwrap());
+        assert!(!journal.delete_entry(id).unwrap());
+        assert!(journal.get_entry(id).unwrap().is_none());
+        assert_eq!(journal.list_entries().unwrap().len(), 1);
+        // Trashed entries can't be changed until they are restored
+        assert!(!journal.update_entry(id, None, "Edited").unwrap());
+        assert!(!journal.set_entry_starred(id, true).unwrap());
+        assert!(!journal.move_entry(id, "Work").unwrap());
+        assert!(journal.list_entry_revisions(id).unwrap().is_empty());
+        assert_eq!(count_tagged(&journal, "ops"), 0);
+
+        let trashed = journal.list_trashed_entries().unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert!(trashed[0].deleted_at.is_some());
+
+        assert!(journal.restore_entry(id).unwrap());
+        assert!(!journal.restore_entry(id).unwrap());
+        assert_eq!(count_tagged(&journal, "ops"), 1);
+    }
+
+    #[test]
+    fn test_empty_trash() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Oops #ops", None).unwrap();
+        journal.delete_entry(id).unwrap();
+
+        let an_hour_ago = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(journal.empty_trash(Some(an_hour_ago)).unwrap(), 0);
+        assert_eq!(journal.empty_trash(None).unwrap(), 1);
+        assert!(journal.list_trashed_entries().unwrap().is_empty());
+        assert!(!journal.restore_entry(id).unwrap());
+        assert!(journal.list_tag_usage(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_journal() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal.create_entry(None, "Old #ops", Some("Ops")).unwrap();
+        journal.create_entry(None, "Older", Some("Ops")).unwrap();
+        journal
+            .create_entry(None, "Scratch #tmp", Some("Scratch"))
+            .unwrap();
+        journal.create_entry(None, "Keep", Some("Work")).unwrap();
+
+        assert_eq!(journal.delete_journal("Ops", Some("Work")).unwrap(), 2);
+        assert_eq!(journal.list_journals().unwrap(), vec!["Scratch", "Work"]);
+        assert_eq!(count_tagged(&journal, "ops"), 1);
+
+        assert_eq!(journal.delete_journal("Scratch", None).unwrap(), 1);
+        assert_eq!(journal.list_journals().unwrap(), vec!["Work"]);
+        assert_eq!(count_tagged(&journal, "tmp"), 0);
+        assert_eq!(journal.delete_journal("Missing", None).unwrap(), 0);
+
+        let trashed = journal.create_entry(None, "Gone", Some("Old")).unwrap();
+        journal.create_entry(None, "Here", Some("Old")).unwrap();
+        journal.delete_entry(trashed).unwrap();
+        assert_eq!(journal.count_journal_entries("Old").unwrap(), (1, 1));
+        assert_eq!(journal.delete_journal("Old", None).unwrap(), 2);
+        assert_eq!(journal.count_journal_entries("Old").unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_move_journal_entries() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal.create_entry(None, "Launch", Some("Ops")).unwrap();
+        journal.create_entry(None, "Landing", Some("Ops")).unwrap();
+        journal.create_entry(None, "Standup", Some("Work")).unwrap();
+
+        let ops = EntryFilter {
+            journal: Some("Ops"),
+            ..Default::default()
+        };
+        assert_eq!(journal.move_entries(&ops, "Work").unwrap(), 2);
+        assert_eq!(journal.list_journals().unwrap(), vec!["Work"]);
+        assert_eq!(journal.move_entries(&ops, "Work").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_move_entries_by_filter() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let at = |date: &str| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+        };
+        journal
+            .create_entry_with_timestamp(None, "Old", Some("Ops"), at("2022-06-01"))
+            .unwrap();
+        let recent = journal
+            .create_entry_with_timestamp(None, "Recent", Some("Ops"), at("2023-03-01"))
+            .unwrap();
+        journal
+            .create_entry_with_timestamp(None, "Elsewhere", Some("Work"), at("2023-03-01"))
+            .unwrap();
+
+        let filter = EntryFilter {
+            journal: Some("Ops"),
+            since: NaiveDate::from_ymd_opt(2023, 1, 1),
+            ..Default::default()
+        };
+        assert_eq!(journal.move_entries(&filter, "Archive").unwrap(), 1);
+        assert_eq!(
+            journal.get_entry(recent).unwrap().unwrap().journal,
+            "Archive"
+        );
+        assert_eq!(
+            journal.list_journals().unwrap(),
+            vec!["Archive", "Ops", "Work"]
+        );
+        assert_eq!(
+            journal.last_undo().unwrap(),
+            Some(UndoAction::Move {
+                moves: vec![(recent, "Ops".to_string())]
+            })
+        );
+    }
+
+    #[test]
+    fn test_journal_info() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        assert_eq!(journal.get_journal_info("Work").unwrap(), None);
+
+        let info = JournalInfo {
+            name: "Work".to_string(),
+            description: Some("Day job".to_string()),
+            color: Some("blue".to_string()),
+            default_template: None,
+            archived: false,
+        };
+        journal.set_journal_info(&info).unwrap();
+        assert_eq!(
+            journal.get_journal_info("Work").unwrap(),
+            Some(info.clone())
+        );
+
+        let info = JournalInfo {
+            color: None,
+            ..info
+        };
+        journal.set_journal_info(&info).unwrap();
+        assert_eq!(journal.list_journal_info().unwrap(), vec![info]);
+
+        journal.create_entry(None, "Log", Some("Work")).unwrap();
+        journal.delete_journal("Work", None).unwrap();
+        assert_eq!(journal.get_journal_info("Work").unwrap(), None);
+    }
+
+    #[test]
+    fn test_attachment_paths() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Captain's log", None).unwrap();
+        assert!(
+            journal
+                .get_entry(id)
+                .unwrap()
+                .unwrap()
+                .attachment_paths()
+                .is_empty()
+        );
+
+        journal.set_entry_audio(id, "audio/log.wav").unwrap();
+        journal
+            .add_entry_images(id, &["attachments/bridge.png".to_string()])
+            .unwrap();
+        assert_eq!(
+            journal.get_entry(id).unwrap().unwrap().attachment_paths(),
+            vec!["attachments/bridge.png", "audio/log.wav"]
+        );
+    }
+
+    #[test]
+    fn test_set_entry_audio() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Captain's log", None).unwrap();
+
+        assert!(journal.set_entry_audio(id, "audio/log.wav").unwrap());
+        assert!(!journal.set_entry_audio(999, "audio/log.wav").unwrap());
+        assert_eq!(
+            journal
+                .get_entry(id)
+                .unwrap()
+                .unwrap()
+                .audio_path
+                .as_deref(),
+            Some("audio/log.wav")
+        );
+    }
+
+    #[test]
+    fn test_add_entry_images() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal.create_entry(None, "Away mission", None).unwrap();
+
+        let first = vec!["attachments/a.png".to_string()];
+        let second = vec!["attachments/b.jpg".to_string()];
+        assert!(journal.add_entry_images(id, &first).unwrap());
+        assert!(journal.add_entry_images(id, &second).unwrap());
+        assert!(!journal.add_entry_images(999, &first).unwrap());
+
+        assert_eq!(
+            journal.get_entry(id).unwrap().unwrap().image_paths,
+            vec!["attachments/a.png", "attachments/b.jpg"]
+        );
+
+        // Trashed entries still reference their attachments
+        journal.delete_entry(id).unwrap();
+        assert_eq!(
+            journal.list_attachment_references().unwrap(),
+            vec![
+                (id, "attachments/a.png".to_string()),
+                (id, "attachments/b.jpg".to_string())
+            ]
+        );
+        journal.empty_trash(None).unwrap();
+        assert!(journal.list_attachment_references().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_starred_entries() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let launch = journal.create_entry(None, "Launch", None).unwrap();
+        journal.create_entry(None, "Standup", None).unwrap();
+
+        assert!(journal.set_entry_starred(launch, true).unwrap());
+        assert!(!journal.set_entry_starred(999, true).unwrap());
+        assert!(journal.get_entry(launch).unwrap().unwrap().starred);
+
+        let filter = EntryFilter {
+            starred: true,
+            ..Default::default()
+        };
+        let starred = journal
+            .list_entries_filtered_with_order(&filter, "id", "ASC")
+            .unwrap();
+        assert_eq!(starred.len(), 1);
+        assert_eq!(starred[0].id, launch);
+
+        journal.set_entry_starred(launch, false).unwrap();
+        assert_eq!(journal.count_entries(&filter).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_exclude_archived_filter() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal
+            .create_entry(None, "Launch", Some("Apollo"))
+            .unwrap();
+        journal.create_entry(None, "Standup", Some("Work")).unwrap();
+        journal
+            .set_journal_info(&JournalInfo {
+                name: "Apollo".to_string(),
+                archived: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let journals = |exclude_archived| {
+            let filter = EntryFilter {
+                exclude_archived,
+                ..Default::default()
+            };
+            journal
+                .list_entries_filtered_with_order(&filter, "id", "ASC")
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.journal)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(journals(false), vec!["Apollo", "Work"]);
+        assert_eq!(journals(true), vec!["Work"]);
+    }
+
+    #[test]
+    fn test_draft_status_filter() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let draft = journal.create_entry(None, "Rough thoughts", None).unwrap();
+        let done = journal.create_entry(None, "Polished log", None).unwrap();
+        assert!(journal.set_entry_status(draft, STATUS_DRAFT).unwrap());
+        assert!(journal.get_entry(draft).unwrap().unwrap().is_draft());
+
+        let ids = |status| {
+            let filter = EntryFilter {
+                status,
+                ..Default::default()
+            };
+            journal
+                .list_entries_filtered_with_order(&filter, "id", "ASC")
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(Some(STATUS_DRAFT)), vec![draft]);
+        assert_eq!(ids(Some(STATUS_FINAL)), vec![done]);
+        assert_eq!(ids(None), vec![draft, done]);
+
+        // Trashed entries keep their status
+        journal.delete_entry(draft).unwrap();
+        assert!(!journal.set_entry_status(draft, STATUS_FINAL).unwrap());
+        journal.restore_entry(draft).unwrap();
+        assert!(journal.get_entry(draft).unwrap().unwrap().is_draft());
+    }
+
+    #[test]
+    fn test_extract_tags() {
+        let content = "# Heading\n\nShore leave on #Risa with #away-team.\n\
+                       See issue #12, https://example.com/page#section and #risa again.";
+        assert_eq!(extract_tags(content), vec!["risa", "away-team"]);
+    }
+
+    #[test]
+    fn test_entry_tags_follow_content() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal
+            .create_entry(None, "Warp core check #engineering", None)
+            .unwrap();
+        let other = journal.create_entry(None, "Nothing to see", None).unwrap();
+        assert_eq!(journal.get_entry_tags(id).unwrap(), vec!["engineering"]);
+
+        let filter = EntryFilter {
+            tag: Some("#Engineering"),
+            ..Default::default()
+        };
+        let tagged: Vec<i64> = journal
+            .list_entries_filtered_with_order(&filter, "timestamp", "DESC")
+            .unwrap()
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(tagged, vec![id]);
+
+        journal.update_entry(id, None, "All fine #status").unwrap();
+        assert_eq!(journal.get_entry_tags(id).unwrap(), vec!["status"]);
+        assert!(journal.get_entry_tags(other).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_entry_revisions() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal
+            .create_entry(Some("Log"), "First draft", None)
+            .unwrap();
+        assert!(journal.list_entry_revisions(id).unwrap().is_empty());
+
+        journal
+            .update_entry(id, Some("Log"), "Second draft")
+            .unwrap();
+        // Saving without changes adds no revision
+        journal
+            .update_entry(id, Some("Log"), "Second draft")
+            .unwrap();
+        journal.append_to_entry(id, "More").unwrap();
+
+        let revisions = journal.list_entry_revisions(id).unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].number, 1);
+        assert_eq!(revisions[0].content, "First draft");
+        assert_eq!(revisions[1].content, "Second draft");
+        assert_eq!(revisions[1].title.as_deref(), Some("Log"));
+
+        journal.delete_entry(id).unwrap();
+        journal.empty_trash(None).unwrap();
+        assert!(journal.list_entry_revisions(id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encrypt_entry() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        let id = journal
+            .create_entry(Some("Orders"), "First draft #secret", None)
+            .unwrap();
+        journal
+            .update_entry(id, Some("Orders"), "Rendezvous at #risa")
+            .unwrap();
+
+        let armored =
+            "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n";
+        assert!(journal.encrypt_entry(id, armored).unwrap());
+        let entry = journal.get_entry(id).unwrap().unwrap();
+        assert!(entry.is_encrypted());
+        assert_eq!(entry.title.as_deref(), Some("Orders"));
+        assert!(journal.list_entry_revisions(id).unwrap().is_empty());
+        assert!(journal.get_entry_tags(id).unwrap().is_empty());
+        assert!(journal.last_undo().unwrap().is_none());
+
+        assert!(!journal.encrypt_entry(id + 1, armored).unwrap());
+    }
+
+    #[test]
+    fn test_list_tag_usage() {
+        let journal = Journal::new(Database::new_with_path(":memory:").unwrap());
+        journal
+            .create_entry(None, "#warp #engineering", Some("Work"))
+            .unwrap();
+        journal
+            .create_entry(None, "More #engineering", Some("Work"))
+            .unwrap();
+        journal
+            .create_entry(None, "Dinner #risa", Some("Personal"))
+            .unwrap();
+
+        let usage = journal.list_tag_usage(None).unwrap();
+        let counts: Vec<(&str, usize)> = usage.iter().map(|u| (u.name.as_str(), u.count)).collect();
+        assert_eq!(counts, vec![("engineering", 2), ("risa", 1), ("warp", 1)]);
+
+        let work = journal.list_tag_usage(Some("Work")).unwrap();
+        assert!(work.iter().all(|u| u.name != "risa"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Boolean search queries for `cl search`, compiled to a SQL predicate.
+//!
+//! Supported syntax:
+//! - words and `"quoted phrases"` match title or content (case-insensitive substring)
+//! - terms next to each other must all match (implicit `AND`)
+//! - `AND`, `OR` and `NOT` (uppercase), with `NOT` > `AND` > `OR` precedence
+//! - parentheses for grouping
+//!
+//! e.g. `"project x" AND deadline NOT cancelled`
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, PartialEq)]
+pub enum SearchQuery {
+    Term(String),
+    Not(Box<SearchQuery>),
+    And(Vec<SearchQuery>),
+    Or(Vec<SearchQuery>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Term(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(anyhow!("Invalid search query: the query is empty"));
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(anyhow!("Invalid search query: unexpected {:?}", token));
+        }
+        Ok(query)
+    }
+
+    /// SQL predicate over the `title` and `content` columns, with one `?` per pushed parameter
+    pub fn to_sql(&self, params: &mut Vec<String>) -> String {
+        match self {
+            SearchQuery::Term(term) => {
+                let pattern = format!("%{}%", term);
+                params.push(pattern.clone());
+                params.push(pattern);
+                // COALESCE so that NOT on an untitled entry is true rather than NULL
+                "(content LIKE ? OR COALESCE(title, '') LIKE ?)".to_string()
+            }
+            SearchQuery::Not(inner) => format!("NOT {}", inner.to_sql(params)),
+            SearchQuery::And(parts) => join_sql(parts, " AND ", params),
+            SearchQuery::Or(parts) => join_sql(parts, " OR ", params),
+        }
+    }
+
+    /// Terms that a matching entry may contain (everything not under a `NOT`),
+    /// used to highlight search results
+    pub fn positive_terms(&self) -> Vec<&str> {
+        match self {
+            SearchQuery::Term(term) => vec![term.as_str()],
+            SearchQuery::Not(_) => Vec::new(),
+            SearchQuery::And(parts) | SearchQuery::Or(parts) => {
+                parts.iter().flat_map(SearchQuery::positive_terms).collect()
+            }
+        }
+    }
+}
+
+fn join_sql(parts: &[SearchQuery], operator: &str, params: &mut Vec<String>) -> String {
+    let parts: Vec<String> = parts.iter().map(|part| part.to_sql(params)).collect();
+    format!("({})", parts.join(operator))
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                if !input.matches('"').count().is_multiple_of(2) {
+                    return Err(anyhow!("Invalid search query: unterminated quote"));
+                }
+                if !phrase.is_empty() {
+                    tokens.push(Token::Term(phrase));
+                }
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(word),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<SearchQuery> {
+        let mut parts = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            parts.push(self.parse_and()?);
+        }
+        Ok(flatten(parts, SearchQuery::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<SearchQuery> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    parts.push(self.parse_unary()?);
+                }
+                // Implicit AND between adjacent terms
+                Some(Token::Term(_)) | Some(Token::Not) | Some(Token::Open) => {
+                    parts.push(self.parse_unary()?);
+                }
+                _ => break,
+            }
+        }
+        Ok(flatten(parts, SearchQuery::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<SearchQuery> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        match token {
+            Some(Token::Not) => Ok(SearchQuery::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Term(term)) => Ok(SearchQuery::Term(term.clone())),
+            Some(Token::Open) => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(anyhow!("Invalid search query: missing ')'"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(token) => Err(anyhow!("Invalid search query: unexpected {:?}", token)),
+            None => Err(anyhow!("Invalid search query: expected a term at the end")),
+        }
+    }
+}
+
+fn flatten(
+    mut parts: Vec<SearchQuery>,
+    combine: fn(Vec<SearchQuery>) -> SearchQuery,
+) -> SearchQuery {
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        combine(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(t: &str) -> SearchQuery {
+        SearchQuery::Term(t.to_string())
+    }
+
+    #[test]
+    fn test_parse_precedence_and_phrases() {
+        let query =
+            SearchQuery::parse(r#""project x" AND deadline NOT cancelled OR urgent"#).unwrap();
+        assert_eq!(
+            query,
+            SearchQuery::Or(vec![
+                SearchQuery::And(vec![
+                    term("project x"),
+                    term("deadline"),
+                    SearchQuery::Not(Box::new(term("cancelled"))),
+                ]),
+                term("urgent"),
+            ])
+        );
+        assert_eq!(
+            query.positive_terms(),
+            vec!["project x", "deadline", "urgent"]
+        );
+    }
+
+    #[test]
+    fn test_parse_groups_and_lowercase_keywords() {
+        let query = SearchQuery::parse("warp (core OR drive) or").unwrap();
+        assert_eq!(
+            query,
+            SearchQuery::And(vec![
+                term("warp"),
+                SearchQuery::Or(vec![term("core"), term("drive")]),
+                term("or"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(SearchQuery::parse("").is_err());
+        assert!(SearchQuery::parse("warp AND").is_err());
+        assert!(SearchQuery::parse("(warp").is_err());
+        assert!(SearchQuery::parse("\"warp core").is_err());
+        assert!(SearchQuery::parse("warp)").is_err());
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let mut params = Vec::new();
+        let sql = SearchQuery::parse("warp NOT core")
+            .unwrap()
+            .to_sql(&mut params);
+        assert_eq!(
+            sql,
+            "((content LIKE ? OR COALESCE(title, '') LIKE ?) AND NOT (content LIKE ? OR COALESCE(title, '') LIKE ?))"
+        );
+        assert_eq!(params, vec!["%warp%", "%warp%", "%core%", "%core%"]);
+    }
+}
@@ -0,0 +1,222 @@
+//! The undo journal behind `cl undo`.
+//!
+//! Deleting, editing and moving entries record how to reverse them in the
+//! single-row `undo_log` table, replacing whatever was recorded before, so only
+//! the last of these operations can be undone.
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use super::{Entry, Journal, extract_tags};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum UndoAction {
+    /// Entries moved to the trash
+    Delete { ids: Vec<i64> },
+    /// An entry as it was before being edited
+    Edit {
+        id: i64,
+        title: Option<String>,
+        content: String,
+        journal: String,
+        timestamp: DateTime<Utc>,
+    },
+    /// Entries moved to another journal, with the journal each came from
+    Move { moves: Vec<(i64, String)> },
+}
+
+impl UndoAction {
+    /// What undoing reverses, e.g. "the delete of entry 4"
+    pub fn describe(&self) -> String {
+        let entries = |ids: &[i64]| match ids {
+            [id] => format!("entry {}", id),
+            ids => format!("{} entries", ids.len()),
+        };
+        match self {
+            UndoAction::Delete { ids } => format!("the delete of {}", entries(ids)),
+            UndoAction::Edit { id, .. } => format!("the edit of entry {}", id),
+            UndoAction::Move { moves } => {
+                let ids: Vec<i64> = moves.iter().map(|(id, _)| *id).collect();
+                format!("the move of {}", entries(&ids))
+            }
+        }
+    }
+}
+
+impl Journal {
+    /// Remember how to reverse the operation about to be made
+    pub(super) fn record_undo(&self, action: &UndoAction) -> Result<()> {
+        self.db.connection().execute(
+            "INSERT OR REPLACE INTO undo_log (id, action, recorded_at) VALUES (1, ?1, ?2)",
+            params![serde_json::to_string(action)?, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    /// Record an edit of `entry`, unless the new values leave it unchanged
+    pub(super) fn record_edit(
+        &self,
+        entry: &Entry,
+        title: Option<&str>,
+        content: &str,
+        journal: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<()> {
+        if entry.title.as_deref() == title
+            && entry.content == content
+            && entry.journal == journal
+            && entry.timestamp == timestamp
+        {
+            return Ok(());
+        }
+        self.record_undo(&UndoAction::Edit {
+            id: entry.id,
+            title: entry.title.clone(),
+            content: entry.content.clone(),
+            journal: entry.journal.clone(),
+            timestamp: entry.timestamp,
+        })
+    }
+
+    /// The operation `undo_last` would reverse, if any
+    pub fn last_undo(&self) -> Result<Option<UndoAction>> {
+        let action: Option<String> = self
+            .db
+            .connection()
+            .query_row("SELECT action FROM undo_log WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        action
+            .map(|json| serde_json::from_str(&json).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Reverse the last recorded operation and forget it. Returns the operation
+    /// undone, or None if there was nothing to undo.
+    pub fn undo_last(&self) -> Result<Option<UndoAction>> {
+        let Some(action) = self.last_undo()? else {
+            return Ok(None);
+        };
+        let conn = self.db.connection();
+        let tx = conn.unchecked_transaction()?;
+
+        match &action {
+            UndoAction::Delete { ids } => {
+                for id in ids {
+                    let restored = tx.execute(
+                        "UPDATE entries SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+                        [id],
+                    )?;
+                    if restored == 0 {
+                        return Err(anyhow!(
+                            "Entry {} is no longer in the trash and cannot be restored",
+                            id
+                        ));
+                    }
+                }
+            }
+            UndoAction::Edit {
+                id,
+                title,
+                content,
+                journal,
+                timestamp,
+            } => {
+                self.save_revision(*id, title.as_deref(), content)?;
+                let reverted = tx.execute(
+                    "UPDATE entries SET title = ?1, content = ?2, journal = ?3, timestamp = ?4, updated_at = ?5
+                     WHERE id = ?6",
+                    params![title, content, journal, timestamp, Utc::now(), id],
+                )?;
+                if reverted == 0 {
+                    return Err(anyhow!("Entry {} no longer exists", id));
+                }
+                self.set_entry_tags(*id, &extract_tags(content))?;
+            }
+            UndoAction::Move { moves } => {
+                for (id, journal) in moves {
+                    tx.execute(
+                        "UPDATE entries SET journal = ?1, updated_at = ?2 WHERE id = ?3",
+                        params![journal, Utc::now(), id],
+                    )?;
+                }
+            }
+        }
+
+        tx.execute("DELETE FROM undo_log", [])?;
+        tx.commit()?;
+        Ok(Some(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::journal::EntryFilter;
+
+    fn journal() -> Journal {
+        Journal::new(Database::new_with_path(":memory:").unwrap())
+    }
+
+    #[test]
+    fn test_undo_delete() {
+        let journal = journal();
+        let id = journal.create_entry(None, "Oops", None).unwrap();
+        journal.delete_entry(id).unwrap();
+
+        assert_eq!(
+            journal.undo_last().unwrap(),
+            Some(UndoAction::Delete { ids: vec![id] })
+        );
+        assert!(journal.get_entry(id).unwrap().is_some());
+        assert_eq!(journal.undo_last().unwrap(), None);
+    }
+
+    #[test]
+    fn test_undo_edit_and_move() {
+        let journal = journal();
+        let id = journal
+            .create_entry(Some("Log"), "Before #old", Some("Ops"))
+            .unwrap();
+        journal.update_entry(id, None, "After #new").unwrap();
+
+        assert_eq!(
+            journal.last_undo().unwrap().unwrap().describe(),
+            format!("the edit of entry {}", id)
+        );
+        journal.undo_last().unwrap();
+        let entry = journal.get_entry(id).unwrap().unwrap();
+        assert_eq!(entry.title.as_deref(), Some("Log"));
+        assert_eq!(entry.content, "Before #old");
+        assert_eq!(journal.get_entry_tags(id).unwrap(), vec!["old"]);
+
+        journal.create_entry(None, "Second", Some("Ops")).unwrap();
+        let ops = EntryFilter {
+            journal: Some("Ops"),
+            ..Default::default()
+        };
+        journal.move_entries(&ops, "Work").unwrap();
+        assert_eq!(
+            journal.last_undo().unwrap().unwrap().describe(),
+            "the move of 2 entries"
+        );
+        journal.undo_last().unwrap();
+        assert_eq!(journal.list_journals().unwrap(), vec!["Ops"]);
+    }
+
+    #[test]
+    fn test_only_the_last_operation_is_kept() {
+        let journal = journal();
+        let id = journal.create_entry(None, "Entry", Some("Ops")).unwrap();
+        journal.move_entry(id, "Work").unwrap();
+        journal.delete_entry(id).unwrap();
+
+        journal.undo_last().unwrap();
+        assert_eq!(journal.get_entry(id).unwrap().unwrap().journal, "Work");
+        assert_eq!(journal.undo_last().unwrap(), None);
+    }
+}
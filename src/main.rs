@@ -3,14 +3,18 @@ use clap::CommandFactory;
 use clap::Parser;
 use std::path::PathBuf;
 
+mod attachments;
 mod audio;
 mod cli;
 mod config;
+mod crypto;
 mod database;
 mod export;
 mod import;
 mod journal;
 mod server;
+#[cfg(test)]
+mod test_support;
 
 use cli::Commands;
 use config::Config;
@@ -32,23 +36,105 @@ struct Cli {
     /// Override database file location
     #[arg(short = 'd', long = "database", global = true)]
     database_file: Option<String>,
+
+    /// Use the config and database of this profile (see `cl profile`)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Use this config file instead of the one in the config directory (created if missing)
+    #[arg(long = "config", global = true, conflicts_with = "profile")]
+    config_file: Option<PathBuf>,
+
+    /// Answer yes to all confirmation prompts (required for destructive commands in scripts)
+    #[arg(short = 'y', long, global = true)]
+    yes: bool,
+
+    /// Open the database read-only and reject commands that modify it
+    #[arg(long, global = true)]
+    readonly: bool,
+
+    /// Launch the editor without first checking that it exists on PATH
+    #[arg(long, global = true)]
+    no_editor_check: bool,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    cli::set_assume_yes(cli.yes);
+    cli::set_editor_check(!cli.no_editor_check);
+    if let Some(config_file) = &cli.config_file {
+        config::set_config_path(config_file.clone());
+    }
+    if let Some(profile) = &cli.profile {
+        config::set_profile(profile)?;
+        // Loading a missing profile would create it; only `cl profile` may do that
+        if !Config::profile_exists(profile)?
+            && !matches!(cli.command, Some(Commands::Profile { .. }))
+        {
+            return Err(anyhow::anyhow!(
+                "Profile '{}' does not exist; create it with `cl profile create {}`",
+                profile,
+                profile
+            ));
+        }
+    }
 
-    let config = Config::load()?;
+    // `doctor` reports a broken config file instead of failing on it
+    let config = match Config::load() {
+        Err(e) if matches!(cli.command, Some(Commands::Doctor)) => {
+            eprintln!("Warning: {:#}; using the default configuration", e);
+            Config::default()
+        }
+        config => config?,
+    };
     let db_path = if let Some(db_file) = &cli.database_file {
         PathBuf::from(db_file)
     } else {
         config.get_database_path()?
     };
 
+    if cli.readonly
+        && let Some(command) = &cli.command
+        && command.is_mutating()
+    {
+        return Err(anyhow::anyhow!(
+            "This command modifies the journal and is not available in --readonly mode"
+        ));
+    }
+
+    if database::is_in_memory(&db_path)
+        && let Some(command) = &cli.command
+        && command.is_mutating()
+    {
+        eprintln!("Note: the database is in memory; changes are discarded when cl exits");
+    }
+
+    if let Some(Commands::Lock) = &cli.command {
+        return cli::handle_lock_command(&db_path);
+    }
+
+    if database::encryption::is_encrypted(&db_path) {
+        let key = cli::database_passphrase(&config, &db_path)?;
+        database::encryption::set_key(key);
+    }
+
+    if let Some(Commands::Restore { backup }) = &cli.command {
+        return cli::handle_restore_command(&db_path, std::path::Path::new(backup));
+    }
+
     if let Some(Commands::Serve { port }) = &cli.command {
-        return server::run(&db_path, *port);
+        return server::run(&db_path, &config.database, *port, cli.readonly);
     }
 
-    let db = Database::new_with_path(&db_path)?;
+    let db = if cli.readonly {
+        let db = Database::new_readonly(&db_path)?;
+        db.ensure_migrated()?;
+        db
+    } else if database::is_in_memory(&db_path) {
+        Database::new_in_memory()?
+    } else {
+        Database::new_with_config(&db_path, &config.database)?
+    };
     let journal = Journal::new(db);
 
     if config.display.colors_enabled {
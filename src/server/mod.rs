@@ -11,6 +11,7 @@ use pulldown_cmark::{Options, Parser as MdParser, html as md_html};
 use serde::Deserialize;
 use std::sync::{Arc, Mutex};
 
+use crate::config::DatabaseConfig;
 use crate::database::Database;
 use crate::journal::{Entry, Journal};
 
@@ -26,8 +27,19 @@ struct AppState {
     journal: Arc<Mutex<Journal>>,
 }
 
-pub fn run(db_path: &std::path::Path, port: u16) -> Result<()> {
-    let db = Database::new_with_path(db_path)?;
+pub fn run(
+    db_path: &std::path::Path,
+    settings: &DatabaseConfig,
+    port: u16,
+    readonly: bool,
+) -> Result<()> {
+    let db = if readonly {
+        let db = Database::new_readonly(db_path)?;
+        db.ensure_migrated()?;
+        db
+    } else {
+        Database::new_with_config(db_path, settings)?
+    };
     let journal = Journal::new(db);
     let state = AppState {
         journal: Arc::new(Mutex::new(journal)),
@@ -178,14 +190,14 @@ async fn update_handler(
     }
 }
 
-fn escape_html(s: &str) -> String {
+pub(crate) fn escape_html(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
 }
 
-fn to_html(markdown: &str) -> String {
+pub(crate) fn to_html(markdown: &str) -> String {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_STRIKETHROUGH);
     opts.insert(Options::ENABLE_TABLES);
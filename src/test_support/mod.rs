@@ -0,0 +1,50 @@
+//! Helpers shared by the unit tests.
+use rand::distr::SampleString;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp directory, removed when dropped (so also
+/// when a test fails)
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create `cl_<name>_test_<random>`
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "cl_{}_test_{}",
+            name,
+            rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 8)
+        ));
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // A test may have made it read-only
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&self.path, fs::Permissions::from_mode(0o755));
+        }
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}